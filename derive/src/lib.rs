@@ -0,0 +1,270 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Fields, Lit, Meta,
+    NestedMeta, Type,
+};
+
+/// Derives `Vertex` for a `#[repr(C)]` struct with named fields. <br>
+/// Fields are mapped in declaration order, the byte offset of every field is read from the struct layout.
+/// Every field type must implement `VertexFieldKindConverter`. Tuple fields are rejected as their layout is
+/// unspecified, use arrays instead.
+///
+/// Fields can be configured with `#[vertex(name = "Position", normalized)]`:
+/// * `name` - the shader attribute the field is bound to, defaults to the field name
+/// * `normalized` - integer values are normalized to `0..1` or `-1..1` when read by the shader
+///
+/// The path of the engine crate can be overridden with `#[vertex(crate = "...")]` on the struct.
+#[proc_macro_derive(Vertex, attributes(vertex))]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+
+    if !is_repr_c(&input.attrs) {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "#[derive(Vertex)] requires the struct to be #[repr(C)]",
+        ));
+    }
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "#[derive(Vertex)] does not support generic structs",
+        ));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "#[derive(Vertex)] requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "#[derive(Vertex)] can only be used on structs",
+            ))
+        }
+    };
+
+    let krate = crate_path(&input.attrs, "vertex")?;
+    let module = quote!(#krate::renderer::render_target::vertex_array);
+
+    let mut definitions = Vec::new();
+    for field in fields {
+        let field_ident = field.ident.as_ref().unwrap();
+        let attribute = FieldAttribute::parse(&field.attrs)?;
+
+        let name = attribute.name.unwrap_or_else(|| field_ident.to_string());
+        let ty = &field.ty;
+
+        if let Some(tuple) = find_tuple(ty) {
+            return Err(syn::Error::new_spanned(
+                tuple,
+                "#[derive(Vertex)] does not support tuple fields as their layout is unspecified, use an array instead",
+            ));
+        }
+
+        let normalized = match attribute.normalized {
+            true => quote!(.normalized()),
+            false => quote!(),
+        };
+
+        definitions.push(quote! {
+            #module::VertexField::from_type::<#ty>(#name)
+                .with_offset(#krate::__private::offset_of!(#ident, #field_ident))
+                #normalized
+        });
+    }
+
+    Ok(quote! {
+        // The offsets are read from the `#[repr(C)]` layout of the struct
+        unsafe impl<'a> #module::Vertex<'a> for #ident {
+            fn get_definition() -> #module::VertexDefiner<'a> {
+                let fields = vec![#(#definitions),*];
+
+                #module::VertexDefiner::new(fields).with_stride(::std::mem::size_of::<#ident>())
+            }
+        }
+    })
+}
+
+/// Reads the engine crate path from `#[<helper>(crate = "...")]`, defaults to `::engine`
+fn crate_path(attrs: &[Attribute], helper: &str) -> syn::Result<TokenStream2> {
+    let mut path = quote!(::engine);
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident(helper)) {
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in list.nested.iter() {
+                match nested {
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("crate") => {
+                        path = match &nv.lit {
+                            Lit::Str(s) => s.parse::<syn::Path>()?.into_token_stream(),
+                            lit => return Err(syn::Error::new(lit.span(), "expected a string")),
+                        };
+                    }
+                    nested => {
+                        return Err(syn::Error::new(
+                            nested.span(),
+                            format!("unknown {} attribute, expected `crate = \"...\"`", helper),
+                        ))
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(path)
+}
+
+/// Derives `BlockLayout` for a struct with named fields. <br>
+/// Fields are written in declaration order with the alignment and padding of the encoder's layout,
+/// every field type must implement `BlockLayout`.
+///
+/// The path of the engine crate can be overridden with `#[block(crate = "...")]` on the struct.
+#[proc_macro_derive(BlockLayout, attributes(block))]
+pub fn derive_block_layout(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand_block_layout(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_block_layout(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "#[derive(BlockLayout)] does not support generic structs",
+        ));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "#[derive(BlockLayout)] requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "#[derive(BlockLayout)] can only be used on structs",
+            ))
+        }
+    };
+
+    let krate = crate_path(&input.attrs, "block")?;
+    let module = quote!(#krate::renderer::buffer::layout);
+
+    let types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+    let idents: Vec<_> = fields
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap())
+        .collect();
+
+    Ok(quote! {
+        impl #module::BlockLayout for #ident {
+            fn alignment(layout: #module::Layout) -> usize {
+                let members = [#((<#types as #module::BlockLayout>::alignment(layout), <#types as #module::BlockLayout>::size(layout))),*];
+                layout.struct_layout(&members).0
+            }
+
+            fn size(layout: #module::Layout) -> usize {
+                let members = [#((<#types as #module::BlockLayout>::alignment(layout), <#types as #module::BlockLayout>::size(layout))),*];
+                layout.struct_layout(&members).1
+            }
+
+            fn encode(&self, encoder: &mut #module::LayoutEncoder) {
+                #(encoder.write(&self.#idents);)*
+            }
+        }
+    })
+}
+
+/// Finds a tuple type in a field type, looking through arrays
+fn find_tuple(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Tuple(tuple) if !tuple.elems.is_empty() => Some(ty),
+        Type::Array(array) => find_tuple(&array.elem),
+        Type::Paren(paren) => find_tuple(&paren.elem),
+        Type::Group(group) => find_tuple(&group.elem),
+        _ => None,
+    }
+}
+
+/// Returns true if the attributes contain `#[repr(C)]`
+fn is_repr_c(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("repr"))
+        .filter_map(|attr| attr.parse_meta().ok())
+        .any(|meta| match meta {
+            Meta::List(list) => list.nested.iter().any(|nested| match nested {
+                NestedMeta::Meta(Meta::Path(path)) => path.is_ident("C"),
+                _ => false,
+            }),
+            _ => false,
+        })
+}
+
+#[derive(Default)]
+struct FieldAttribute {
+    name: Option<String>,
+    normalized: bool,
+}
+
+impl FieldAttribute {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut attribute = FieldAttribute::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("vertex")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta => return Err(syn::Error::new(meta.span(), "expected #[vertex(...)]")),
+            };
+
+            for nested in list.nested.iter() {
+                match nested {
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("name") => {
+                        match &nv.lit {
+                            Lit::Str(s) => attribute.name = Some(s.value()),
+                            lit => return Err(syn::Error::new(lit.span(), "expected a string")),
+                        }
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("normalized") => {
+                        attribute.normalized = true;
+                    }
+                    nested => {
+                        return Err(syn::Error::new(
+                            nested.span(),
+                            "unknown vertex attribute, expected `name = \"...\"` or `normalized`",
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(attribute)
+    }
+}
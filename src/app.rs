@@ -1,98 +1,304 @@
-use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use glutin::platform::run_return::EventLoopExtRunReturn;
+use glutin::{event::VirtualKeyCode, platform::run_return::EventLoopExtRunReturn};
+use thiserror::Error;
 
-use crate::{assets::AssetManager, context::Context, engine::Engine, system::System};
+use crate::{
+    assets::{Asset, AssetKind, AssetManager},
+    context::Context,
+    engine::Engine,
+    input::{InputEvent, InputMap, InputSource},
+    system::{Schedule, Stage, SystemDescriptor},
+    time::{FrameRate, Time},
+};
 
 use self::builder::AppBuilder;
 
 mod builder;
 
-pub struct App<'a, T> where T: System {
+#[derive(Debug, Error)]
+pub enum AppError {
+    /// The OpenGL context could not be created, such as when no headless backend is available
+    #[error("failed to create context: {0}")]
+    Context(String),
+    #[error("failed to order systems: {0}")]
+    Schedule(String),
+}
+
+pub struct App<'a> {
     name: &'a str,
-    system: Arc<Mutex<T>>,
+    /// Moved into the schedule when running
+    systems: Vec<SystemDescriptor>,
+    schedule: Option<Schedule>,
     size: (u32, u32),
+    headless: bool,
+    frames: Option<u32>,
+    screenshot_key: Option<VirtualKeyCode>,
+    input_sources: Vec<Box<dyn InputSource>>,
+    input_map: Option<&'a str>,
+    frame: u64,
+    frame_rate: FrameRate,
+    /// Moved into the context on awake
+    time: Option<Time>,
 
     context: Option<Context>
 }
 
-impl<'a, T> App<'a, T> where T: System {
-    pub fn builder() -> AppBuilder<'a, T> {
+impl<'a> App<'a> {
+    pub fn builder() -> AppBuilder<'a> {
         AppBuilder::default()
     }
 
-    pub fn run(mut self) {
+    pub fn run(self) {
+        self.try_run().expect("Failed to run application");
+    }
+
+    /// Runs the application, returning an error if the OpenGL context could not be created
+    /// or the systems could not be ordered
+    pub fn try_run(mut self) -> Result<(), AppError> {
+        self.schedule = Some(Schedule::new(std::mem::take(&mut self.systems)).map_err(AppError::Schedule)?);
+
         let mut asset_manager = AssetManager::new();
         asset_manager.awake_hotreload("./assets".into());
 
-        let (engine, mut event_loop) = Engine::new(self.name.to_string(), self.size.0, self.size.1, asset_manager.clone());
-        let context = Context::new(engine, asset_manager);
-        self.context = Some(context);
+        if self.headless {
+            self.run_headless(asset_manager)
+        } else {
+            self.run_windowed(asset_manager);
+            Ok(())
+        }
+    }
 
-        // Awake engine
-        self.context.as_mut().unwrap().engine_mut().awake();
+    /// Runs the application offscreen for a fixed number of frames
+    fn run_headless(&mut self, asset_manager: AssetManager) -> Result<(), AppError> {
+        let engine = Engine::new_headless(self.size.0, self.size.1, asset_manager.clone()).map_err(AppError::Context)?;
+        self.awake(engine, asset_manager);
+
+        // Headless frames advance by a constant step so offscreen renders are reproducible
+        let delta = self.frame_rate.frame_time().unwrap_or_else(|| self.context.as_ref().unwrap().time().fixed_delta());
+
+        let frames = self.frames.unwrap_or(1);
+        trace!("Running headless application for {} frames", frames);
+        for _ in 0..frames {
+            self.frame(delta);
+        }
+
+        Ok(())
+    }
 
-        // Awake system
-        self.system.lock().unwrap().awake(self.context.as_mut().unwrap());
+    fn run_windowed(mut self, asset_manager: AssetManager) {
+        let (engine, mut event_loop) = Engine::new(
+            self.name.to_string(),
+            self.size.0,
+            self.size.1,
+            self.frame_rate.is_vsync(),
+            asset_manager.clone(),
+        );
+        self.awake(engine, asset_manager);
 
         // Start event loop
-        let start_time = std::time::Instant::now();
+        let mut frame_count = 0;
+        let frame_time = self.frame_rate.frame_time();
+        let mut last_frame = Instant::now();
+        let mut next_frame = last_frame;
         event_loop.run_return(move |event, _, control_flow| {
-            use glutin::event::{Event, WindowEvent};
+            use glutin::event::{ElementState, Event, WindowEvent};
             use glutin::event_loop::ControlFlow;
 
             *control_flow = ControlFlow::Wait;
             match event {
                 Event::LoopDestroyed => return,
-                Event::WindowEvent { event, .. } => match event {
-                    WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
-                    WindowEvent::Resized(e) => {
-                        unsafe {
-                            gl::Viewport(
-                                0,
-                                0,
-                                e.width as gl::types::GLint,
-                                e.height as gl::types::GLint,
-                            );
-                        };
+                Event::DeviceEvent { event, .. } => {
+                    if let Some(event) = InputEvent::from_device_event(&event) {
+                        self.context.as_mut().unwrap().input_mut().handle_event(&event);
+                    }
+                }
+                Event::WindowEvent { event, .. } => {
+                    if let Some(event) = InputEvent::from_window_event(&event) {
+                        self.context.as_mut().unwrap().input_mut().handle_event(&event);
                     }
-                    _ => (),
-                },
-                Event::RedrawRequested(_) => {
-                    let renderer = self.context.as_mut().unwrap().engine_mut().renderer_mut();
-                    
-                    // Clear screen
-                    renderer.clear();
-
-                    #[cfg(debug_assertions)]
-                    renderer.update_editor();
 
-                    self.system.lock().unwrap().update(self.context.as_mut().unwrap());
+                    match event {
+                        WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
+                        WindowEvent::Resized(e) => {
+                            let renderer = self.context.as_mut().unwrap().engine_mut().renderer_mut();
+                            renderer.resize(e.width, e.height);
+                        }
+                        WindowEvent::KeyboardInput { input, .. }
+                            if input.state == ElementState::Pressed
+                                && input.virtual_keycode.is_some()
+                                && input.virtual_keycode == self.screenshot_key =>
+                        {
+                            self.screenshot();
+                        }
+                        _ => (),
+                    }
+                }
+                Event::MainEventsCleared => {
+                    // Only request a frame once the previous one is due, waiting in between without spinning
+                    let now = Instant::now();
+                    if now >= next_frame {
+                        if let Some(frame_time) = frame_time {
+                            next_frame = std::cmp::max(next_frame + frame_time, now);
+                        }
 
-                    let renderer = self.context.as_mut().unwrap().engine_mut().renderer_mut();
-                    renderer.swap_buffers();
+                        let renderer = self.context.as_mut().unwrap().engine_mut().renderer_mut();
+                        if let Some(window) = renderer.window() {
+                            window.window().request_redraw();
+                        }
+                    }
+                }
+                Event::RedrawRequested(_) => {
+                    let now = Instant::now();
+                    self.frame(now - last_frame);
+                    last_frame = now;
 
-                    self.system.lock().unwrap().late_update(self.context.as_mut().unwrap());
+                    // Exit once the requested amount of frames has been rendered
+                    frame_count += 1;
+                    if matches!(self.frames, Some(frames) if frame_count >= frames) {
+                        *control_flow = ControlFlow::Exit;
+                    }
                 }
                 _ => (),
             }
-            match *control_flow {
-                ControlFlow::Exit => (),
-                _ => {
-                    let renderer = self.context.as_mut().unwrap().engine_mut().renderer_mut();
-                    renderer.window().window().request_redraw();
-
-                    let elapsed_time = std::time::Instant::now().duration_since(start_time);
-                    let elapsed_time = elapsed_time.as_millis() as u64;
-
-                    let wait_millis = match 1000 / 144 >= elapsed_time {
-                        true => 1000 / 144 - elapsed_time,
-                        false => 0,
+            if *control_flow != ControlFlow::Exit {
+                *control_flow = match frame_time {
+                    Some(_) => ControlFlow::WaitUntil(next_frame),
+                    // Vsync blocks in swap_buffers, unlimited renders as soon as events are handled
+                    None => ControlFlow::Poll,
+                };
+            }
+        });
+    }
+
+    /// Creates the context and awakes both the engine and the system
+    fn awake(&mut self, engine: Engine, asset_manager: AssetManager) {
+        let time = self.time.take().unwrap_or_default();
+        let context = Context::new(engine, asset_manager, time);
+        self.context = Some(context);
+
+        if let Some(file) = self.input_map {
+            self.load_input_map(file);
+        }
+
+        // Awake engine
+        self.context.as_mut().unwrap().engine_mut().awake();
+
+        // Awake systems
+        let context = self.context.as_mut().unwrap();
+        let schedule = self.schedule.as_mut().unwrap();
+        schedule.awake(context);
+        schedule.run(Stage::Startup, context);
+    }
+
+    /// Loads the input map from the assets directory and registers it for hot-reload
+    fn load_input_map(&mut self, file: &str) {
+        let path = std::path::Path::new("./assets").join(file);
+        let context = self.context.as_mut().unwrap();
+
+        match InputMap::load(path.clone()) {
+            Ok(map) => *context.input_map_mut() = map,
+            Err(e) => {
+                warn!("Failed to load input map: {}", e);
+                return;
+            }
+        }
+
+        match Asset::new(file.to_string(), path.clone(), AssetKind::Config, file, 0) {
+            Ok(asset) => {
+                let asset_manager = context.asset_manager_mut();
+                asset_manager.create_asset(asset);
+                asset_manager.register_for_hotreload(path);
+            }
+            Err(e) => warn!("Failed to register input map for hot-reload: {}", e),
+        }
+    }
+
+    /// Applies assets reloaded since the last frame
+    #[cfg(debug_assertions)]
+    fn reload_assets(&mut self) {
+        let context = self.context.as_mut().unwrap();
+        let reloaded: Vec<_> = match context.asset_manager().channel() {
+            Some(channel) => channel.try_iter().collect(),
+            None => return,
+        };
+
+        for (id, kind) in reloaded {
+            match kind {
+                AssetKind::Config => {
+                    if context.input_map().path().map(AssetManager::key).as_ref() != Some(&id) {
+                        continue;
+                    }
+
+                    let raw = match context.asset_manager_mut().asset(&id) {
+                        Some(asset) => asset.raw.clone(),
+                        None => continue,
                     };
-                    let new_inst = start_time + std::time::Duration::from_millis(wait_millis);
-                    *control_flow = ControlFlow::WaitUntil(new_inst);
+                    match context.input_map_mut().reload(&raw) {
+                        Ok(_) => info!("Reloaded input map {}", id),
+                        Err(e) => warn!("Failed to reload input map {}, keeping previous bindings: {}", id, e),
+                    }
                 }
+                _ => context.engine_mut().renderer_mut().reload_asset(&id, kind),
             }
-        });
+        }
+    }
+
+    /// Saves a screenshot of the next frame to the screenshots directory
+    fn screenshot(&mut self) {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let path = format!("./screenshots/{}-{}.png", self.name, timestamp);
+
+        let renderer = self.context.as_mut().unwrap().engine_mut().renderer_mut();
+        renderer.request_screenshot(path.into());
+    }
+
+    /// Renders a single frame, `delta` is the time since the previous frame
+    fn frame(&mut self, delta: Duration) {
+        let context = self.context.as_mut().unwrap();
+        for source in self.input_sources.iter_mut() {
+            for event in source.poll(self.frame) {
+                context.input_mut().handle_event(&event);
+            }
+        }
+
+        #[cfg(debug_assertions)]
+        self.reload_assets();
+
+        let context = self.context.as_mut().unwrap();
+        let schedule = self.schedule.as_mut().unwrap();
+        context.time_mut().advance(delta);
+
+        // Clear screen
+        context.engine_mut().renderer_mut().clear();
+
+        schedule.run(Stage::PreUpdate, context);
+        for _ in 0..context.time_mut().fixed_steps() {
+            schedule.fixed_update(context);
+        }
+        schedule.run(Stage::Update, context);
+        schedule.run(Stage::PostUpdate, context);
+        context.draw_world();
+        schedule.run(Stage::Render, context);
+
+        context.engine_mut().renderer_mut().swap_buffers();
+
+        schedule.run(Stage::LateUpdate, context);
+        schedule.late_update(context);
+
+        if let Some((grab, visible)) = context.input_mut().take_cursor_changes() {
+            if let Some(window) = context.engine().renderer().window() {
+                if let Err(e) = window.window().set_cursor_grab(grab) {
+                    warn!("Failed to grab cursor: {}", e);
+                }
+                window.window().set_cursor_visible(visible);
+            }
+        }
+
+        context.input_mut().end_frame();
+        self.frame += 1;
     }
 }
\ No newline at end of file
@@ -1,14 +1,23 @@
-use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use crate::{App, system::System};
+use glutin::event::VirtualKeyCode;
 
-pub struct AppBuilder<'a, T> where T: System {
+use crate::{App, input::InputSource, system::SystemDescriptor, time::{FrameRate, Time}};
+
+pub struct AppBuilder<'a> {
     name: Option<&'a str>,
-    system: Option<T>,
+    systems: Vec<SystemDescriptor>,
     size: (u32, u32), // width & height
+    headless: bool,
+    frames: Option<u32>,
+    screenshot_key: Option<VirtualKeyCode>,
+    input_sources: Vec<Box<dyn InputSource>>,
+    input_map: Option<&'a str>,
+    frame_rate: FrameRate,
+    time: Time,
 }
 
-impl<'a, T> AppBuilder<'a, T> where T: System {
+impl<'a> AppBuilder<'a> {
     pub fn name(mut self, name: &'a str) -> Self {
         self.name = Some(name);
         self
@@ -19,13 +28,73 @@ impl<'a, T> AppBuilder<'a, T> where T: System {
         self
     }
 
-    pub fn system(mut self, system: T) -> App<'a, T> {
-        self.system = Some(system);
-        
+    /// Render offscreen without a window, used for automated rendering on machines without a display. <br>
+    /// Runs a single frame unless [frames](Self::frames) is set.
+    pub fn headless(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height);
+        self.headless = true;
+        self
+    }
+
+    /// Exit the application after the given amount of frames have been rendered
+    pub fn frames(mut self, frames: u32) -> Self {
+        self.frames = Some(frames);
+        self
+    }
+
+    /// Key which saves a screenshot to `./screenshots` when pressed, `None` disables the hotkey. <br>
+    /// Defaults to F12.
+    pub fn screenshot_key(mut self, key: Option<VirtualKeyCode>) -> Self {
+        self.screenshot_key = key;
+        self
+    }
+
+    /// Add a source of input events polled every frame, used for gamepad backends and replaying input in tests
+    pub fn input_source(mut self, source: impl InputSource + 'static) -> Self {
+        self.input_sources.push(Box::new(source));
+        self
+    }
+
+    /// Load action and axis bindings from a `.ron` or `.toml` file in `./assets`, changes are hot-reloaded
+    pub fn input_map(mut self, file: &'a str) -> Self {
+        self.input_map = Some(file);
+        self
+    }
+
+    /// How often frames are rendered, either capped to a target rate, synced to the display or unlimited. <br>
+    /// Defaults to 144 frames per second.
+    pub fn frame_rate(mut self, frame_rate: FrameRate) -> Self {
+        self.frame_rate = frame_rate;
+        self
+    }
+
+    /// Run [System::fixed_update] every `step`, at most `max_substeps` times per frame. <br>
+    /// Defaults to 60 updates per second and 5 substeps.
+    pub fn fixed_timestep(mut self, step: Duration, max_substeps: u32) -> Self {
+        self.time = Time::new(step, max_substeps);
+        self
+    }
+
+    /// Add a system, either directly to the update stage or through a [SystemDescriptor] with its stage and ordering
+    pub fn add_system(mut self, system: impl Into<SystemDescriptor>) -> Self {
+        self.systems.push(system.into());
+        self
+    }
+
+    pub fn build(self) -> App<'a> {
         App {
             name: self.name.expect("no name was set for application"),
-            system: Arc::new(Mutex::new(self.system.expect("not system was set"))),
+            systems: self.systems,
+            schedule: None,
             size: self.size,
+            headless: self.headless,
+            frames: self.frames,
+            screenshot_key: self.screenshot_key,
+            input_sources: self.input_sources,
+            input_map: self.input_map,
+            frame: 0,
+            frame_rate: self.frame_rate,
+            time: Some(self.time),
 
             context: None,
         }
@@ -42,12 +111,19 @@ impl<'a, T> AppBuilder<'a, T> where T: System {
     }
 }
 
-impl<'a, T> Default for AppBuilder<'a, T> where T: System {
+impl<'a> Default for AppBuilder<'a> {
     fn default() -> Self {
         AppBuilder {
             name: None,
-            system: None,
+            systems: Vec::new(),
             size: (1280, 720),
+            headless: false,
+            frames: None,
+            screenshot_key: Some(VirtualKeyCode::F12),
+            input_sources: Vec::new(),
+            input_map: None,
+            frame_rate: FrameRate::default(),
+            time: Time::default(),
         }
     }
 }
\ No newline at end of file
@@ -4,12 +4,17 @@ use dashmap::{
     DashMap,
 };
 use notify::{DebouncedEvent, RecursiveMode, Watcher};
-use std::{sync::Arc, thread};
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+    thread,
+};
 
 pub use asset::{Asset, AssetKind};
 
 mod asset;
 
+/// Assets are keyed by their absolute path, so files of the same name in different directories stay apart
 #[derive(Clone)]
 pub struct AssetManager {
     assets: Arc<DashMap<String, Asset>>,
@@ -40,30 +45,29 @@ impl AssetManager {
                 info!("Created hot-reload thread");
                 let (tx, rx) = std::sync::mpsc::channel();
                 let mut watcher = notify::watcher(tx, std::time::Duration::from_secs(1)).unwrap();
-                watcher
-                    .watch(path.clone(), RecursiveMode::Recursive)
-                    .unwrap();
+                if let Err(e) = watcher.watch(path.clone(), RecursiveMode::Recursive) {
+                    warn!("Hot-reload disabled, failed to watch {:?}: {}", path, e);
+                    return;
+                }
 
                 loop {
                     match rx.recv() {
                         Ok(event) => match event {
                             DebouncedEvent::Write(p) => {
                                 info!("Received a write event on path");
-                                let p = std::path::Path::new(&p);
-                                let id = p.file_name().unwrap().to_string_lossy().to_string();
+                                let id = AssetManager::key(&p);
 
                                 let asset = assets.get_mut(&id);
                                 let mut asset = match asset {
                                     Some(asset) => asset,
                                     None => {
-                                        dbg!("here {}", id);
-                                        return;
+                                        trace!("Ignoring write to unregistered file {:?}", id);
+                                        continue;
                                     }
                                 };
 
                                 // Check if item should be reloaded
                                 if !asset.should_reload {
-                                    dbg!("here");
                                     continue;
                                 }
 
@@ -89,7 +93,10 @@ impl AssetManager {
                             }
                             _ => {}
                         },
-                        Err(e) => println!("watch error: {:?}", e),
+                        Err(e) => {
+                            warn!("Hot-reload stopped, the file watcher disconnected: {}", e);
+                            return;
+                        }
                     }
                 }
             });
@@ -98,23 +105,13 @@ impl AssetManager {
 
     pub fn create_asset(&mut self, asset: Asset) {
         info!("Creating asset {:?}", asset.path.to_str().unwrap());
-        let id = asset
-            .path
-            .file_name()
-            .unwrap()
-            .to_string_lossy()
-            .to_string();
-        self.assets.insert(id, asset);
+        self.assets.insert(Self::key(&asset.path), asset);
     }
 
     pub fn register_for_hotreload(&mut self, path: std::path::PathBuf) -> Option<()> {
         #[cfg(debug_assertions)]
         {
-            let id = std::path::Path::new(&path)
-                .file_name()
-                .unwrap()
-                .to_string_lossy()
-                .to_string();
+            let id = Self::key(&path);
             trace!("Registering file for hot-reload {:?}", id.as_str());
             let mut asset = self.asset_mut(&id)?;
             asset.should_reload = true;
@@ -122,12 +119,36 @@ impl AssetManager {
         Some(())
     }
 
-    pub fn asset_mut(&mut self, identifier: &str) -> Option<RefMut<'_, String, Asset>> {
-        self.assets.get_mut(&identifier.to_string())
+    /// Get the key of the asset at a path, which may be given relative to the working directory
+    pub fn key(path: impl AsRef<Path>) -> String {
+        let path = path.as_ref();
+        if let Ok(path) = std::fs::canonicalize(path) {
+            return path.to_string_lossy().to_string();
+        }
+
+        // Files which do not exist (anymore) are keyed by their normalized absolute path
+        let mut normalized = PathBuf::new();
+        if path.is_relative() {
+            normalized = std::env::current_dir().unwrap_or_default();
+        }
+        for component in path.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    normalized.pop();
+                }
+                component => normalized.push(component),
+            }
+        }
+        normalized.to_string_lossy().to_string()
+    }
+
+    pub fn asset_mut(&mut self, path: &str) -> Option<RefMut<'_, String, Asset>> {
+        self.assets.get_mut(&Self::key(path))
     }
 
-    pub fn asset(&mut self, identifier: &str) -> Option<Ref<'_, String, Asset>> {
-        self.assets.get(&identifier.to_string())
+    pub fn asset(&mut self, path: &str) -> Option<Ref<'_, String, Asset>> {
+        self.assets.get(&Self::key(path))
     }
 
     /// Get a reference to the asset manager's channel.
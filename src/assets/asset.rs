@@ -8,6 +8,8 @@ pub enum AssetKind {
     Shader,
     Texture,
     Video,
+    /// Configuration files such as input maps
+    Config,
 }
 
 /// Asset object represents any assets within the engine such as textures and shaders
@@ -68,9 +70,11 @@ impl Asset {
         Ok(())
     }
 
-    pub fn raw_to_cstr(&self) -> std::ffi::CString {
-        let s = std::str::from_utf8(&self.raw).expect("Failed conversion to string");
-        CString::new(s).unwrap()
+    /// Get the asset's data as a CString, fails if it is not valid UTF-8 or contains a nul byte.
+    pub fn raw_to_cstr(&self) -> Result<std::ffi::CString, String> {
+        let s =
+            std::str::from_utf8(&self.raw).map_err(|e| format!("Asset {:?} is not valid UTF-8: {}", self.name, e))?;
+        CString::new(s).map_err(|e| format!("Asset {:?} contains a nul byte: {}", self.name, e))
     }
 
     /// Get a reference to the asset's kind.
@@ -1,13 +1,26 @@
-use crate::{assets::AssetManager, engine::Engine};
+use crate::{assets::AssetManager, ecs::World, engine::Engine, input::{Actions, Input, InputMap}, system::Systems, time::Time};
 
 pub struct Context {
     engine: Engine,
     asset_manager: AssetManager,
+    input: Input,
+    input_map: InputMap,
+    time: Time,
+    systems: Systems,
+    world: World,
 }
 
 impl Context {
-    pub fn new(engine: Engine, asset_manager: AssetManager) -> Self {
-        Context { engine, asset_manager }
+    pub fn new(engine: Engine, asset_manager: AssetManager, time: Time) -> Self {
+        Context {
+            engine,
+            asset_manager,
+            input: Input::new(),
+            input_map: InputMap::new(),
+            time,
+            systems: Systems::default(),
+            world: World::new(),
+        }
     }
 
     /// Get a reference to the context's engine.
@@ -29,4 +42,65 @@ impl Context {
     pub fn asset_manager_mut(&mut self) -> &mut AssetManager {
         &mut self.asset_manager
     }
+
+    /// Get a reference to the context's input.
+    pub fn input(&self) -> &Input {
+        &self.input
+    }
+
+    /// Get a mutable reference to the context's input.
+    pub fn input_mut(&mut self) -> &mut Input {
+        &mut self.input
+    }
+
+    /// Get a reference to the context's input map.
+    pub fn input_map(&self) -> &InputMap {
+        &self.input_map
+    }
+
+    /// Get a mutable reference to the context's input map, used for rebinding.
+    pub fn input_map_mut(&mut self) -> &mut InputMap {
+        &mut self.input_map
+    }
+
+    /// Get a reference to the context's time.
+    pub fn time(&self) -> &Time {
+        &self.time
+    }
+
+    /// Get a mutable reference to the context's time, used to change the fixed timestep.
+    pub fn time_mut(&mut self) -> &mut Time {
+        &mut self.time
+    }
+
+    /// Get a reference to the context's systems.
+    pub fn systems(&self) -> &Systems {
+        &self.systems
+    }
+
+    /// Get a mutable reference to the context's systems, used to enable and disable systems.
+    pub fn systems_mut(&mut self) -> &mut Systems {
+        &mut self.systems
+    }
+
+    /// Get a reference to the context's world.
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// Get a mutable reference to the context's world, used to spawn entities and change their components.
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    /// Propagates the transforms of the world and draws its entities with the renderer
+    pub(crate) fn draw_world(&mut self) {
+        self.world.propagate_transforms();
+        self.engine.renderer_mut().draw_world(&self.world);
+    }
+
+    /// Get the actions and axes of the input map evaluated for the current frame.
+    pub fn actions(&self) -> Actions<'_> {
+        Actions::new(&self.input_map, &self.input)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,13 @@
+pub use components::{Mesh, MeshRenderer};
+pub use entity::Entity;
+pub use query::{Access, Query, QueryIter, ReadOnlyQuery};
+pub use transform::{Children, GlobalTransform, Parent, Transform};
+pub use world::World;
+
+pub use crate::renderer::camera::Camera;
+
+mod components;
+mod entity;
+mod query;
+mod transform;
+mod world;
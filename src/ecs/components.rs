@@ -0,0 +1,57 @@
+use std::rc::Rc;
+
+use crate::renderer::{
+    bounds::Aabb,
+    render_target::{vertex_array::Vertex, PrimitiveTopology, RenderTarget},
+};
+
+/// Geometry which can be drawn without knowing its vertex type
+pub trait Mesh {
+    /// Draws all elements, the shader program has to be bound
+    fn draw(&self);
+
+    /// Get the bounds of the mesh in model space, `None` if it is never culled.
+    fn bounds(&self) -> Option<&Aabb>;
+
+    /// Get the topology the mesh is drawn with.
+    fn topology(&self) -> PrimitiveTopology {
+        PrimitiveTopology::default()
+    }
+}
+
+impl<'a, VERTEX> Mesh for RenderTarget<'a, VERTEX>
+where
+    VERTEX: Vertex<'a>,
+{
+    fn draw(&self) {
+        RenderTarget::draw(self);
+    }
+
+    fn bounds(&self) -> Option<&Aabb> {
+        RenderTarget::bounds(self)
+    }
+
+    fn topology(&self) -> PrimitiveTopology {
+        RenderTarget::topology(self)
+    }
+}
+
+/// Draws a mesh with a shader program of the renderer at the entity's [Transform](super::Transform) every frame
+#[derive(Clone)]
+pub struct MeshRenderer {
+    /// Shared between entities drawing the same geometry
+    pub mesh: Rc<dyn Mesh>,
+    /// Name of the program in the renderer's shader programs
+    pub shader: String,
+    pub visible: bool,
+}
+
+impl MeshRenderer {
+    pub fn new(mesh: Rc<dyn Mesh>, shader: &str) -> Self {
+        MeshRenderer {
+            mesh,
+            shader: shader.to_string(),
+            visible: true,
+        }
+    }
+}
@@ -0,0 +1,81 @@
+/// Handle to an entity of a [World](super::World). <br>
+/// Indices are reused after an entity is despawned, the generation tells the old and new entity apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    pub(super) fn new(index: u32, generation: u32) -> Self {
+        Entity { index, generation }
+    }
+
+    /// Get the entity's index, used to look up its components.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Get the entity's generation.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+/// Hands out entities, reusing the indices of despawned entities
+#[derive(Debug, Default)]
+pub(super) struct Entities {
+    /// Generation of every index, odd while alive
+    generations: Vec<u32>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl Entities {
+    pub(super) fn alloc(&mut self) -> Entity {
+        self.len += 1;
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.generations.push(0);
+                (self.generations.len() - 1) as u32
+            }
+        };
+
+        let generation = &mut self.generations[index as usize];
+        *generation += 1;
+        Entity::new(index, *generation)
+    }
+
+    pub(super) fn free(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+
+        self.generations[entity.index as usize] += 1;
+        self.free.push(entity.index);
+        self.len -= 1;
+        true
+    }
+
+    pub(super) fn is_alive(&self, entity: Entity) -> bool {
+        matches!(self.generations.get(entity.index as usize), Some(&g) if g == entity.generation && g % 2 == 1)
+    }
+
+    /// Get the living entity at an index.
+    pub(super) fn get(&self, index: usize) -> Option<Entity> {
+        match self.generations.get(index) {
+            Some(&g) if g % 2 == 1 => Some(Entity::new(index as u32, g)),
+            _ => None,
+        }
+    }
+
+    /// Get the amount of indices handed out, including those of despawned entities.
+    pub(super) fn capacity(&self) -> usize {
+        self.generations.len()
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.len
+    }
+}
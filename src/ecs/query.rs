@@ -0,0 +1,226 @@
+use std::{
+    any::{type_name, TypeId},
+    marker::PhantomData,
+};
+
+use super::{Entity, World};
+
+/// Component types a query reads and writes
+pub struct Access {
+    components: Vec<(TypeId, &'static str, bool)>,
+}
+
+impl Access {
+    fn add<T>(&mut self, mutable: bool)
+    where
+        T: 'static,
+    {
+        self.components.push((TypeId::of::<T>(), type_name::<T>(), mutable));
+    }
+
+    /// Panics if a component type is accessed mutably along with any other access to it
+    fn validate(&self) {
+        for (i, (id, name, mutable)) in self.components.iter().enumerate() {
+            for (other, _, other_mutable) in self.components[i + 1..].iter() {
+                if id == other && (*mutable || *other_mutable) {
+                    panic!("Query accesses {} mutably more than once", name);
+                }
+            }
+        }
+    }
+}
+
+/// A set of components fetched together for every entity having all of them. <br>
+/// Implemented for `&T`, `&mut T`, `Option<&T>`, `Option<&mut T>` and tuples of up to six queries.
+///
+/// # Safety
+/// `access` has to list every component type `get` reads or writes.
+pub unsafe trait Query<'w> {
+    type Item;
+    /// Pointers into the storages of the queried components
+    type Fetch: Copy;
+
+    fn access(access: &mut Access);
+
+    /// Get pointers to the queried storages, `None` if a required storage does not exist.
+    fn fetch(world: &'w World) -> Option<Self::Fetch>;
+
+    /// Get the components of the entity at an index, `None` if it lacks a required component.
+    ///
+    /// # Safety
+    /// The fetch must belong to a world borrowed for `'w` whose access has been validated,
+    /// and each index may only be fetched once while the items are alive.
+    unsafe fn get(fetch: Self::Fetch, index: usize) -> Option<Self::Item>;
+}
+
+/// A query which only reads components and can be run on a shared world
+///
+/// # Safety
+/// The query may not write any component.
+pub unsafe trait ReadOnlyQuery<'w>: Query<'w> {}
+
+unsafe impl<'w, T> Query<'w> for &'w T
+where
+    T: 'static,
+{
+    type Item = &'w T;
+    type Fetch = (*const Option<T>, usize);
+
+    fn access(access: &mut Access) {
+        access.add::<T>(false);
+    }
+
+    fn fetch(world: &'w World) -> Option<Self::Fetch> {
+        let (ptr, len) = world.storage::<T>()?.as_ptr();
+        Some((ptr as *const _, len))
+    }
+
+    unsafe fn get((ptr, len): Self::Fetch, index: usize) -> Option<Self::Item> {
+        match index < len {
+            true => (*ptr.add(index)).as_ref(),
+            false => None,
+        }
+    }
+}
+
+unsafe impl<'w, T> ReadOnlyQuery<'w> for &'w T where T: 'static {}
+
+unsafe impl<'w, T> Query<'w> for &'w mut T
+where
+    T: 'static,
+{
+    type Item = &'w mut T;
+    type Fetch = (*mut Option<T>, usize);
+
+    fn access(access: &mut Access) {
+        access.add::<T>(true);
+    }
+
+    fn fetch(world: &'w World) -> Option<Self::Fetch> {
+        Some(world.storage::<T>()?.as_ptr())
+    }
+
+    unsafe fn get((ptr, len): Self::Fetch, index: usize) -> Option<Self::Item> {
+        match index < len {
+            true => (*ptr.add(index)).as_mut(),
+            false => None,
+        }
+    }
+}
+
+/// Optional components never exclude an entity from a query
+unsafe impl<'w, Q> Query<'w> for Option<Q>
+where
+    Q: Query<'w>,
+{
+    type Item = Option<Q::Item>;
+    type Fetch = Option<Q::Fetch>;
+
+    fn access(access: &mut Access) {
+        Q::access(access);
+    }
+
+    fn fetch(world: &'w World) -> Option<Self::Fetch> {
+        Some(Q::fetch(world))
+    }
+
+    unsafe fn get(fetch: Self::Fetch, index: usize) -> Option<Self::Item> {
+        match fetch {
+            Some(fetch) => Some(Q::get(fetch, index)),
+            None => Some(None),
+        }
+    }
+}
+
+unsafe impl<'w, Q> ReadOnlyQuery<'w> for Option<Q> where Q: ReadOnlyQuery<'w> {}
+
+macro_rules! impl_query_tuple {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case)]
+        unsafe impl<'w, $($name),*> Query<'w> for ($($name,)*)
+        where
+            $($name: Query<'w>),*
+        {
+            type Item = ($($name::Item,)*);
+            type Fetch = ($($name::Fetch,)*);
+
+            fn access(access: &mut Access) {
+                $($name::access(access);)*
+            }
+
+            fn fetch(world: &'w World) -> Option<Self::Fetch> {
+                Some(($($name::fetch(world)?,)*))
+            }
+
+            unsafe fn get(($($name,)*): Self::Fetch, index: usize) -> Option<Self::Item> {
+                Some(($($name::get($name, index)?,)*))
+            }
+        }
+
+        unsafe impl<'w, $($name),*> ReadOnlyQuery<'w> for ($($name,)*) where $($name: ReadOnlyQuery<'w>),* {}
+    };
+}
+
+impl_query_tuple!(A);
+impl_query_tuple!(A, B);
+impl_query_tuple!(A, B, C);
+impl_query_tuple!(A, B, C, D);
+impl_query_tuple!(A, B, C, D, E);
+impl_query_tuple!(A, B, C, D, E, F);
+
+/// Iterator over the entities matching a query along with their components
+pub struct QueryIter<'w, Q>
+where
+    Q: Query<'w>,
+{
+    world: &'w World,
+    fetch: Option<Q::Fetch>,
+    index: usize,
+    marker: PhantomData<Q>,
+}
+
+impl<'w, Q> QueryIter<'w, Q>
+where
+    Q: Query<'w>,
+{
+    /// Callers must hold a mutable borrow of the world unless the query is read only
+    pub(super) fn new(world: &'w World) -> Self {
+        let mut access = Access { components: Vec::new() };
+        Q::access(&mut access);
+        access.validate();
+
+        QueryIter {
+            world,
+            fetch: Q::fetch(world),
+            index: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'w, Q> Iterator for QueryIter<'w, Q>
+where
+    Q: Query<'w>,
+{
+    type Item = (Entity, Q::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let fetch = self.fetch?;
+        while self.index < self.world.capacity() {
+            let index = self.index;
+            self.index += 1;
+
+            let entity = match self.world.entity(index) {
+                Some(entity) => entity,
+                None => continue,
+            };
+
+            // Safety: the access was validated and every index is visited once
+            if let Some(item) = unsafe { Q::get(fetch, index) } {
+                return Some((entity, item));
+            }
+        }
+
+        None
+    }
+}
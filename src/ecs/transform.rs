@@ -0,0 +1,274 @@
+extern crate nalgebra as na;
+
+use super::{Entity, World};
+
+/// Translation, rotation and scale of an entity relative to its parent. <br>
+/// Changes mark the transform dirty, its [GlobalTransform] and those of its children are recomputed on the next propagation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    translation: na::Vector3<f32>,
+    rotation: na::UnitQuaternion<f32>,
+    scale: na::Vector3<f32>,
+    dirty: bool,
+}
+
+impl Transform {
+    pub fn from_translation(translation: na::Vector3<f32>) -> Self {
+        Transform {
+            translation,
+            ..Transform::default()
+        }
+    }
+
+    pub fn with_rotation(mut self, rotation: na::UnitQuaternion<f32>) -> Self {
+        self.set_rotation(rotation);
+        self
+    }
+
+    pub fn with_scale(mut self, scale: na::Vector3<f32>) -> Self {
+        self.set_scale(scale);
+        self
+    }
+
+    /// Get the transform's translation.
+    pub fn translation(&self) -> na::Vector3<f32> {
+        self.translation
+    }
+
+    /// Set the transform's translation.
+    pub fn set_translation(&mut self, translation: na::Vector3<f32>) {
+        self.translation = translation;
+        self.dirty = true;
+    }
+
+    /// Moves the transform by an offset in its parent's space
+    pub fn translate(&mut self, offset: na::Vector3<f32>) {
+        self.set_translation(self.translation + offset);
+    }
+
+    /// Get the transform's rotation.
+    pub fn rotation(&self) -> na::UnitQuaternion<f32> {
+        self.rotation
+    }
+
+    /// Set the transform's rotation.
+    pub fn set_rotation(&mut self, rotation: na::UnitQuaternion<f32>) {
+        self.rotation = rotation;
+        self.dirty = true;
+    }
+
+    /// Applies a rotation on top of the current one
+    pub fn rotate(&mut self, rotation: na::UnitQuaternion<f32>) {
+        self.set_rotation(rotation * self.rotation);
+    }
+
+    /// Get the transform's scale.
+    pub fn scale(&self) -> na::Vector3<f32> {
+        self.scale
+    }
+
+    /// Set the transform's scale.
+    pub fn set_scale(&mut self, scale: na::Vector3<f32>) {
+        self.scale = scale;
+        self.dirty = true;
+    }
+
+    /// Returns true if the transform changed since its global transform was computed
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Get the local matrix, scaling first, then rotating and translating.
+    pub fn matrix(&self) -> na::Matrix4<f32> {
+        na::Matrix4::new_translation(&self.translation)
+            * self.rotation.to_homogeneous()
+            * na::Matrix4::new_nonuniform_scaling(&self.scale)
+    }
+
+    /// Get the direction the transform faces, -Z rotated by the rotation.
+    pub fn forward(&self) -> na::Vector3<f32> {
+        self.rotation * -na::Vector3::z()
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform {
+            translation: na::Vector3::zeros(),
+            rotation: na::UnitQuaternion::identity(),
+            scale: na::Vector3::repeat(1.0),
+            dirty: true,
+        }
+    }
+}
+
+/// World space matrix of an entity, computed from its [Transform] and those of its ancestors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlobalTransform {
+    matrix: na::Matrix4<f32>,
+    rotation: na::UnitQuaternion<f32>,
+}
+
+impl GlobalTransform {
+    /// Get the model matrix.
+    pub fn matrix(&self) -> &na::Matrix4<f32> {
+        &self.matrix
+    }
+
+    /// Get the world space translation.
+    pub fn translation(&self) -> na::Vector3<f32> {
+        self.matrix.fixed_slice::<3, 1>(0, 3).into_owned()
+    }
+
+    /// Get the world space rotation, combined from the rotations of the entity and its ancestors.
+    pub fn rotation(&self) -> na::UnitQuaternion<f32> {
+        self.rotation
+    }
+}
+
+impl Default for GlobalTransform {
+    fn default() -> Self {
+        GlobalTransform {
+            matrix: na::Matrix4::identity(),
+            rotation: na::UnitQuaternion::identity(),
+        }
+    }
+}
+
+/// Parent of an entity, managed by [World::set_parent]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parent(Entity);
+
+impl Parent {
+    /// Get the parent entity.
+    pub fn entity(&self) -> Entity {
+        self.0
+    }
+}
+
+/// Children of an entity, managed by [World::set_parent]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Children(Vec<Entity>);
+
+impl Children {
+    /// Get the child entities in the order they were attached.
+    pub fn entities(&self) -> &[Entity] {
+        &self.0
+    }
+}
+
+impl World {
+    /// Attaches an entity to a parent, or detaches it with `None`. <br>
+    /// Fails if either entity is despawned or the parent is a descendant of the entity.
+    pub fn set_parent(&mut self, entity: Entity, parent: Option<Entity>) -> Result<(), String> {
+        if !self.is_alive(entity) {
+            return Err(format!("Failed to set parent, {:?} has been despawned", entity));
+        }
+
+        if let Some(parent) = parent {
+            if !self.is_alive(parent) {
+                return Err(format!("Failed to set parent, {:?} has been despawned", parent));
+            }
+
+            let mut ancestor = Some(parent);
+            while let Some(current) = ancestor {
+                if current == entity {
+                    return Err(format!("Failed to set parent, {:?} is a descendant of {:?}", parent, entity));
+                }
+                ancestor = self.parent(current);
+            }
+        }
+
+        if let Some(Parent(old)) = self.remove::<Parent>(entity) {
+            if let Some(children) = self.get_mut::<Children>(old) {
+                children.0.retain(|&child| child != entity);
+            }
+        }
+
+        if let Some(parent) = parent {
+            self.insert(entity, Parent(parent))?;
+            match self.get_mut::<Children>(parent) {
+                Some(children) => children.0.push(entity),
+                None => {
+                    self.insert(parent, Children(vec![entity]))?;
+                }
+            }
+        }
+
+        if let Some(transform) = self.get_mut::<Transform>(entity) {
+            transform.dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Get the parent of an entity, `None` for root entities.
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        self.get::<Parent>(entity).map(Parent::entity)
+    }
+
+    /// Get the children of an entity.
+    pub fn children(&self, entity: Entity) -> &[Entity] {
+        match self.get::<Children>(entity) {
+            Some(children) => children.entities(),
+            None => &[],
+        }
+    }
+
+    /// Despawns an entity along with all of its descendants, returns false if the entity was already despawned
+    pub fn despawn_recursive(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+
+        // Detach first so the parent does not keep the despawned entity as a child
+        let _ = self.set_parent(entity, None);
+
+        let mut stack = vec![entity];
+        while let Some(current) = stack.pop() {
+            stack.extend_from_slice(self.children(current));
+            self.despawn(current);
+        }
+        true
+    }
+
+    /// Recomputes the [GlobalTransform] of every entity whose transform or ancestors' transforms changed. <br>
+    /// Entities whose parent has no transform are treated as roots.
+    pub fn propagate_transforms(&mut self) {
+        let roots: Vec<_> = self
+            .query::<(&Transform, Option<&Parent>)>()
+            .filter(|(_, (_, parent))| !matches!(parent, Some(Parent(p)) if self.has::<Transform>(*p)))
+            .map(|(entity, _)| entity)
+            .collect();
+
+        let mut stack: Vec<_> = roots
+            .into_iter()
+            .map(|root| (root, GlobalTransform::default(), false))
+            .collect();
+
+        while let Some((entity, parent, parent_dirty)) = stack.pop() {
+            let missing = !self.has::<GlobalTransform>(entity);
+            let transform = match self.get_mut::<Transform>(entity) {
+                Some(transform) => transform,
+                None => continue,
+            };
+
+            let dirty = parent_dirty || transform.dirty || missing;
+            let global = match dirty {
+                true => {
+                    transform.dirty = false;
+                    let global = GlobalTransform {
+                        matrix: parent.matrix * transform.matrix(),
+                        rotation: parent.rotation * transform.rotation,
+                    };
+                    let _ = self.insert(entity, global);
+                    global
+                }
+                false => *self.get::<GlobalTransform>(entity).unwrap(),
+            };
+
+            for &child in self.children(entity) {
+                stack.push((child, global, dirty));
+            }
+        }
+    }
+}
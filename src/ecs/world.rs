@@ -0,0 +1,233 @@
+use std::{
+    any::{type_name, Any, TypeId},
+    cell::UnsafeCell,
+    collections::HashMap,
+};
+
+use super::{
+    entity::{Entities, Entity},
+    query::{Query, QueryIter, ReadOnlyQuery},
+};
+
+/// Entities and their components, each component type is stored in its own storage indexed by entity
+#[derive(Default)]
+pub struct World {
+    entities: Entities,
+    storages: HashMap<TypeId, Box<dyn AnyStorage>>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        World::default()
+    }
+
+    /// Creates an entity without components
+    pub fn spawn(&mut self) -> Entity {
+        self.entities.alloc()
+    }
+
+    /// Removes an entity along with all of its components, returns false if the entity was already despawned
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.entities.free(entity) {
+            return false;
+        }
+
+        for storage in self.storages.values_mut() {
+            storage.remove(entity.index() as usize);
+        }
+        true
+    }
+
+    /// Returns true if the entity has not been despawned
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.entities.is_alive(entity)
+    }
+
+    /// Get the amount of living entities.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over all living entities
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        (0..self.entities.capacity()).filter_map(move |index| self.entities.get(index))
+    }
+
+    /// Adds a component to an entity, replacing and returning the component of the same type it already had
+    pub fn insert<T>(&mut self, entity: Entity, component: T) -> Result<Option<T>, String>
+    where
+        T: 'static,
+    {
+        if !self.is_alive(entity) {
+            return Err(format!("Failed to insert {}, {:?} has been despawned", type_name::<T>(), entity));
+        }
+
+        let storage = self
+            .storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Storage::<T>::default()))
+            .as_any_mut()
+            .downcast_mut::<Storage<T>>()
+            .unwrap();
+
+        Ok(storage.insert(entity.index() as usize, component))
+    }
+
+    /// Removes a component from an entity, returning it if the entity had one
+    pub fn remove<T>(&mut self, entity: Entity) -> Option<T>
+    where
+        T: 'static,
+    {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        self.storage_mut::<T>()?.remove(entity.index() as usize)
+    }
+
+    /// Get a reference to a component of an entity.
+    pub fn get<T>(&self, entity: Entity) -> Option<&T>
+    where
+        T: 'static,
+    {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        self.storage::<T>()?.get(entity.index() as usize)
+    }
+
+    /// Get a mutable reference to a component of an entity.
+    pub fn get_mut<T>(&mut self, entity: Entity) -> Option<&mut T>
+    where
+        T: 'static,
+    {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        self.storage_mut::<T>()?.get_mut(entity.index() as usize)
+    }
+
+    /// Returns true if the entity has a component of the given type
+    pub fn has<T>(&self, entity: Entity) -> bool
+    where
+        T: 'static,
+    {
+        self.get::<T>(entity).is_some()
+    }
+
+    /// Iterates over the entities having every component of the query, for example `(&Transform, Option<&Camera>)`
+    pub fn query<'w, Q>(&'w self) -> QueryIter<'w, Q>
+    where
+        Q: ReadOnlyQuery<'w>,
+    {
+        QueryIter::new(self)
+    }
+
+    /// Iterates over the entities having every component of the query, for example `(&mut Transform, &Velocity)`. <br>
+    /// Panics if the query accesses a component type mutably more than once.
+    pub fn query_mut<'w, Q>(&'w mut self) -> QueryIter<'w, Q>
+    where
+        Q: Query<'w>,
+    {
+        QueryIter::new(self)
+    }
+
+    pub(super) fn storage<T>(&self) -> Option<&Storage<T>>
+    where
+        T: 'static,
+    {
+        self.storages.get(&TypeId::of::<T>())?.as_any().downcast_ref()
+    }
+
+    fn storage_mut<T>(&mut self) -> Option<&mut Storage<T>>
+    where
+        T: 'static,
+    {
+        self.storages.get_mut(&TypeId::of::<T>())?.as_any_mut().downcast_mut()
+    }
+
+    /// Get the amount of indices entities have been given, every index below is checked by queries.
+    pub(super) fn capacity(&self) -> usize {
+        self.entities.capacity()
+    }
+
+    pub(super) fn entity(&self, index: usize) -> Option<Entity> {
+        self.entities.get(index)
+    }
+}
+
+/// Components of a single type, indexed by entity index. <br>
+/// The components are kept in an [UnsafeCell] so queries can hand out mutable references to several storages at once.
+pub(super) struct Storage<T> {
+    components: UnsafeCell<Vec<Option<T>>>,
+}
+
+impl<T> Storage<T> {
+    fn insert(&mut self, index: usize, component: T) -> Option<T> {
+        let components = self.components.get_mut();
+        if components.len() <= index {
+            components.resize_with(index + 1, || None);
+        }
+
+        components[index].replace(component)
+    }
+
+    fn remove(&mut self, index: usize) -> Option<T> {
+        self.components.get_mut().get_mut(index)?.take()
+    }
+
+    fn get(&self, index: usize) -> Option<&T> {
+        // Safety: mutable access through `as_ptr` requires a query over a mutably borrowed world
+        let components = unsafe { &*self.components.get() };
+        components.get(index)?.as_ref()
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.components.get_mut().get_mut(index)?.as_mut()
+    }
+
+    /// Get a pointer to the components and their amount, reading or writing them is up to the query
+    pub(super) fn as_ptr(&self) -> (*mut Option<T>, usize) {
+        // Safety: only the vector's header is borrowed, not the components other queries may reference
+        let components = unsafe { &mut *self.components.get() };
+        (components.as_mut_ptr(), components.len())
+    }
+}
+
+impl<T> Default for Storage<T> {
+    fn default() -> Self {
+        Storage {
+            components: UnsafeCell::new(Vec::new()),
+        }
+    }
+}
+
+/// Type erased [Storage], used to remove the components of despawned entities
+trait AnyStorage {
+    fn remove(&mut self, index: usize);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T> AnyStorage for Storage<T>
+where
+    T: 'static,
+{
+    fn remove(&mut self, index: usize) {
+        Storage::remove(self, index);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
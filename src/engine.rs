@@ -1,19 +1,21 @@
 use glutin::event_loop::EventLoop;
 
-use crate::{assets::AssetManager, renderer::Renderer};
+use crate::{assets::AssetManager, renderer::{Renderer, Surface}};
 
 pub struct Engine {
     renderer: Renderer<'static, 2>,
 }
 
 impl Engine {
-    pub fn new(name: String, width: u32, height: u32, asset_manager: AssetManager) -> (Engine, EventLoop<()>) {
+    pub fn new(name: String, width: u32, height: u32, vsync: bool, asset_manager: AssetManager) -> (Engine, EventLoop<()>) {
         let event_loop = glutin::event_loop::EventLoop::new();
         let window = glutin::window::WindowBuilder::new()
             .with_title(name)
             .with_inner_size(glutin::dpi::LogicalSize::new(width, height));
 
         let gl_window = glutin::ContextBuilder::new()
+                    .with_vsync(vsync)
+                    .with_depth_buffer(24)
                     .build_windowed(window, &event_loop)
                     .unwrap();
 
@@ -21,12 +23,21 @@ impl Engine {
 
         (
             Engine {
-                renderer: Renderer::new(gl_window, asset_manager),
+                renderer: Renderer::new(Surface::Windowed(gl_window), asset_manager),
             }, 
             event_loop
         )
     }
 
+    /// Creates an engine rendering into an offscreen framebuffer, no window or display is required.
+    pub fn new_headless(width: u32, height: u32, asset_manager: AssetManager) -> Result<Engine, String> {
+        let surface = Surface::headless(width, height)?;
+
+        Ok(Engine {
+            renderer: Renderer::new(surface, asset_manager),
+        })
+    }
+
     pub fn awake(&mut self) {
         self.renderer.awake();
     }
@@ -0,0 +1,274 @@
+extern crate nalgebra as na;
+
+use std::{collections::HashSet, hash::Hash};
+
+pub use glutin::event::{ElementState, MouseButton, VirtualKeyCode};
+
+pub use event::InputEvent;
+pub use gamepad::{Gamepad, GamepadAxis, GamepadButton, GamepadId};
+pub use mapping::{Actions, Axis2Binding, Axis2Mapping, AxisBinding, AxisMapping, Binding, ConfigFormat, InputMap};
+pub use source::{InputSource, ScriptedInput};
+
+mod event;
+mod gamepad;
+mod mapping;
+mod source;
+
+/// Keyboard, mouse and gamepad state of the current frame. <br>
+/// Events are collected between frames, pressed and released states last for a single frame.
+pub struct Input {
+    keys: Buttons<VirtualKeyCode>,
+    mouse_buttons: Buttons<MouseButton>,
+    gamepads: Vec<Gamepad>,
+
+    cursor_position: Option<na::Point2<f32>>,
+    cursor_delta: na::Vector2<f32>,
+    scroll_delta: na::Vector2<f32>,
+    text: String,
+    focused: bool,
+
+    cursor_grabbed: bool,
+    cursor_visible: bool,
+    cursor_changed: bool,
+}
+
+impl Input {
+    pub fn new() -> Self {
+        Input {
+            keys: Buttons::default(),
+            mouse_buttons: Buttons::default(),
+            gamepads: Vec::new(),
+
+            cursor_position: None,
+            cursor_delta: na::Vector2::zeros(),
+            scroll_delta: na::Vector2::zeros(),
+            text: String::new(),
+            focused: true,
+
+            cursor_grabbed: false,
+            cursor_visible: true,
+            cursor_changed: false,
+        }
+    }
+
+    /// Applies an event to the state of the current frame
+    pub fn handle_event(&mut self, event: &InputEvent) {
+        match *event {
+            InputEvent::Key { key, state } => self.keys.set(key, state),
+            InputEvent::MouseButton { button, state } => self.mouse_buttons.set(button, state),
+            InputEvent::CursorMoved { position } => self.cursor_position = Some(position),
+            InputEvent::CursorLeft => self.cursor_position = None,
+            InputEvent::MouseMotion { delta } => self.cursor_delta += delta,
+            InputEvent::Scroll { delta } => self.scroll_delta += delta,
+            InputEvent::Text(c) => self.text.push(c),
+            InputEvent::Focused(focused) => {
+                self.focused = focused;
+
+                // Releases are not received while unfocused, so nothing is held afterwards
+                if !focused {
+                    self.keys.release_all();
+                    self.mouse_buttons.release_all();
+                }
+            }
+            InputEvent::GamepadConnected(id) => {
+                if self.gamepad(id).is_none() {
+                    trace!("Gamepad {:?} connected", id);
+                    self.gamepads.push(Gamepad::new(id));
+                }
+            }
+            InputEvent::GamepadDisconnected(id) => {
+                trace!("Gamepad {:?} disconnected", id);
+                self.gamepads.retain(|g| g.id() != id);
+            }
+            InputEvent::GamepadButton { id, button, state } => {
+                self.gamepad_or_connect(id).buttons.set(button, state);
+            }
+            InputEvent::GamepadAxis { id, axis, value } => {
+                self.gamepad_or_connect(id).set_axis(axis, value);
+            }
+        }
+    }
+
+    /// Clears the pressed and released states, deltas and text of the current frame
+    pub fn end_frame(&mut self) {
+        self.keys.end_frame();
+        self.mouse_buttons.end_frame();
+        for gamepad in self.gamepads.iter_mut() {
+            gamepad.buttons.end_frame();
+        }
+
+        self.cursor_delta = na::Vector2::zeros();
+        self.scroll_delta = na::Vector2::zeros();
+        self.text.clear();
+    }
+
+    /// Returns true if the key went down this frame
+    pub fn key_pressed(&self, key: VirtualKeyCode) -> bool {
+        self.keys.pressed(&key)
+    }
+
+    /// Returns true while the key is down
+    pub fn key_held(&self, key: VirtualKeyCode) -> bool {
+        self.keys.held(&key)
+    }
+
+    /// Returns true if the key went up this frame
+    pub fn key_released(&self, key: VirtualKeyCode) -> bool {
+        self.keys.released(&key)
+    }
+
+    /// Returns true if the mouse button went down this frame
+    pub fn mouse_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.pressed(&button)
+    }
+
+    /// Returns true while the mouse button is down
+    pub fn mouse_held(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.held(&button)
+    }
+
+    /// Returns true if the mouse button went up this frame
+    pub fn mouse_released(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.released(&button)
+    }
+
+    /// Get the cursor's position in physical pixels from the top left of the window, `None` if it is outside of the window.
+    pub fn cursor_position(&self) -> Option<na::Point2<f32>> {
+        self.cursor_position
+    }
+
+    /// Get the mouse movement of this frame, it is also reported while the cursor is grabbed.
+    pub fn cursor_delta(&self) -> na::Vector2<f32> {
+        self.cursor_delta
+    }
+
+    /// Get the scrolled lines of this frame.
+    pub fn scroll_delta(&self) -> na::Vector2<f32> {
+        self.scroll_delta
+    }
+
+    /// Get the text typed this frame.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns true if the window has focus
+    pub fn focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Get a reference to a connected gamepad.
+    pub fn gamepad(&self, id: GamepadId) -> Option<&Gamepad> {
+        self.gamepads.iter().find(|g| g.id() == id)
+    }
+
+    /// Get a reference to the connected gamepads.
+    pub fn gamepads(&self) -> &[Gamepad] {
+        &self.gamepads
+    }
+
+    /// Confine the cursor to the window, applied to the window after the current frame
+    pub fn set_cursor_grab(&mut self, grab: bool) {
+        self.cursor_changed |= self.cursor_grabbed != grab;
+        self.cursor_grabbed = grab;
+    }
+
+    /// Returns true if the cursor has been grabbed
+    pub fn cursor_grabbed(&self) -> bool {
+        self.cursor_grabbed
+    }
+
+    /// Show or hide the cursor, applied to the window after the current frame
+    pub fn set_cursor_visible(&mut self, visible: bool) {
+        self.cursor_changed |= self.cursor_visible != visible;
+        self.cursor_visible = visible;
+    }
+
+    /// Returns true if the cursor is visible
+    pub fn cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    /// Returns the cursor grab and visibility if they changed since the last call
+    pub(crate) fn take_cursor_changes(&mut self) -> Option<(bool, bool)> {
+        match std::mem::take(&mut self.cursor_changed) {
+            true => Some((self.cursor_grabbed, self.cursor_visible)),
+            false => None,
+        }
+    }
+
+    fn gamepad_or_connect(&mut self, id: GamepadId) -> &mut Gamepad {
+        if let Some(i) = self.gamepads.iter().position(|g| g.id() == id) {
+            return &mut self.gamepads[i];
+        }
+
+        trace!("Gamepad {:?} connected", id);
+        self.gamepads.push(Gamepad::new(id));
+        self.gamepads.last_mut().unwrap()
+    }
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pressed, held and released state of a set of buttons
+pub(crate) struct Buttons<T> {
+    held: HashSet<T>,
+    pressed: HashSet<T>,
+    released: HashSet<T>,
+}
+
+impl<T> Buttons<T>
+where
+    T: Eq + Hash + Copy,
+{
+    fn set(&mut self, button: T, state: ElementState) {
+        match state {
+            // Key repeats are ignored, a button is pressed once until it is released
+            ElementState::Pressed => {
+                if self.held.insert(button) {
+                    self.pressed.insert(button);
+                }
+            }
+            ElementState::Released => {
+                if self.held.remove(&button) {
+                    self.released.insert(button);
+                }
+            }
+        }
+    }
+
+    fn release_all(&mut self) {
+        self.released.extend(self.held.drain());
+    }
+
+    fn end_frame(&mut self) {
+        self.pressed.clear();
+        self.released.clear();
+    }
+
+    fn pressed(&self, button: &T) -> bool {
+        self.pressed.contains(button)
+    }
+
+    fn held(&self, button: &T) -> bool {
+        self.held.contains(button)
+    }
+
+    fn released(&self, button: &T) -> bool {
+        self.released.contains(button)
+    }
+}
+
+impl<T> Default for Buttons<T> {
+    fn default() -> Self {
+        Buttons {
+            held: HashSet::new(),
+            pressed: HashSet::new(),
+            released: HashSet::new(),
+        }
+    }
+}
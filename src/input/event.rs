@@ -0,0 +1,87 @@
+extern crate nalgebra as na;
+
+use glutin::event::{DeviceEvent, ElementState, MouseButton, MouseScrollDelta, VirtualKeyCode, WindowEvent};
+
+use super::{GamepadAxis, GamepadButton, GamepadId};
+
+/// Pixels scrolled per line for touchpads reporting pixel deltas
+const PIXELS_PER_LINE: f32 = 20.0;
+
+/// A single change of input state, created from window events or injected by an [InputSource](super::InputSource)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputEvent {
+    Key {
+        key: VirtualKeyCode,
+        state: ElementState,
+    },
+    MouseButton {
+        button: MouseButton,
+        state: ElementState,
+    },
+    /// The cursor moved to a position in physical pixels from the top left of the window
+    CursorMoved {
+        position: na::Point2<f32>,
+    },
+    CursorLeft,
+    /// Relative mouse movement, independent of the cursor position
+    MouseMotion {
+        delta: na::Vector2<f32>,
+    },
+    /// Scrolled lines
+    Scroll {
+        delta: na::Vector2<f32>,
+    },
+    Text(char),
+    Focused(bool),
+    GamepadConnected(GamepadId),
+    GamepadDisconnected(GamepadId),
+    GamepadButton {
+        id: GamepadId,
+        button: GamepadButton,
+        state: ElementState,
+    },
+    GamepadAxis {
+        id: GamepadId,
+        axis: GamepadAxis,
+        value: f32,
+    },
+}
+
+impl InputEvent {
+    /// Converts a window event, `None` if the event does not affect input state
+    pub fn from_window_event(event: &WindowEvent) -> Option<Self> {
+        match *event {
+            WindowEvent::KeyboardInput { input, .. } => input.virtual_keycode.map(|key| InputEvent::Key {
+                key,
+                state: input.state,
+            }),
+            WindowEvent::MouseInput { state, button, .. } => Some(InputEvent::MouseButton { button, state }),
+            WindowEvent::CursorMoved { position, .. } => Some(InputEvent::CursorMoved {
+                position: na::Point2::new(position.x as f32, position.y as f32),
+            }),
+            WindowEvent::CursorLeft { .. } => Some(InputEvent::CursorLeft),
+            WindowEvent::MouseWheel { delta, .. } => {
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => na::Vector2::new(x, y),
+                    MouseScrollDelta::PixelDelta(p) => {
+                        na::Vector2::new(p.x as f32, p.y as f32) / PIXELS_PER_LINE
+                    }
+                };
+                Some(InputEvent::Scroll { delta })
+            }
+            WindowEvent::ReceivedCharacter(c) if !c.is_control() => Some(InputEvent::Text(c)),
+            WindowEvent::Focused(focused) => Some(InputEvent::Focused(focused)),
+            _ => None,
+        }
+    }
+
+    /// Converts a device event, `None` if the event does not affect input state
+    pub fn from_device_event(event: &DeviceEvent) -> Option<Self> {
+        match *event {
+            DeviceEvent::MouseMotion { delta } => Some(InputEvent::MouseMotion {
+                delta: na::Vector2::new(delta.0 as f32, delta.1 as f32),
+            }),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use super::Buttons;
+
+/// Identifies a connected gamepad, assigned by the [InputSource](super::InputSource) reporting it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GamepadId(pub usize);
+
+/// Gamepad buttons, named after their position on the gamepad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    Mode,
+    LeftStick,
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// Gamepad axes, sticks range from `-1..1` and triggers from `0..1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+const AXIS_COUNT: usize = 6;
+
+/// Button and axis state of a connected gamepad
+pub struct Gamepad {
+    id: GamepadId,
+    pub(super) buttons: Buttons<GamepadButton>,
+    axes: [f32; AXIS_COUNT],
+}
+
+impl Gamepad {
+    pub(super) fn new(id: GamepadId) -> Self {
+        Gamepad {
+            id,
+            buttons: Buttons::default(),
+            axes: [0.0; AXIS_COUNT],
+        }
+    }
+
+    /// Get the gamepad's id.
+    pub fn id(&self) -> GamepadId {
+        self.id
+    }
+
+    /// Returns true if the button went down this frame
+    pub fn pressed(&self, button: GamepadButton) -> bool {
+        self.buttons.pressed(&button)
+    }
+
+    /// Returns true while the button is down
+    pub fn held(&self, button: GamepadButton) -> bool {
+        self.buttons.held(&button)
+    }
+
+    /// Returns true if the button went up this frame
+    pub fn released(&self, button: GamepadButton) -> bool {
+        self.buttons.released(&button)
+    }
+
+    /// Get the current value of an axis.
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes[axis as usize]
+    }
+
+    pub(super) fn set_axis(&mut self, axis: GamepadAxis, value: f32) {
+        self.axes[axis as usize] = value;
+    }
+}
@@ -0,0 +1,614 @@
+extern crate nalgebra as na;
+
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{GamepadAxis, GamepadButton, Input, MouseButton, VirtualKeyCode};
+
+/// A single button an action can be bound to. <br>
+/// Stored as text such as `Key(Space)`, `Mouse(Left)` or `Gamepad(South)` since TOML has no notation for enums.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Binding {
+    Key(VirtualKeyCode),
+    Mouse(MouseButton),
+    /// A button of any connected gamepad
+    Gamepad(GamepadButton),
+}
+
+/// A source of values for a 1D axis. <br>
+/// Stored as text such as `Buttons(Key(D), Key(A))`, `Gamepad(LeftStickX)`, `MouseX`, `MouseY` or `Scroll`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum AxisBinding {
+    /// 1 while positive is held, -1 while negative is held
+    Buttons {
+        positive: Binding,
+        negative: Binding,
+    },
+    /// An axis of any connected gamepad
+    Gamepad(GamepadAxis),
+    /// Horizontal mouse movement in pixels
+    MouseX,
+    /// Vertical mouse movement in pixels, upwards is positive
+    MouseY,
+    /// Vertical scroll in lines
+    Scroll,
+}
+
+/// A source of values for a 2D axis. <br>
+/// Stored as text such as `Buttons(Key(W), Key(S), Key(A), Key(D))`, `Gamepad(LeftStickX, LeftStickY)` or `Mouse`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Axis2Binding {
+    /// Up, down, left and right buttons such as WASD, diagonals are normalized
+    Buttons {
+        up: Binding,
+        down: Binding,
+        left: Binding,
+        right: Binding,
+    },
+    /// Two axes of any connected gamepad, usually a stick
+    Gamepad { x: GamepadAxis, y: GamepadAxis },
+    /// Mouse movement in pixels, upwards is positive
+    Mouse,
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "Key({:?})", key),
+            Binding::Mouse(button) => write!(f, "Mouse({:?})", button),
+            Binding::Gamepad(button) => write!(f, "Gamepad({:?})", button),
+        }
+    }
+}
+
+impl FromStr for Binding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, arguments) = parse_call(s)?;
+        match (name, arguments.as_slice()) {
+            ("Key", [key]) => Ok(Binding::Key(parse_variant(key)?)),
+            ("Mouse", [button]) => match parse_call(button)? {
+                ("Other", id) if id.len() == 1 => Ok(Binding::Mouse(MouseButton::Other(
+                    id[0].parse().map_err(|_| invalid(s))?,
+                ))),
+                _ => Ok(Binding::Mouse(parse_variant(button)?)),
+            },
+            ("Gamepad", [button]) => Ok(Binding::Gamepad(parse_variant(button)?)),
+            _ => Err(invalid(s)),
+        }
+    }
+}
+
+impl fmt::Display for AxisBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AxisBinding::Buttons { positive, negative } => {
+                write!(f, "Buttons({}, {})", positive, negative)
+            }
+            AxisBinding::Gamepad(axis) => write!(f, "Gamepad({:?})", axis),
+            AxisBinding::MouseX => write!(f, "MouseX"),
+            AxisBinding::MouseY => write!(f, "MouseY"),
+            AxisBinding::Scroll => write!(f, "Scroll"),
+        }
+    }
+}
+
+impl FromStr for AxisBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, arguments) = parse_call(s)?;
+        match (name, arguments.as_slice()) {
+            ("Buttons", [positive, negative]) => Ok(AxisBinding::Buttons {
+                positive: positive.parse()?,
+                negative: negative.parse()?,
+            }),
+            ("Gamepad", [axis]) => Ok(AxisBinding::Gamepad(parse_variant(axis)?)),
+            ("MouseX", []) => Ok(AxisBinding::MouseX),
+            ("MouseY", []) => Ok(AxisBinding::MouseY),
+            ("Scroll", []) => Ok(AxisBinding::Scroll),
+            _ => Err(invalid(s)),
+        }
+    }
+}
+
+impl fmt::Display for Axis2Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Axis2Binding::Buttons {
+                up,
+                down,
+                left,
+                right,
+            } => {
+                write!(f, "Buttons({}, {}, {}, {})", up, down, left, right)
+            }
+            Axis2Binding::Gamepad { x, y } => write!(f, "Gamepad({:?}, {:?})", x, y),
+            Axis2Binding::Mouse => write!(f, "Mouse"),
+        }
+    }
+}
+
+impl FromStr for Axis2Binding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, arguments) = parse_call(s)?;
+        match (name, arguments.as_slice()) {
+            ("Buttons", [up, down, left, right]) => Ok(Axis2Binding::Buttons {
+                up: up.parse()?,
+                down: down.parse()?,
+                left: left.parse()?,
+                right: right.parse()?,
+            }),
+            ("Gamepad", [x, y]) => Ok(Axis2Binding::Gamepad {
+                x: parse_variant(x)?,
+                y: parse_variant(y)?,
+            }),
+            ("Mouse", []) => Ok(Axis2Binding::Mouse),
+            _ => Err(invalid(s)),
+        }
+    }
+}
+
+macro_rules! impl_string_conversion {
+    ($($ty:ty),*) => {
+        $(
+            impl TryFrom<String> for $ty {
+                type Error = String;
+
+                fn try_from(s: String) -> Result<Self, Self::Error> {
+                    s.parse()
+                }
+            }
+
+            impl From<$ty> for String {
+                fn from(value: $ty) -> Self {
+                    value.to_string()
+                }
+            }
+        )*
+    };
+}
+
+impl_string_conversion!(Binding, AxisBinding, Axis2Binding);
+
+/// Splits `Name(a, B(c))` into its name and top level arguments
+fn parse_call(s: &str) -> Result<(&str, Vec<&str>), String> {
+    let s = s.trim();
+    let open = match s.find('(') {
+        Some(open) => open,
+        None => return Ok((s, Vec::new())),
+    };
+
+    if !s.ends_with(')') {
+        return Err(invalid(s));
+    }
+
+    let inner = &s[open + 1..s.len() - 1];
+    let mut arguments = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in inner.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                arguments.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    if !inner.trim().is_empty() {
+        arguments.push(inner[start..].trim());
+    }
+
+    Ok((s[..open].trim(), arguments))
+}
+
+/// Reads a unit enum variant from its name
+fn parse_variant<T>(name: &str) -> Result<T, String>
+where
+    T: DeserializeOwned,
+{
+    use serde::de::{value, IntoDeserializer};
+
+    let deserializer: value::StrDeserializer<value::Error> = name.into_deserializer();
+    T::deserialize(deserializer).map_err(|e| e.to_string())
+}
+
+fn invalid(s: &str) -> String {
+    format!("Invalid binding {:?}", s)
+}
+
+/// Bindings of a named 1D axis, the binding with the largest value wins
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AxisMapping {
+    /// Gamepad values below the dead zone read as 0, larger values are rescaled to start at 0
+    pub dead_zone: f32,
+    pub bindings: Vec<AxisBinding>,
+}
+
+/// Bindings of a named 2D axis, the binding with the longest value wins
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Axis2Mapping {
+    /// Gamepad values with a length below the dead zone read as 0, larger values are rescaled to start at 0
+    pub dead_zone: f32,
+    pub bindings: Vec<Axis2Binding>,
+}
+
+/// File formats an [InputMap] can be stored in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Ron,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// Get the format matching the file extension of a path, `None` if it is neither `.ron` nor `.toml`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "ron" => Some(ConfigFormat::Ron),
+            "toml" => Some(ConfigFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// Maps named actions and axes to bindings, queried against the [Input] of the current frame
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputMap {
+    actions: BTreeMap<String, Vec<Binding>>,
+    axes: BTreeMap<String, AxisMapping>,
+    axes_2d: BTreeMap<String, Axis2Mapping>,
+
+    /// The file the map was loaded from, used for hot-reload
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl InputMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a map from a `.ron` or `.toml` file
+    pub fn load(path: PathBuf) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        let format =
+            ConfigFormat::from_path(&path).ok_or(format!("Unknown input map format {:?}", path))?;
+
+        let mut map = Self::parse(&contents, format)?;
+        map.path = Some(path);
+        Ok(map)
+    }
+
+    /// Saves the map to a `.ron` or `.toml` file
+    pub fn save(&self, path: PathBuf) -> Result<(), String> {
+        let format =
+            ConfigFormat::from_path(&path).ok_or(format!("Unknown input map format {:?}", path))?;
+        let contents = self.serialize(format)?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&path, contents).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+
+    pub fn parse(contents: &str, format: ConfigFormat) -> Result<Self, String> {
+        match format {
+            ConfigFormat::Ron => ron::from_str(contents).map_err(|e| e.to_string()),
+            ConfigFormat::Toml => toml::from_str(contents).map_err(|e| e.to_string()),
+        }
+    }
+
+    pub fn serialize(&self, format: ConfigFormat) -> Result<String, String> {
+        match format {
+            ConfigFormat::Ron => {
+                ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+                    .map_err(|e| e.to_string())
+            }
+            ConfigFormat::Toml => toml::to_string_pretty(self).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Replaces the bindings with the contents of the file the map was loaded from. <br>
+    /// The current bindings are kept if the contents can not be parsed.
+    pub fn reload(&mut self, contents: &[u8]) -> Result<(), String> {
+        let path = self
+            .path
+            .clone()
+            .ok_or("Input map was not loaded from a file")?;
+        let format =
+            ConfigFormat::from_path(&path).ok_or(format!("Unknown input map format {:?}", path))?;
+        let contents = std::str::from_utf8(contents).map_err(|e| e.to_string())?;
+
+        *self = Self::parse(contents, format)?;
+        self.path = Some(path);
+        Ok(())
+    }
+
+    /// Get the file the map was loaded from.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Adds a binding to an action, creating the action if it does not exist
+    pub fn bind(&mut self, action: &str, binding: Binding) {
+        let bindings = self.actions.entry(action.to_string()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Removes a binding from an action
+    pub fn unbind(&mut self, action: &str, binding: &Binding) {
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.retain(|b| b != binding);
+        }
+    }
+
+    /// Replaces all bindings of an action
+    pub fn set_bindings(&mut self, action: &str, bindings: Vec<Binding>) {
+        self.actions.insert(action.to_string(), bindings);
+    }
+
+    /// Get the bindings of an action.
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.actions.get(action).map_or(&[], |b| b.as_slice())
+    }
+
+    /// Get the names of all actions.
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(|k| k.as_str())
+    }
+
+    /// Binds an action to the first button pressed this frame, replacing its bindings. <br>
+    /// Returns true once a button was pressed, call it every frame while waiting for the player.
+    pub fn rebind_from_input(&mut self, action: &str, input: &Input) -> bool {
+        match first_pressed(input) {
+            Some(binding) => {
+                trace!("Rebinding action {} to {:?}", action, binding);
+                self.set_bindings(action, vec![binding]);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn set_axis(&mut self, axis: &str, mapping: AxisMapping) {
+        self.axes.insert(axis.to_string(), mapping);
+    }
+
+    /// Get the mapping of an axis.
+    pub fn axis_mapping(&self, axis: &str) -> Option<&AxisMapping> {
+        self.axes.get(axis)
+    }
+
+    /// Get a mutable reference to the mapping of an axis, used for rebinding.
+    pub fn axis_mapping_mut(&mut self, axis: &str) -> Option<&mut AxisMapping> {
+        self.axes.get_mut(axis)
+    }
+
+    pub fn set_axis_2d(&mut self, axis: &str, mapping: Axis2Mapping) {
+        self.axes_2d.insert(axis.to_string(), mapping);
+    }
+
+    /// Get the mapping of a 2D axis.
+    pub fn axis_2d_mapping(&self, axis: &str) -> Option<&Axis2Mapping> {
+        self.axes_2d.get(axis)
+    }
+
+    /// Get a mutable reference to the mapping of a 2D axis, used for rebinding.
+    pub fn axis_2d_mapping_mut(&mut self, axis: &str) -> Option<&mut Axis2Mapping> {
+        self.axes_2d.get_mut(axis)
+    }
+
+    /// Returns true if any binding of the action went down this frame
+    pub fn pressed(&self, input: &Input, action: &str) -> bool {
+        self.bindings(action)
+            .iter()
+            .any(|b| binding_pressed(input, b))
+    }
+
+    /// Returns true while any binding of the action is down
+    pub fn held(&self, input: &Input, action: &str) -> bool {
+        self.bindings(action).iter().any(|b| binding_held(input, b))
+    }
+
+    /// Returns true if a binding of the action went up this frame and no other binding is held
+    pub fn released(&self, input: &Input, action: &str) -> bool {
+        let bindings = self.bindings(action);
+        bindings.iter().any(|b| binding_released(input, b))
+            && !bindings.iter().any(|b| binding_held(input, b))
+    }
+
+    /// Get the value of an axis, 0 if the axis does not exist.
+    pub fn axis(&self, input: &Input, axis: &str) -> f32 {
+        let mapping = match self.axes.get(axis) {
+            Some(mapping) => mapping,
+            None => return 0.0,
+        };
+
+        mapping
+            .bindings
+            .iter()
+            .map(|binding| match *binding {
+                AxisBinding::Buttons { positive, negative } => {
+                    button_axis(input, &positive, &negative)
+                }
+                AxisBinding::Gamepad(axis) => {
+                    dead_zone(gamepad_axis(input, axis), mapping.dead_zone)
+                }
+                AxisBinding::MouseX => input.cursor_delta().x,
+                AxisBinding::MouseY => -input.cursor_delta().y,
+                AxisBinding::Scroll => input.scroll_delta().y,
+            })
+            .fold(0.0, |a: f32, b: f32| if b.abs() > a.abs() { b } else { a })
+    }
+
+    /// Get the value of a 2D axis, 0 if the axis does not exist.
+    pub fn axis_2d(&self, input: &Input, axis: &str) -> na::Vector2<f32> {
+        let mapping = match self.axes_2d.get(axis) {
+            Some(mapping) => mapping,
+            None => return na::Vector2::zeros(),
+        };
+
+        mapping
+            .bindings
+            .iter()
+            .map(|binding| match *binding {
+                Axis2Binding::Buttons {
+                    up,
+                    down,
+                    left,
+                    right,
+                } => {
+                    let value = na::Vector2::new(
+                        button_axis(input, &right, &left),
+                        button_axis(input, &up, &down),
+                    );
+                    match value.norm() > 1.0 {
+                        true => value.normalize(),
+                        false => value,
+                    }
+                }
+                Axis2Binding::Gamepad { x, y } => {
+                    let value = na::Vector2::new(gamepad_axis(input, x), gamepad_axis(input, y));
+                    let length = value.norm();
+                    match length > 0.0 {
+                        true => value / length * dead_zone(length, mapping.dead_zone),
+                        false => value,
+                    }
+                }
+                Axis2Binding::Mouse => {
+                    na::Vector2::new(input.cursor_delta().x, -input.cursor_delta().y)
+                }
+            })
+            .fold(na::Vector2::zeros(), |a, b| {
+                if b.norm() > a.norm() {
+                    b
+                } else {
+                    a
+                }
+            })
+    }
+}
+
+/// Borrows the input map together with the input of the current frame
+pub struct Actions<'a> {
+    map: &'a InputMap,
+    input: &'a Input,
+}
+
+impl<'a> Actions<'a> {
+    pub fn new(map: &'a InputMap, input: &'a Input) -> Self {
+        Actions { map, input }
+    }
+
+    /// Returns true if any binding of the action went down this frame
+    pub fn pressed(&self, action: &str) -> bool {
+        self.map.pressed(self.input, action)
+    }
+
+    /// Returns true while any binding of the action is down
+    pub fn held(&self, action: &str) -> bool {
+        self.map.held(self.input, action)
+    }
+
+    /// Returns true if the action went up this frame
+    pub fn released(&self, action: &str) -> bool {
+        self.map.released(self.input, action)
+    }
+
+    /// Get the value of an axis.
+    pub fn axis(&self, axis: &str) -> f32 {
+        self.map.axis(self.input, axis)
+    }
+
+    /// Get the value of a 2D axis.
+    pub fn axis_2d(&self, axis: &str) -> na::Vector2<f32> {
+        self.map.axis_2d(self.input, axis)
+    }
+}
+
+fn binding_pressed(input: &Input, binding: &Binding) -> bool {
+    match *binding {
+        Binding::Key(key) => input.key_pressed(key),
+        Binding::Mouse(button) => input.mouse_pressed(button),
+        Binding::Gamepad(button) => input.gamepads().iter().any(|g| g.pressed(button)),
+    }
+}
+
+fn binding_held(input: &Input, binding: &Binding) -> bool {
+    match *binding {
+        Binding::Key(key) => input.key_held(key),
+        Binding::Mouse(button) => input.mouse_held(button),
+        Binding::Gamepad(button) => input.gamepads().iter().any(|g| g.held(button)),
+    }
+}
+
+fn binding_released(input: &Input, binding: &Binding) -> bool {
+    match *binding {
+        Binding::Key(key) => input.key_released(key),
+        Binding::Mouse(button) => input.mouse_released(button),
+        Binding::Gamepad(button) => input.gamepads().iter().any(|g| g.released(button)),
+    }
+}
+
+fn button_axis(input: &Input, positive: &Binding, negative: &Binding) -> f32 {
+    binding_held(input, positive) as i32 as f32 - binding_held(input, negative) as i32 as f32
+}
+
+/// The value of an axis on the gamepad where it is deflected the most
+fn gamepad_axis(input: &Input, axis: GamepadAxis) -> f32 {
+    input
+        .gamepads()
+        .iter()
+        .map(|g| g.axis(axis))
+        .fold(0.0, |a: f32, b: f32| if b.abs() > a.abs() { b } else { a })
+}
+
+fn dead_zone(value: f32, dead_zone: f32) -> f32 {
+    let dead_zone = dead_zone.clamp(0.0, 0.99);
+    match value.abs() < dead_zone {
+        true => 0.0,
+        false => value.signum() * (value.abs() - dead_zone) / (1.0 - dead_zone),
+    }
+}
+
+fn first_pressed(input: &Input) -> Option<Binding> {
+    let key = input.keys.pressed.iter().next().map(|k| Binding::Key(*k));
+    let mouse = || {
+        input
+            .mouse_buttons
+            .pressed
+            .iter()
+            .next()
+            .map(|b| Binding::Mouse(*b))
+    };
+    let gamepad = || {
+        input
+            .gamepads
+            .iter()
+            .find_map(|g| g.buttons.pressed.iter().next())
+            .map(|b| Binding::Gamepad(*b))
+    };
+
+    key.or_else(mouse).or_else(gamepad)
+}
@@ -0,0 +1,40 @@
+use super::InputEvent;
+
+/// Provides input events in addition to the window, such as gamepad backends or recorded input. <br>
+/// Sources are polled at the start of every frame, also when running headless.
+pub trait InputSource {
+    /// Returns the events to apply before the given frame is updated, frames are counted from 0
+    fn poll(&mut self, frame: u64) -> Vec<InputEvent>;
+}
+
+/// Replays a fixed list of events at given frames, used to test input handling without a window
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedInput {
+    events: Vec<(u64, InputEvent)>,
+}
+
+impl ScriptedInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies the event before the given frame is updated
+    pub fn at(mut self, frame: u64, event: InputEvent) -> Self {
+        self.events.push((frame, event));
+        self
+    }
+
+    /// Returns true once every event has been polled
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+impl InputSource for ScriptedInput {
+    fn poll(&mut self, frame: u64) -> Vec<InputEvent> {
+        // Events of skipped frames are applied late rather than dropped
+        let (due, pending) = self.events.drain(..).partition(|(f, _)| *f <= frame);
+        self.events = pending;
+        due.into_iter().map(|(_, event)| event).collect()
+    }
+}
@@ -14,7 +14,17 @@ pub mod renderer;
 
 pub mod assets;
 pub mod color;
+pub mod ecs;
+pub mod input;
+pub mod testing;
+pub mod time;
 
-pub use app::App;
-pub use system::System;
-pub use context::Context;
\ No newline at end of file
+pub use app::{App, AppError};
+pub use system::{Schedule, Stage, System, SystemDescriptor, Systems};
+pub use context::Context;
+pub use glutin::event::VirtualKeyCode;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use memoffset::offset_of;
+}
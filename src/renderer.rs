@@ -1,40 +1,80 @@
-use std::collections::HashMap;
+extern crate nalgebra as na;
+
+use std::{collections::HashMap, path::PathBuf};
 
 use glutin::{window::Window, ContextWrapper, PossiblyCurrent};
 
-use crate::{assets::AssetManager, color::prelude::*};
+use crate::{
+    assets::AssetManager,
+    color::prelude::*,
+    ecs::{Entity, GlobalTransform, Mesh, MeshRenderer, Transform, World},
+};
 use pipeline_info::PipelineInfo;
 
-use self::shader::ShaderProgram;
+use self::buffer::{BindingPoints, BlockLayout, StorageBuffer, UniformBuffer};
+use self::camera::Camera;
+use self::culling::{CullingStats, Frustum};
+use self::render_target::{vertex_array::Vertex, RenderTarget};
+use self::shader::{ShaderError, ShaderProgram};
+use self::texture::image::{Format, Image};
+pub use self::surface::Surface;
 
 type GLWindow = ContextWrapper<PossiblyCurrent, Window>;
 
+pub mod bounds;
 pub mod buffer;
 pub mod camera;
+pub mod culling;
 pub mod pipeline_info;
 pub mod render_target;
 pub mod shader;
+pub mod surface;
 pub mod texture;
 
 pub struct Renderer<'a, const T: usize> {
-    window: GLWindow,
+    surface: Surface,
     plinfo: Option<PipelineInfo<'a>>,
     clear_color: RGBAColor<f32>,
+    camera: Option<Camera>,
+    // Frustum of the camera, computed by the first submit after the camera changed
+    frustum: Option<Frustum>,
+    // Depth convention applied to the context, true for reverse-Z
+    reverse_z: Option<bool>,
+    // Camera entity of the world as last applied by draw_world, a user-set camera is kept until it changes
+    world_camera: Option<(Entity, Camera)>,
+    culling_stats: CullingStats,
 
     // Transfer ownership of data to engine-manager
     asset_manager: AssetManager,
     shader_programs: HashMap<String, ShaderProgram<'a>>,
+    uniform_bindings: BindingPoints,
+    storage_bindings: BindingPoints,
+    // Errors of the last failed reload of each shader program, cleared once it reloads
+    shader_errors: HashMap<String, ShaderError>,
+
+    // Screenshots captured before the next buffer swap
+    pending_screenshots: Vec<PathBuf>,
 }
 
 impl<'a, const T: usize> Renderer<'a, T> {
-    pub fn new(window: GLWindow, asset_manager: AssetManager) -> Self {
+    pub fn new(surface: Surface, asset_manager: AssetManager) -> Self {
         Renderer {
-            window,
+            surface,
             plinfo: None,
             clear_color: (HexColor::<u8>::new(0x131519).rgba() / 255),
+            camera: None,
+            frustum: None,
+            reverse_z: None,
+            world_camera: None,
+            culling_stats: CullingStats::default(),
 
             asset_manager,
             shader_programs: HashMap::new(),
+            uniform_bindings: BindingPoints::new(),
+            storage_bindings: BindingPoints::new(),
+            shader_errors: HashMap::new(),
+
+            pending_screenshots: Vec::new(),
         }
     }
 
@@ -42,19 +82,34 @@ impl<'a, const T: usize> Renderer<'a, T> {
     pub fn awake(&mut self) {
         // Load OpenGL function pointers
         trace!("Loading OpenGL proc addresses");
-        gl::load_with(|symbol| self.window().get_proc_address(symbol));
+        gl::load_with(|symbol| self.surface.get_proc_address(symbol));
         trace!("Finished loading OpenGL proc addresses");
 
-        // Enable OpenGL debug logging
-        unsafe {
-            gl::DebugMessageCallback(
-                Some(opengl_error_handling),
-                0 as *const gl::types::GLvoid,
-            );
+        // Enable OpenGL debug logging, software rasterizers might not expose it
+        if gl::DebugMessageCallback::is_loaded() {
+            unsafe {
+                gl::DebugMessageCallback(
+                    Some(opengl_error_handling),
+                    0 as *const gl::types::GLvoid,
+                );
+            }
         }
 
         // Request pipeline information
-        self.plinfo = Some(PipelineInfo::new(&self.window()));
+        self.plinfo = Some(PipelineInfo::new(&self.surface));
+
+        // Headless surfaces are never resized, so the viewport is assigned once
+        if self.surface.is_headless() {
+            let (width, height) = self.surface.size();
+            unsafe {
+                gl::Viewport(0, 0, width as gl::types::GLint, height as gl::types::GLint);
+            }
+        }
+
+        // Nearer fragments hide farther ones regardless of the order they are drawn in
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+        }
 
         // Set clear color
         self.set_clear_color(self.clear_color);
@@ -75,55 +130,162 @@ impl<'a, const T: usize> Renderer<'a, T> {
         info!("Finished activating renderer");
     }
 
-    /// Trigger clear
+    /// Trigger clear, starts counting culled objects of the new frame. <br>
+    /// The depth state of the camera is applied beforehand, reverse-Z cameras clear the depth to 0.
     pub fn clear(&mut self) {
+        self.culling_stats = CullingStats::default();
+        self.apply_depth_state();
+        unsafe {
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        }
+    }
+
+    /// Resizes the viewport and updates the camera's aspect ratio, called when the window is resized
+    pub fn resize(&mut self, width: u32, height: u32) {
+        trace!("Resizing renderer to {}x{}", width, height);
+        if let Some(window) = self.surface.window() {
+            window.resize(glutin::dpi::PhysicalSize::new(width, height));
+        }
+
         unsafe {
-            gl::Clear(gl::COLOR_BUFFER_BIT);
+            gl::Viewport(0, 0, width as gl::types::GLint, height as gl::types::GLint);
+        }
+
+        if let Some(camera) = self.camera.as_mut() {
+            camera.resize(width, height);
+            self.frustum = None;
         }
     }
 
-    /// Swap buffers
-    pub fn swap_buffers(&self) {
-        self.window()
+    /// Swap buffers, pending screenshots are captured beforehand
+    pub fn swap_buffers(&mut self) {
+        if !self.pending_screenshots.is_empty() {
+            let image = self.capture_frame();
+            for path in self.pending_screenshots.drain(..) {
+                match image.save_png(path.clone()) {
+                    Ok(_) => info!("Saved screenshot to {:?}", path),
+                    Err(e) => warn!("Failed to save screenshot to {:?}: {}", path, e),
+                }
+            }
+        }
+
+        self.surface
             .swap_buffers()
             .expect("Failed to swap buffers");
     }
 
+    /// Reads the current color buffer into an image. <br>
+    /// Must be called before [swap_buffers](Self::swap_buffers) as the back buffer is undefined after swapping.
+    pub fn capture_frame(&self) -> Image {
+        let (width, height) = self.surface.size();
+        let stride = width as usize * 4;
+
+        let mut data = vec![0u8; stride * height as usize];
+        unsafe {
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(
+                0,
+                0,
+                width as gl::types::GLsizei,
+                height as gl::types::GLsizei,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                data.as_mut_ptr() as *mut gl::types::GLvoid,
+            );
+        }
+
+        // OpenGL stores rows bottom to top, images are stored top to bottom
+        let data = data.chunks_exact(stride).rev().flatten().copied().collect();
+
+        Image::new(Format::RGBA8, width, height, data)
+    }
+
+    /// Request a screenshot of the current frame to be saved as a png at the given path. <br>
+    /// The frame is captured right before the buffers are swapped.
+    pub fn request_screenshot(&mut self, path: PathBuf) {
+        trace!("Requested screenshot {:?}", path);
+        self.pending_screenshots.push(path);
+    }
+
+    /// Reloads the resources created from a hot-reloaded asset
     #[cfg(debug_assertions)]
-    pub fn update_editor(&mut self) {
-        let channel = self.asset_manager
-            .channel()
-            .as_ref()
-            .expect("No reload channel has been launched");
-
-        let msg = channel.try_recv();
-        match msg {
-            Ok(asset) => {
-                trace!("Received reload editor event");
-                match asset.1 {
-                    crate::assets::AssetKind::Shader => {
-                        let id = asset.0;
-
-                        // Fetc the asset from the asset manager
-                        let asset = self.asset_manager.asset(&id).unwrap();
-
-                        // In the case of a shader, the identifier is used to identify the shaderprogram to reload
-                        let program = self.shader_programs.get_mut(asset.identifier()).unwrap();
-
-                        // Reload asset inside shader program
-                        program.reload(asset.value());
+    pub fn reload_asset(&mut self, id: &str, kind: crate::assets::AssetKind) {
+        trace!("Received reload editor event");
+        match kind {
+            crate::assets::AssetKind::Shader => {
+                // Programs built from preprocessed sources are rebuilt when any of their files changed, includes too
+                let mut reloaded = Vec::new();
+                for (name, program) in self.shader_programs.iter_mut() {
+                    if program.depends_on(id) {
+                        reloaded.push((name.clone(), program.rebuild(&mut self.asset_manager)));
+                    }
+                }
+
+                if reloaded.is_empty() {
+                    // In the case of a shader, the identifier is used to identify the shaderprogram to reload
+                    let asset = match self.asset_manager.asset(id) {
+                        Some(asset) => asset,
+                        None => {
+                            warn!("Reloaded shader {:?} is not a known asset", id);
+                            return;
+                        }
+                    };
+
+                    let name = asset.identifier().to_string();
+                    match self.shader_programs.get_mut(&name) {
+                        Some(program) => reloaded.push((name, program.reload(asset.value()))),
+                        None => {
+                            warn!("Reloaded shader {:?} belongs to no shader program {:?}", id, name);
+                            return;
+                        }
+                    }
+                }
+
+                for (name, result) in reloaded {
+                    match result {
+                        Ok(_) => {
+                            info!("Reloaded shader program {:?}", name);
+                            self.shader_errors.remove(&name);
+
+                            // Blocks declared for the first time by the new sources have no binding point yet
+                            if let Some(program) = self.shader_programs.get_mut(&name) {
+                                program.bind_blocks(&self.uniform_bindings);
+                                program.bind_storage_blocks(&self.storage_bindings);
+                                for variant in program.variants_mut() {
+                                    variant.bind_blocks(&self.uniform_bindings);
+                                    variant.bind_storage_blocks(&self.storage_bindings);
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            warn!(
+                                "Failed to reload shader program {:?}, keeping the previous program: {}",
+                                name, e
+                            );
+                            self.shader_errors.insert(name, e);
+                        }
                     }
-                    crate::assets::AssetKind::Texture => {}
-                    crate::assets::AssetKind::Video => {}
-                };
+                }
             }
-            Err(_) => {}
-        }
+            crate::assets::AssetKind::Texture => {}
+            crate::assets::AssetKind::Video => {}
+            crate::assets::AssetKind::Config => {}
+        };
+    }
+
+    /// Get the errors of shader programs whose last reload failed, to be shown until the program reloads successfully.
+    pub fn shader_errors(&self) -> &HashMap<String, ShaderError> {
+        &self.shader_errors
     }
 
-    /// Get a reference to the renderer's window.
-    pub fn window(&self) -> &ContextWrapper<PossiblyCurrent, Window> {
-        &self.window
+    /// Get a reference to the renderer's window, `None` if the renderer is headless.
+    pub fn window(&self) -> Option<&ContextWrapper<PossiblyCurrent, Window>> {
+        self.surface.window()
+    }
+
+    /// Get a reference to the renderer's surface.
+    pub fn surface(&self) -> &Surface {
+        &self.surface
     }
 
     /// Get a reference to the renderer's plinfo.
@@ -145,6 +307,168 @@ impl<'a, const T: usize> Renderer<'a, T> {
         }
     }
 
+    /// Set the renderer's camera, its aspect ratio is matched to the surface. <br>
+    /// Reverse-Z cameras switch the depth test to `GREATER` with a `ZERO_TO_ONE` depth range.
+    /// Switching between reverse-Z and regular cameras during a frame clears the depth buffer again,
+    /// as it was cleared for the previous depth test.
+    pub fn set_camera(&mut self, mut camera: Camera) {
+        let (width, height) = self.surface.size();
+        camera.resize(width, height);
+
+        self.camera = Some(camera);
+        self.frustum = None;
+
+        if self.apply_depth_state() {
+            unsafe {
+                gl::Clear(gl::DEPTH_BUFFER_BIT);
+            }
+        }
+    }
+
+    /// Applies the depth range, clear depth and depth test of the camera's depth convention. <br>
+    /// Returns true if the convention changed since it was last applied.
+    fn apply_depth_state(&mut self) -> bool {
+        let reverse_z = self.camera.as_ref().is_some_and(|camera| camera.is_reverse_z());
+        if self.reverse_z == Some(reverse_z) {
+            return false;
+        }
+
+        let (clip_depth, clear_depth, depth_func) = match reverse_z {
+            true => (gl::ZERO_TO_ONE, 0.0, gl::GREATER),
+            false => (gl::NEGATIVE_ONE_TO_ONE, 1.0, gl::LESS),
+        };
+
+        unsafe {
+            // Clip control is core since OpenGL 4.5
+            if gl::ClipControl::is_loaded() {
+                gl::ClipControl(gl::LOWER_LEFT, clip_depth);
+            } else if reverse_z {
+                warn!("Reverse-Z camera requires glClipControl, depth precision will be reduced");
+            }
+            gl::ClearDepth(clear_depth);
+            gl::DepthFunc(depth_func);
+        }
+
+        self.reverse_z = Some(reverse_z);
+        true
+    }
+
+    /// Get a reference to the renderer's camera.
+    pub fn camera(&self) -> Option<&Camera> {
+        self.camera.as_ref()
+    }
+
+    /// Get a mutable reference to the renderer's camera.
+    pub fn camera_mut(&mut self) -> Option<&mut Camera> {
+        self.frustum = None;
+        self.camera.as_mut()
+    }
+
+    /// Draws a render target transformed by `model` unless it lies outside of the camera's frustum. <br>
+    /// Returns true if the target was drawn, without a camera every target is drawn.
+    pub fn submit<'b, VERTEX>(&mut self, target: &RenderTarget<'b, VERTEX>, model: &na::Matrix4<f32>) -> bool
+    where
+        VERTEX: Vertex<'b>,
+    {
+        self.submit_mesh(target, model)
+    }
+
+    /// Draws a mesh transformed by `model` unless it lies outside of the camera's frustum, see [submit](Self::submit)
+    pub fn submit_mesh(&mut self, mesh: &dyn Mesh, model: &na::Matrix4<f32>) -> bool {
+        if self.frustum.is_none() {
+            self.frustum = self.camera.as_ref().map(Camera::frustum);
+        }
+
+        let drawn = match (self.frustum.as_ref(), mesh.bounds()) {
+            (Some(frustum), Some(bounds)) => frustum.intersects_aabb(&bounds.transform(model)),
+            _ => true,
+        };
+
+        match drawn {
+            true => {
+                mesh.draw();
+                self.culling_stats.drawn += 1;
+            }
+            false => self.culling_stats.culled += 1,
+        }
+        drawn
+    }
+
+    /// Draws every visible [MeshRenderer] of the world at its [GlobalTransform], uploading it as the model matrix. <br>
+    /// The first entity with a [Camera] becomes the renderer's camera whenever its camera or transform changes, placed at
+    /// the entity's transform if it has one, its view projection matrix is uploaded to [VIEW_PROJECTION_UNIFORM](shader::VIEW_PROJECTION_UNIFORM).
+    /// Transforms have to be propagated beforehand, entities without a global transform use their local one.
+    pub fn draw_world(&mut self, world: &World) {
+        type CameraQuery<'w> = (&'w Camera, Option<&'w Transform>, Option<&'w GlobalTransform>);
+        if let Some((entity, (camera, transform, global))) = world.query::<CameraQuery>().next() {
+            let mut camera = camera.clone();
+            match (global, transform) {
+                (Some(global), _) => {
+                    camera.set_position(global.translation().into());
+                    camera.set_orientation(global.rotation());
+                }
+                (None, Some(transform)) => {
+                    camera.set_position(transform.translation().into());
+                    camera.set_orientation(transform.rotation());
+                }
+                (None, None) => (),
+            }
+
+            // Cameras set through set_camera or camera_mut stay until the entity's camera or transform changes
+            let unchanged = matches!(&self.world_camera, Some((e, c)) if *e == entity && *c == camera);
+            if !unchanged {
+                self.world_camera = Some((entity, camera.clone()));
+                self.set_camera(camera);
+            }
+        }
+
+        // Uploaded along with the model matrix to every program which declares the uniform
+        let view_projection = self.camera.as_ref().map(|camera| camera.view_projection());
+
+        type MeshQuery<'w> = (&'w Transform, Option<&'w GlobalTransform>, &'w MeshRenderer);
+        for (entity, (transform, global, mesh_renderer)) in world.query::<MeshQuery>() {
+            if !mesh_renderer.visible {
+                continue;
+            }
+
+            let model = match global {
+                Some(global) => *global.matrix(),
+                None => transform.matrix(),
+            };
+
+            match self.shader_programs.get_mut(&mesh_renderer.shader) {
+                Some(program) => {
+                    if let Err(e) = mesh_renderer.mesh.topology().check_program(program) {
+                        warn!("Skipping {:?} drawn with {:?}, {}", entity, mesh_renderer.shader, e);
+                        continue;
+                    }
+                    program.bind();
+                    program.bind_blocks(&self.uniform_bindings);
+                    program.bind_storage_blocks(&self.storage_bindings);
+                    if let Err(e) = program.set_model_matrix(&model) {
+                        warn!("Failed to upload model matrix of {:?}: {}", entity, e);
+                    }
+                    if let Some(view_projection) = &view_projection {
+                        if let Err(e) = program.set_view_projection(view_projection) {
+                            warn!("Failed to upload view projection of {:?}: {}", entity, e);
+                        }
+                    }
+                }
+                None => {
+                    trace!("Skipping {:?}, shader program {:?} does not exist", entity, mesh_renderer.shader);
+                    continue;
+                }
+            }
+
+            self.submit_mesh(mesh_renderer.mesh.as_ref(), &model);
+        }
+    }
+
+    /// Get the amount of targets drawn and culled by [submit](Self::submit) since the last [clear](Self::clear).
+    pub fn culling_stats(&self) -> CullingStats {
+        self.culling_stats
+    }
+
     /// Get a reference to the renderer's clear color.
     pub fn clear_color(&self) -> &RGBAColor<f32> {
         &self.clear_color
@@ -154,6 +478,43 @@ impl<'a, const T: usize> Renderer<'a, T> {
     pub fn shader_programs(&mut self) -> &mut HashMap<String, ShaderProgram<'a>> {
         &mut self.shader_programs
     }
+
+    /// Binds a uniform buffer to the binding point of a named block, every program declaring the block reads from it. <br>
+    /// Programs apply the binding when drawn through [draw_world](Self::draw_world),
+    /// others can apply it with [ShaderProgram::bind_blocks].
+    pub fn bind_uniform_buffer<U>(&mut self, block: &str, buffer: &UniformBuffer<U>)
+    where
+        U: BlockLayout,
+    {
+        let point = self.uniform_bindings.assign(block);
+        buffer.bind_base(point);
+        for program in self.shader_programs.values_mut() {
+            program.bind_blocks(&self.uniform_bindings);
+        }
+    }
+
+    /// Get a reference to the renderer's uniform block binding points.
+    pub fn uniform_bindings(&self) -> &BindingPoints {
+        &self.uniform_bindings
+    }
+
+    /// Binds a storage buffer to the binding point of a named shader storage block. <br>
+    /// Compute programs apply the binding with [ComputeProgram::bind_storage_blocks](shader::ComputeProgram::bind_storage_blocks).
+    pub fn bind_storage_buffer<U>(&mut self, block: &str, buffer: &StorageBuffer<U>)
+    where
+        U: BlockLayout + ?Sized,
+    {
+        let point = self.storage_bindings.assign(block);
+        buffer.bind_base(point);
+        for program in self.shader_programs.values_mut() {
+            program.bind_storage_blocks(&self.storage_bindings);
+        }
+    }
+
+    /// Get a reference to the renderer's shader storage block binding points.
+    pub fn storage_bindings(&self) -> &BindingPoints {
+        &self.storage_bindings
+    }
 }
 
 #[no_mangle]
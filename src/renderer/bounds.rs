@@ -0,0 +1,180 @@
+extern crate nalgebra as na;
+
+use super::render_target::vertex_array::{Vertex, VertexFieldKind};
+
+/// Name of the vertex attribute bounds are computed from by default
+pub const POSITION_ATTRIBUTE: &str = "Position";
+
+/// Axis aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: na::Point3<f32>,
+    pub max: na::Point3<f32>,
+}
+
+impl Aabb {
+    pub fn new(min: na::Point3<f32>, max: na::Point3<f32>) -> Self {
+        Aabb { min, max }
+    }
+
+    /// Creates the smallest box containing all points, `None` if there are no points
+    pub fn from_points<I>(points: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = na::Point3<f32>>,
+    {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+
+        Some(points.fold(Aabb::new(first, first), |aabb, p| Aabb {
+            min: aabb.min.inf(&p),
+            max: aabb.max.sup(&p),
+        }))
+    }
+
+    /// Creates the smallest box containing the positions stored in the attribute `name` of the vertices. <br>
+    /// `None` if there are no vertices or the attribute is not made up of 2 to 4 floats in the first binding.
+    pub fn from_vertices<'a, VERTEX>(vertices: &[VERTEX], name: &str) -> Option<Self>
+    where
+        VERTEX: Vertex<'a>,
+    {
+        Self::from_points(positions(vertices, name)?)
+    }
+
+    /// Get the center of the box.
+    pub fn center(&self) -> na::Point3<f32> {
+        na::center(&self.min, &self.max)
+    }
+
+    /// Get half of the box's size along every axis.
+    pub fn half_extents(&self) -> na::Vector3<f32> {
+        (self.max - self.min) / 2.0
+    }
+
+    /// Get the 8 corners of the box.
+    pub fn corners(&self) -> [na::Point3<f32>; 8] {
+        let (min, max) = (self.min, self.max);
+        [
+            na::Point3::new(min.x, min.y, min.z),
+            na::Point3::new(max.x, min.y, min.z),
+            na::Point3::new(min.x, max.y, min.z),
+            na::Point3::new(max.x, max.y, min.z),
+            na::Point3::new(min.x, min.y, max.z),
+            na::Point3::new(max.x, min.y, max.z),
+            na::Point3::new(min.x, max.y, max.z),
+            na::Point3::new(max.x, max.y, max.z),
+        ]
+    }
+
+    /// Returns true if the point lies inside or on the box
+    pub fn contains(&self, point: &na::Point3<f32>) -> bool {
+        (0..3).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+
+    /// Get the box containing this box after it has been transformed by `matrix`.
+    pub fn transform(&self, matrix: &na::Matrix4<f32>) -> Self {
+        let corners = self.corners();
+        Self::from_points(corners.iter().map(|c| matrix.transform_point(c))).unwrap()
+    }
+
+    /// Get the box containing both boxes.
+    pub fn merge(&self, other: &Aabb) -> Self {
+        Aabb {
+            min: self.min.inf(&other.min),
+            max: self.max.sup(&other.max),
+        }
+    }
+}
+
+/// Bounding sphere
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere {
+    pub center: na::Point3<f32>,
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    pub fn new(center: na::Point3<f32>, radius: f32) -> Self {
+        BoundingSphere { center, radius }
+    }
+
+    /// Creates a sphere around the center of the points' bounding box containing all points, `None` if there are no points
+    pub fn from_points<I>(points: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = na::Point3<f32>>,
+        I::IntoIter: Clone,
+    {
+        let points = points.into_iter();
+        let center = Aabb::from_points(points.clone())?.center();
+        let radius = points.map(|p| na::distance(&center, &p)).fold(0.0, f32::max);
+
+        Some(BoundingSphere { center, radius })
+    }
+
+    /// Creates a sphere containing the positions stored in the attribute `name` of the vertices, see [Aabb::from_vertices]
+    pub fn from_vertices<'a, VERTEX>(vertices: &[VERTEX], name: &str) -> Option<Self>
+    where
+        VERTEX: Vertex<'a>,
+    {
+        Self::from_points(positions(vertices, name)?)
+    }
+
+    /// Returns true if the point lies inside or on the sphere
+    pub fn contains(&self, point: &na::Point3<f32>) -> bool {
+        na::distance(&self.center, point) <= self.radius
+    }
+
+    /// Get the sphere containing this sphere after it has been transformed by `matrix`. <br>
+    /// The radius is scaled by the largest scale of the matrix.
+    pub fn transform(&self, matrix: &na::Matrix4<f32>) -> Self {
+        let scale = (0..3)
+            .map(|i| matrix.fixed_slice::<3, 1>(0, i).norm())
+            .fold(0.0, f32::max);
+
+        BoundingSphere {
+            center: matrix.transform_point(&self.center),
+            radius: self.radius * scale,
+        }
+    }
+}
+
+impl From<Aabb> for BoundingSphere {
+    fn from(aabb: Aabb) -> Self {
+        BoundingSphere {
+            center: aabb.center(),
+            radius: aabb.half_extents().norm(),
+        }
+    }
+}
+
+/// Reads the positions stored in a float attribute of the first binding
+fn positions<'a, 'v, VERTEX>(vertices: &'v [VERTEX], name: &str) -> Option<impl Iterator<Item = na::Point3<f32>> + Clone + 'v>
+where
+    VERTEX: Vertex<'a>,
+{
+    let definer = VERTEX::get_definition();
+    let binding = definer.bindings().next()?;
+    let (offset, field) = binding.field_offsets().find(|(_, field)| *field.name() == name)?;
+
+    if !matches!(field.kind(), VertexFieldKind::Float) || !(2..=4).contains(&field.count()) {
+        warn!("Attribute {} is not a float position, bounds can not be computed", name);
+        return None;
+    }
+
+    let components = field.count().min(3);
+    let size = std::mem::size_of::<VERTEX>();
+    if offset + components * std::mem::size_of::<f32>() > size {
+        return None;
+    }
+
+    Some(vertices.iter().map(move |vertex| {
+        let base = vertex as *const VERTEX as *const u8;
+        let mut position = na::Point3::origin();
+        for i in 0..components {
+            // The offset was checked to lie within the vertex, implementors of Vertex guarantee it holds floats
+            position[i] = unsafe {
+                std::ptr::read_unaligned(base.add(offset + i * std::mem::size_of::<f32>()) as *const f32)
+            };
+        }
+        position
+    }))
+}
@@ -1,7 +1,76 @@
+pub use binding::BindingPoints;
+pub use layout::{BlockLayout, Layout, LayoutEncoder};
+pub use storagebuffer::StorageBuffer;
+pub use uniformbuffer::UniformBuffer;
+
+pub mod binding;
+pub mod layout;
+pub mod storagebuffer;
+pub mod uniformbuffer;
+
 pub trait Buffer {
     /// Bind buffer
     fn bind(&self);
 
     /// Unbind buffer
     fn unbind(&self);
-}
\ No newline at end of file
+}
+
+/// Hints OpenGL how often the contents of a buffer will change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BufferUsage {
+    /// Uploaded once and drawn many times
+    #[default]
+    Static,
+    /// Modified repeatedly and drawn many times
+    Dynamic,
+    /// Modified once and drawn at most a few times
+    Stream,
+}
+
+impl BufferUsage {
+    pub fn get_opengl_enum(&self) -> gl::types::GLenum {
+        match self {
+            BufferUsage::Static => gl::STATIC_DRAW,
+            BufferUsage::Dynamic => gl::DYNAMIC_DRAW,
+            BufferUsage::Stream => gl::STREAM_DRAW,
+        }
+    }
+}
+
+/// Allocates `capacity` elements of storage for a buffer and uploads `data` to the start of it. <br>
+/// Uploads go through `COPY_WRITE_BUFFER` so the element binding of the bound vertex array is left untouched.
+pub(crate) fn allocate<T>(id: gl::types::GLuint, capacity: usize, data: &[T], usage: BufferUsage) {
+    let size = std::mem::size_of::<T>();
+    unsafe {
+        gl::BindBuffer(gl::COPY_WRITE_BUFFER, id);
+        gl::BufferData(
+            gl::COPY_WRITE_BUFFER,
+            (capacity * size) as gl::types::GLsizeiptr,
+            std::ptr::null(),
+            usage.get_opengl_enum(),
+        );
+        gl::BufferSubData(
+            gl::COPY_WRITE_BUFFER,
+            0,
+            std::mem::size_of_val(data) as gl::types::GLsizeiptr,
+            data.as_ptr() as *const gl::types::GLvoid,
+        );
+        gl::BindBuffer(gl::COPY_WRITE_BUFFER, 0);
+    }
+}
+
+/// Uploads `data` into an already allocated buffer starting at element `offset`
+pub(crate) fn sub_data<T>(id: gl::types::GLuint, offset: usize, data: &[T]) {
+    let size = std::mem::size_of::<T>();
+    unsafe {
+        gl::BindBuffer(gl::COPY_WRITE_BUFFER, id);
+        gl::BufferSubData(
+            gl::COPY_WRITE_BUFFER,
+            (offset * size) as gl::types::GLintptr,
+            std::mem::size_of_val(data) as gl::types::GLsizeiptr,
+            data.as_ptr() as *const gl::types::GLvoid,
+        );
+        gl::BindBuffer(gl::COPY_WRITE_BUFFER, 0);
+    }
+}
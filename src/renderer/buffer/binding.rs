@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+
+/// Assigns indexed buffer binding points to block names, so buffers and programs agree on where a block is bound
+#[derive(Debug, Clone, Default)]
+pub struct BindingPoints {
+    points: HashMap<String, gl::types::GLuint>,
+}
+
+impl BindingPoints {
+    pub fn new() -> Self {
+        BindingPoints::default()
+    }
+
+    /// Get the binding point of a block, assigning the next free one if the block has none
+    pub fn assign(&mut self, block: &str) -> gl::types::GLuint {
+        if let Some(point) = self.points.get(block) {
+            return *point;
+        }
+
+        let point = self.points.len() as gl::types::GLuint;
+        self.points.insert(block.to_string(), point);
+        point
+    }
+
+    /// Get the binding point assigned to a block.
+    pub fn get(&self, block: &str) -> Option<gl::types::GLuint> {
+        self.points.get(block).copied()
+    }
+
+    /// Get an iterator over the assigned blocks and their binding points.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, gl::types::GLuint)> {
+        self.points.iter().map(|(block, point)| (block.as_str(), *point))
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
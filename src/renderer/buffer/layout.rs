@@ -0,0 +1,270 @@
+extern crate nalgebra as na;
+
+use crate::color::prelude::{RGBAColor, RGBColor};
+
+pub use engine_derive::BlockLayout;
+
+/// Memory layout of an interface block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Layout {
+    /// Layout of uniform blocks, arrays and structs are aligned to 16 bytes
+    Std140,
+    /// Layout of shader storage blocks, arrays and structs are aligned like their members
+    Std430,
+}
+
+impl Layout {
+    /// Get the alignment and size of a struct from the alignment and size of its members in declaration order.
+    pub fn struct_layout(&self, members: &[(usize, usize)]) -> (usize, usize) {
+        let mut alignment = members.iter().map(|(alignment, _)| *alignment).max().unwrap_or(1);
+        if *self == Layout::Std140 {
+            alignment = round_up(alignment, 16);
+        }
+
+        let end = members
+            .iter()
+            .fold(0, |offset, (alignment, size)| round_up(offset, *alignment) + size);
+        (alignment, round_up(end, alignment))
+    }
+
+    /// Get the alignment and stride of the elements of an array.
+    pub fn array_layout(&self, alignment: usize, size: usize) -> (usize, usize) {
+        let alignment = match self {
+            Layout::Std140 => round_up(alignment, 16),
+            Layout::Std430 => alignment,
+        };
+        (alignment, round_up(size, alignment))
+    }
+}
+
+/// A value which can be written to a uniform or shader storage block. <br>
+/// Structs implement it with `#[derive(BlockLayout)]`, members are written in declaration order.
+pub trait BlockLayout {
+    /// Get the alignment of the value in the given layout.
+    fn alignment(layout: Layout) -> usize;
+
+    /// Get the size of the value in the given layout, including trailing padding.
+    fn size(layout: Layout) -> usize;
+
+    /// Writes the members of the value, the encoder is aligned and padded around it
+    fn encode(&self, encoder: &mut LayoutEncoder);
+}
+
+/// Writes values into a byte buffer following the alignment rules of a layout
+#[derive(Debug, Clone)]
+pub struct LayoutEncoder {
+    layout: Layout,
+    data: Vec<u8>,
+}
+
+impl LayoutEncoder {
+    pub fn new(layout: Layout) -> Self {
+        LayoutEncoder {
+            layout,
+            data: Vec::new(),
+        }
+    }
+
+    /// Encodes a single value into a new buffer
+    pub fn encode<T>(layout: Layout, value: &T) -> Vec<u8>
+    where
+        T: BlockLayout + ?Sized,
+    {
+        let mut encoder = LayoutEncoder::new(layout);
+        encoder.write(value);
+        encoder.finish()
+    }
+
+    /// Aligns and writes a value, returns the offset it was written at
+    pub fn write<T>(&mut self, value: &T) -> usize
+    where
+        T: BlockLayout + ?Sized,
+    {
+        self.align(T::alignment(self.layout));
+        let offset = self.offset();
+        value.encode(self);
+        self.pad_to(offset + T::size(self.layout));
+        offset
+    }
+
+    /// Appends raw bytes without any alignment
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.data.extend_from_slice(bytes);
+    }
+
+    /// Pads the buffer up to the next multiple of `alignment`
+    pub fn align(&mut self, alignment: usize) {
+        self.pad_to(round_up(self.offset(), alignment));
+    }
+
+    /// Pads the buffer with zeroes up to `offset`
+    pub fn pad_to(&mut self, offset: usize) {
+        if offset > self.data.len() {
+            self.data.resize(offset, 0);
+        }
+    }
+
+    /// Get the offset the next value is written at before alignment.
+    pub fn offset(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Get the encoder's layout.
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+fn round_up(value: usize, alignment: usize) -> usize {
+    match value.checked_rem(alignment) {
+        None | Some(0) => value,
+        Some(rest) => value + alignment - rest,
+    }
+}
+
+macro_rules! impl_scalar {
+    ($($ty:ty => |$value:ident| $bytes:expr),* $(,)?) => {
+        $(
+            impl BlockLayout for $ty {
+                fn alignment(_: Layout) -> usize {
+                    4
+                }
+
+                fn size(_: Layout) -> usize {
+                    4
+                }
+
+                fn encode(&self, encoder: &mut LayoutEncoder) {
+                    let $value = *self;
+                    encoder.write_bytes(&$bytes.to_ne_bytes());
+                }
+            }
+        )*
+    };
+}
+
+// Booleans are stored as 32 bit integers
+impl_scalar!(f32 => |v| v, i32 => |v| v, u32 => |v| v, bool => |v| (v as u32));
+
+/// Vectors of 2 components are aligned to twice, vectors of 3 and 4 components to four times their component size
+macro_rules! impl_vector {
+    ($($ty:ty, $components:expr, |$value:ident| $slice:expr);* $(;)?) => {
+        $(
+            impl BlockLayout for $ty {
+                fn alignment(_: Layout) -> usize {
+                    match $components {
+                        2 => 8,
+                        _ => 16,
+                    }
+                }
+
+                fn size(_: Layout) -> usize {
+                    4 * $components
+                }
+
+                fn encode(&self, encoder: &mut LayoutEncoder) {
+                    let $value = self;
+                    for component in $slice.iter() {
+                        component.encode(encoder);
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_vector!(
+    na::Vector2<f32>, 2, |v| v.as_slice();
+    na::Vector3<f32>, 3, |v| v.as_slice();
+    na::Vector4<f32>, 4, |v| v.as_slice();
+    na::Vector2<i32>, 2, |v| v.as_slice();
+    na::Vector3<i32>, 3, |v| v.as_slice();
+    na::Vector4<i32>, 4, |v| v.as_slice();
+    na::Vector2<u32>, 2, |v| v.as_slice();
+    na::Vector3<u32>, 3, |v| v.as_slice();
+    na::Vector4<u32>, 4, |v| v.as_slice();
+    na::Point3<f32>, 3, |v| v.coords.as_slice();
+    RGBColor<f32>, 3, |v| [v.r, v.g, v.b];
+    RGBAColor<f32>, 4, |v| [v.r, v.g, v.b, v.a];
+);
+
+/// Column major matrices are laid out like an array of their column vectors
+macro_rules! impl_matrix {
+    ($($ty:ty, $columns:expr, $column:ty);* $(;)?) => {
+        $(
+            impl BlockLayout for $ty {
+                fn alignment(layout: Layout) -> usize {
+                    <[$column; $columns]>::alignment(layout)
+                }
+
+                fn size(layout: Layout) -> usize {
+                    <[$column; $columns]>::size(layout)
+                }
+
+                fn encode(&self, encoder: &mut LayoutEncoder) {
+                    let columns: Vec<$column> = self.column_iter().map(|column| column.into_owned()).collect();
+                    columns[..].encode(encoder);
+                }
+            }
+        )*
+    };
+}
+
+// GLSL matCxR has C columns of R rows, nalgebra's MatrixRxC has R rows and C columns
+impl_matrix!(
+    na::Matrix2<f32>, 2, na::Vector2<f32>;
+    na::Matrix3<f32>, 3, na::Vector3<f32>;
+    na::Matrix4<f32>, 4, na::Vector4<f32>;
+    na::Matrix3x2<f32>, 2, na::Vector3<f32>;
+    na::Matrix4x2<f32>, 2, na::Vector4<f32>;
+    na::Matrix2x3<f32>, 3, na::Vector2<f32>;
+    na::Matrix4x3<f32>, 3, na::Vector4<f32>;
+    na::Matrix2x4<f32>, 4, na::Vector2<f32>;
+    na::Matrix3x4<f32>, 4, na::Vector3<f32>;
+);
+
+impl<T, const N: usize> BlockLayout for [T; N]
+where
+    T: BlockLayout,
+{
+    fn alignment(layout: Layout) -> usize {
+        layout.array_layout(T::alignment(layout), T::size(layout)).0
+    }
+
+    fn size(layout: Layout) -> usize {
+        layout.array_layout(T::alignment(layout), T::size(layout)).1 * N
+    }
+
+    fn encode(&self, encoder: &mut LayoutEncoder) {
+        self[..].encode(encoder);
+    }
+}
+
+/// Slices are written as runtime sized arrays, the last member of a shader storage block
+impl<T> BlockLayout for [T]
+where
+    T: BlockLayout,
+{
+    fn alignment(layout: Layout) -> usize {
+        <[T; 1]>::alignment(layout)
+    }
+
+    /// Get the stride of one element, the size of the slice depends on its length.
+    fn size(layout: Layout) -> usize {
+        <[T; 1]>::size(layout)
+    }
+
+    fn encode(&self, encoder: &mut LayoutEncoder) {
+        let stride = Self::size(encoder.layout());
+        let start = encoder.offset();
+        for (i, element) in self.iter().enumerate() {
+            encoder.pad_to(start + i * stride);
+            encoder.write(element);
+        }
+        encoder.pad_to(start + self.len() * stride);
+    }
+}
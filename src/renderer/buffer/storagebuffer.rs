@@ -0,0 +1,127 @@
+use std::marker::PhantomData;
+
+use super::{
+    layout::{BlockLayout, Layout, LayoutEncoder},
+    Buffer, BufferUsage,
+};
+
+/// Buffer backing a shader storage block, the value is encoded with the std430 layout. <br>
+/// Slices fill the runtime sized array at the end of a block.
+pub struct StorageBuffer<T>
+where
+    T: BlockLayout + ?Sized,
+{
+    id: gl::types::GLuint,
+    usage: BufferUsage,
+    size: usize,
+    _value: PhantomData<T>,
+}
+
+impl<T> StorageBuffer<T>
+where
+    T: BlockLayout + ?Sized,
+{
+    pub fn new(value: &T, usage: BufferUsage) -> Self {
+        let data = LayoutEncoder::encode(Layout::Std430, value);
+        let sb = Self::with_size(data.len(), usage);
+        super::sub_data(sb.id, 0, &data);
+        sb
+    }
+
+    /// Creates a zeroed buffer of `size` bytes, for data which is only written by shaders
+    pub fn with_size(size: usize, usage: BufferUsage) -> Self {
+        let mut sb = StorageBuffer {
+            id: 0,
+            usage,
+            size,
+            _value: PhantomData,
+        };
+
+        trace!("Initializing storagebuffer of {} bytes", sb.size);
+        unsafe {
+            gl::GenBuffers(1, &mut sb.id);
+        }
+        super::allocate(sb.id, sb.size, &vec![0u8; sb.size], sb.usage);
+
+        sb
+    }
+
+    /// Overwrites the contents of the buffer, the buffer is reallocated if the encoded value is larger
+    pub fn set(&mut self, value: &T) {
+        let data = LayoutEncoder::encode(Layout::Std430, value);
+        match data.len() > self.size {
+            true => {
+                self.size = data.len();
+                super::allocate(self.id, self.size, &data, self.usage);
+            }
+            false => super::sub_data(self.id, 0, &data),
+        }
+    }
+
+    /// Reads the contents of the buffer back, writes of shaders have to be made visible with
+    /// [Barrier::BUFFER_UPDATE](crate::renderer::shader::Barrier::BUFFER_UPDATE) first
+    pub fn read(&self) -> Vec<u8> {
+        let mut data = vec![0u8; self.size];
+        unsafe {
+            gl::BindBuffer(gl::COPY_READ_BUFFER, self.id);
+            gl::GetBufferSubData(
+                gl::COPY_READ_BUFFER,
+                0,
+                self.size as gl::types::GLsizeiptr,
+                data.as_mut_ptr() as *mut gl::types::GLvoid,
+            );
+            gl::BindBuffer(gl::COPY_READ_BUFFER, 0);
+        }
+        data
+    }
+
+    /// Binds the buffer to an indexed shader storage buffer binding point
+    pub fn bind_base(&self, point: gl::types::GLuint) {
+        unsafe {
+            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, point, self.id);
+        }
+    }
+
+    /// Get the storage buffer's id.
+    pub fn id(&self) -> gl::types::GLuint {
+        self.id
+    }
+
+    /// Get the storage buffer's byte size.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Get the storage buffer's usage.
+    pub fn usage(&self) -> BufferUsage {
+        self.usage
+    }
+}
+
+impl<T> Buffer for StorageBuffer<T>
+where
+    T: BlockLayout + ?Sized,
+{
+    fn bind(&self) {
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.id);
+        }
+    }
+
+    fn unbind(&self) {
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, 0);
+        }
+    }
+}
+
+impl<T> Drop for StorageBuffer<T>
+where
+    T: BlockLayout + ?Sized,
+{
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.id);
+        }
+    }
+}
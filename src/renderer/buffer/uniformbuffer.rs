@@ -0,0 +1,96 @@
+use std::marker::PhantomData;
+
+use super::{
+    layout::{BlockLayout, Layout, LayoutEncoder},
+    Buffer, BufferUsage,
+};
+
+/// Buffer backing a uniform block, the value is encoded with the std140 layout
+pub struct UniformBuffer<T>
+where
+    T: BlockLayout,
+{
+    id: gl::types::GLuint,
+    usage: BufferUsage,
+    size: usize,
+    _value: PhantomData<T>,
+}
+
+impl<T> UniformBuffer<T>
+where
+    T: BlockLayout,
+{
+    pub fn new(value: &T, usage: BufferUsage) -> Self {
+        let data = LayoutEncoder::encode(Layout::Std140, value);
+        let mut ub = UniformBuffer {
+            id: 0,
+            usage,
+            size: data.len(),
+            _value: PhantomData,
+        };
+
+        trace!("Initializing uniformbuffer of {} bytes", ub.size);
+        unsafe {
+            gl::GenBuffers(1, &mut ub.id);
+        }
+        super::allocate(ub.id, ub.size, &data, ub.usage);
+
+        ub
+    }
+
+    /// Overwrites the contents of the buffer
+    pub fn set(&mut self, value: &T) {
+        let data = LayoutEncoder::encode(Layout::Std140, value);
+        super::sub_data(self.id, 0, &data);
+    }
+
+    /// Binds the buffer to an indexed uniform buffer binding point
+    pub fn bind_base(&self, point: gl::types::GLuint) {
+        unsafe {
+            gl::BindBufferBase(gl::UNIFORM_BUFFER, point, self.id);
+        }
+    }
+
+    /// Get the uniform buffer's id.
+    pub fn id(&self) -> gl::types::GLuint {
+        self.id
+    }
+
+    /// Get the uniform buffer's byte size.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Get the uniform buffer's usage.
+    pub fn usage(&self) -> BufferUsage {
+        self.usage
+    }
+}
+
+impl<T> Buffer for UniformBuffer<T>
+where
+    T: BlockLayout,
+{
+    fn bind(&self) {
+        unsafe {
+            gl::BindBuffer(gl::UNIFORM_BUFFER, self.id);
+        }
+    }
+
+    fn unbind(&self) {
+        unsafe {
+            gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
+        }
+    }
+}
+
+impl<T> Drop for UniformBuffer<T>
+where
+    T: BlockLayout,
+{
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.id);
+        }
+    }
+}
@@ -1,37 +1,269 @@
 extern crate nalgebra as na;
 
+use super::culling::Frustum;
+
+pub use controller::{CameraController, ControllerInput, FlyController, OrbitController, PanController};
+
+pub mod controller;
+
+/// Largest vertical field of view after zooming, wider angles would flip the projection
+pub const MAX_FOVY: f32 = std::f32::consts::PI - 0.01;
+
+/// Describes how the camera maps view space onto clip space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    Orthographic {
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        znear: f32,
+        zfar: f32,
+    },
+    Perspective {
+        /// Vertical field of view in radians
+        fovy: f32,
+        aspect: f32,
+        znear: f32,
+        zfar: f32,
+    },
+    /// Perspective projection with an infinite far plane, depth is mapped from 1 at znear to 0 at infinity. <br>
+    /// Requires a `ZERO_TO_ONE` clip control and a `GREATER` depth test, see [Renderer::set_camera](super::Renderer::set_camera).
+    ReverseZ {
+        /// Vertical field of view in radians
+        fovy: f32,
+        aspect: f32,
+        znear: f32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Camera {
-    proj: na::Matrix4::<f32>,
-    view: na::Matrix4::<f32>,
+    projection: Projection,
+    zoom: f32,
+    position: na::Point3<f32>,
+    orientation: na::UnitQuaternion<f32>,
+
+    proj: na::Matrix4<f32>,
+    view: na::Matrix4<f32>,
 }
 
 impl Camera {
-    // pub fn new_perspective() -> Camera {
-    //     let proj = na::Matrix4::<f32>::identity();
-    //     let view = na::Matrix4::<f32>::identity();
+    pub fn new(projection: Projection) -> Camera {
+        let mut camera = Camera {
+            projection,
+            zoom: 1.0,
+            position: na::Point3::origin(),
+            orientation: na::UnitQuaternion::identity(),
+            proj: na::Matrix4::identity(),
+            view: na::Matrix4::identity(),
+        };
 
-    //     Camera {
-    //         proj,
-    //         view
-    //     }
-    // }
+        camera.update_projection();
+        camera.update_view();
+        camera
+    }
 
     pub fn ortho(left: f32, right: f32, bottom: f32, top: f32, znear: f32, zfar: f32) -> Camera {
-        let mut proj = na::Matrix4::<f32>::identity();
-        let two: f32 = num::cast(2.0).unwrap();
-        proj[(0, 0)] = two / (right - left);
-        proj[(1, 1)] = two / (top - bottom);
-        proj[(2, 2)] = -two / (zfar - znear);
-        proj[(0, 3)] = -(right + left) / (right - left);
-        proj[(1, 3)] = -(top + bottom) / (top - bottom);
-        proj[(2, 3)] = -(zfar + znear) / (zfar - znear);
-        proj[(3, 3)] = 1.0;
-
-        let view = na::Matrix4::<f32>::identity();
-
-        Camera {
-            proj,
-            view
+        Camera::new(Projection::Orthographic {
+            left,
+            right,
+            bottom,
+            top,
+            znear,
+            zfar,
+        })
+    }
+
+    /// Creates a perspective camera, `fovy` is the vertical field of view in radians
+    pub fn perspective(aspect: f32, fovy: f32, znear: f32, zfar: f32) -> Camera {
+        Camera::new(Projection::Perspective {
+            fovy,
+            aspect,
+            znear,
+            zfar,
+        })
+    }
+
+    /// Creates a reverse-Z perspective camera with an infinite far plane, `fovy` is the vertical field of view in radians
+    pub fn reverse_z(aspect: f32, fovy: f32, znear: f32) -> Camera {
+        Camera::new(Projection::ReverseZ { fovy, aspect, znear })
+    }
+
+    /// Moves the camera to `eye` and rotates it to face `target`
+    pub fn look_at(&mut self, eye: na::Point3<f32>, target: na::Point3<f32>, up: na::Vector3<f32>) {
+        let view = na::Isometry3::look_at_rh(&eye, &target, &up);
+        self.position = eye;
+        self.orientation = view.rotation.inverse();
+        self.update_view();
+    }
+
+    /// Set the camera's position in world space.
+    pub fn set_position(&mut self, position: na::Point3<f32>) {
+        self.position = position;
+        self.update_view();
+    }
+
+    /// Get the camera's position in world space.
+    pub fn position(&self) -> na::Point3<f32> {
+        self.position
+    }
+
+    /// Set the camera's orientation, the camera looks along -Z of its orientation.
+    pub fn set_orientation(&mut self, orientation: na::UnitQuaternion<f32>) {
+        self.orientation = orientation;
+        self.update_view();
+    }
+
+    /// Get the camera's orientation.
+    pub fn orientation(&self) -> na::UnitQuaternion<f32> {
+        self.orientation
+    }
+
+    /// Get the direction the camera is looking in.
+    pub fn forward(&self) -> na::Vector3<f32> {
+        self.orientation * -na::Vector3::z()
+    }
+
+    /// Get the camera's right direction.
+    pub fn right(&self) -> na::Vector3<f32> {
+        self.orientation * na::Vector3::x()
+    }
+
+    /// Get the camera's up direction.
+    pub fn up(&self) -> na::Vector3<f32> {
+        self.orientation * na::Vector3::y()
+    }
+
+    /// Set the camera's projection.
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+        self.update_projection();
+    }
+
+    /// Get the camera's projection.
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    /// Returns true if the camera uses a reverse-Z projection
+    pub fn is_reverse_z(&self) -> bool {
+        matches!(self.projection, Projection::ReverseZ { .. })
+    }
+
+    /// Get the camera's aspect ratio.
+    pub fn aspect(&self) -> f32 {
+        match self.projection {
+            Projection::Orthographic { left, right, bottom, top, .. } => (right - left) / (top - bottom),
+            Projection::Perspective { aspect, .. } | Projection::ReverseZ { aspect, .. } => aspect,
+        }
+    }
+
+    /// Set the camera's aspect ratio. <br>
+    /// Orthographic projections keep their height and center and adjust their width.
+    pub fn set_aspect(&mut self, new_aspect: f32) {
+        match &mut self.projection {
+            Projection::Orthographic { left, right, bottom, top, .. } => {
+                let center = (*left + *right) / 2.0;
+                let half_width = (*top - *bottom) * new_aspect / 2.0;
+                *left = center - half_width;
+                *right = center + half_width;
+            }
+            Projection::Perspective { aspect, .. } | Projection::ReverseZ { aspect, .. } => {
+                *aspect = new_aspect;
+            }
+        }
+        self.update_projection();
+    }
+
+    /// Updates the aspect ratio to match a surface of the given size, ignored if either side is 0
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.set_aspect(width as f32 / height as f32);
+    }
+
+    /// Set the camera's zoom, orthographic extents and the field of view are divided by it.
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom.max(f32::EPSILON);
+        self.update_projection();
+    }
+
+    /// Get the camera's zoom.
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Get the vertical field of view divided by the zoom, clamped to [MAX_FOVY]. <br>
+    /// Returns `None` for orthographic projections.
+    pub fn fovy(&self) -> Option<f32> {
+        match self.projection {
+            Projection::Orthographic { .. } => None,
+            Projection::Perspective { fovy, .. } | Projection::ReverseZ { fovy, .. } => {
+                Some((fovy / self.zoom).min(MAX_FOVY))
+            }
         }
     }
-}
\ No newline at end of file
+
+    /// Get a reference to the camera's view matrix.
+    pub fn view(&self) -> &na::Matrix4<f32> {
+        &self.view
+    }
+
+    /// Get a reference to the camera's projection matrix.
+    pub fn projection_matrix(&self) -> &na::Matrix4<f32> {
+        &self.proj
+    }
+
+    /// Get the camera's combined view-projection matrix.
+    pub fn view_projection(&self) -> na::Matrix4<f32> {
+        self.proj * self.view
+    }
+
+    /// Get the frustum of the camera's view-projection in world space.
+    pub fn frustum(&self) -> Frustum {
+        Frustum::from_matrix(&self.view_projection(), self.is_reverse_z())
+    }
+
+    fn update_view(&mut self) {
+        let transform = na::Isometry3::from_parts(self.position.coords.into(), self.orientation);
+        self.view = transform.inverse().to_homogeneous();
+    }
+
+    fn update_projection(&mut self) {
+        self.proj = match self.projection {
+            Projection::Orthographic { left, right, bottom, top, znear, zfar } => {
+                let (cx, cy) = ((left + right) / 2.0, (bottom + top) / 2.0);
+                let (hw, hh) = ((right - left) / 2.0 / self.zoom, (top - bottom) / 2.0 / self.zoom);
+                ortho_matrix(cx - hw, cx + hw, cy - hh, cy + hh, znear, zfar)
+            }
+            Projection::Perspective { aspect, znear, zfar, .. } => {
+                na::Perspective3::new(aspect, self.fovy().unwrap(), znear, zfar).to_homogeneous()
+            }
+            Projection::ReverseZ { aspect, znear, .. } => {
+                let f = 1.0 / (self.fovy().unwrap() / 2.0).tan();
+
+                let mut proj = na::Matrix4::zeros();
+                proj[(0, 0)] = f / aspect;
+                proj[(1, 1)] = f;
+                proj[(2, 3)] = znear;
+                proj[(3, 2)] = -1.0;
+                proj
+            }
+        };
+    }
+}
+
+fn ortho_matrix(left: f32, right: f32, bottom: f32, top: f32, znear: f32, zfar: f32) -> na::Matrix4<f32> {
+    let mut proj = na::Matrix4::<f32>::identity();
+    let two: f32 = num::cast(2.0).unwrap();
+    proj[(0, 0)] = two / (right - left);
+    proj[(1, 1)] = two / (top - bottom);
+    proj[(2, 2)] = -two / (zfar - znear);
+    proj[(0, 3)] = -(right + left) / (right - left);
+    proj[(1, 3)] = -(top + bottom) / (top - bottom);
+    proj[(2, 3)] = -(zfar + znear) / (zfar - znear);
+    proj[(3, 3)] = 1.0;
+    proj
+}
@@ -0,0 +1,187 @@
+extern crate nalgebra as na;
+
+use crate::input::{Input, MouseButton, VirtualKeyCode};
+
+use super::Camera;
+
+/// Input consumed by camera controllers for a single frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ControllerInput {
+    /// Movement along the camera's right, up and forward axes, usually in `-1..1`
+    pub movement: na::Vector3<f32>,
+    /// Rotation in pixels, usually the mouse delta
+    pub look: na::Vector2<f32>,
+    /// Panning in pixels, usually the mouse delta while a button is held
+    pub pan: na::Vector2<f32>,
+    /// Zoom steps, usually the scroll wheel delta
+    pub zoom: f32,
+}
+
+impl Default for ControllerInput {
+    fn default() -> Self {
+        ControllerInput {
+            movement: na::Vector3::zeros(),
+            look: na::Vector2::zeros(),
+            pan: na::Vector2::zeros(),
+            zoom: 0.0,
+        }
+    }
+}
+
+impl ControllerInput {
+    /// Reads the default bindings, WASD moves, space and left shift move up and down,
+    /// the mouse looks around, dragging with the right mouse button pans and scrolling zooms
+    pub fn from_input(input: &Input) -> Self {
+        let axis = |positive, negative| {
+            input.key_held(positive) as i32 as f32 - input.key_held(negative) as i32 as f32
+        };
+
+        let drag = match input.mouse_held(MouseButton::Right) {
+            true => input.cursor_delta(),
+            false => na::Vector2::zeros(),
+        };
+
+        ControllerInput {
+            movement: na::Vector3::new(
+                axis(VirtualKeyCode::D, VirtualKeyCode::A),
+                axis(VirtualKeyCode::Space, VirtualKeyCode::LShift),
+                axis(VirtualKeyCode::W, VirtualKeyCode::S),
+            ),
+            look: input.cursor_delta() - drag,
+            pan: drag,
+            zoom: input.scroll_delta().y,
+        }
+    }
+}
+
+/// Moves a camera from input, `delta` is the frame time in seconds
+pub trait CameraController {
+    fn update(&mut self, camera: &mut Camera, input: &ControllerInput, delta: f32);
+}
+
+/// First person camera, moving along the camera's axes and rotating with yaw and pitch
+pub struct FlyController {
+    pub yaw: f32,
+    pub pitch: f32,
+    /// Movement in units per second
+    pub speed: f32,
+    /// Rotation in radians per pixel
+    pub sensitivity: f32,
+}
+
+impl Default for FlyController {
+    fn default() -> Self {
+        FlyController {
+            yaw: 0.0,
+            pitch: 0.0,
+            speed: 5.0,
+            sensitivity: 0.002,
+        }
+    }
+}
+
+impl CameraController for FlyController {
+    fn update(&mut self, camera: &mut Camera, input: &ControllerInput, delta: f32) {
+        let limit = std::f32::consts::FRAC_PI_2 - 0.01;
+        self.yaw -= input.look.x * self.sensitivity;
+        self.pitch = (self.pitch - input.look.y * self.sensitivity).clamp(-limit, limit);
+
+        let orientation = na::UnitQuaternion::from_axis_angle(&na::Vector3::y_axis(), self.yaw)
+            * na::UnitQuaternion::from_axis_angle(&na::Vector3::x_axis(), self.pitch);
+
+        let movement = na::Vector3::new(input.movement.x, input.movement.y, -input.movement.z);
+        let position = camera.position() + orientation * movement * self.speed * delta;
+
+        camera.set_orientation(orientation);
+        camera.set_position(position);
+    }
+}
+
+/// Rotates the camera around a target and zooms towards it
+pub struct OrbitController {
+    pub target: na::Point3<f32>,
+    pub distance: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+    /// Rotation in radians per pixel
+    pub sensitivity: f32,
+    /// Fraction of the distance moved per zoom step
+    pub zoom_speed: f32,
+}
+
+impl Default for OrbitController {
+    fn default() -> Self {
+        OrbitController {
+            target: na::Point3::origin(),
+            distance: 10.0,
+            min_distance: 0.1,
+            max_distance: 1000.0,
+            yaw: 0.0,
+            pitch: 0.0,
+            sensitivity: 0.005,
+            zoom_speed: 0.1,
+        }
+    }
+}
+
+impl CameraController for OrbitController {
+    fn update(&mut self, camera: &mut Camera, input: &ControllerInput, _delta: f32) {
+        let limit = std::f32::consts::FRAC_PI_2 - 0.01;
+        self.yaw -= input.look.x * self.sensitivity;
+        self.pitch = (self.pitch - input.look.y * self.sensitivity).clamp(-limit, limit);
+
+        self.distance = (self.distance * (1.0 - input.zoom * self.zoom_speed))
+            .clamp(self.min_distance, self.max_distance);
+
+        let rotation = na::UnitQuaternion::from_axis_angle(&na::Vector3::y_axis(), self.yaw)
+            * na::UnitQuaternion::from_axis_angle(&na::Vector3::x_axis(), self.pitch);
+        let eye = self.target + rotation * na::Vector3::new(0.0, 0.0, self.distance);
+
+        camera.look_at(eye, self.target, na::Vector3::y());
+    }
+}
+
+/// Pans a 2D camera in its view plane and zooms with the camera's zoom
+pub struct PanController {
+    /// Movement in units per second
+    pub speed: f32,
+    /// Movement in units per pixel of panning at zoom 1
+    pub drag_speed: f32,
+    /// Zoom factor applied per zoom step
+    pub zoom_speed: f32,
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+}
+
+impl Default for PanController {
+    fn default() -> Self {
+        PanController {
+            speed: 5.0,
+            drag_speed: 0.01,
+            zoom_speed: 0.1,
+            min_zoom: 0.1,
+            max_zoom: 10.0,
+        }
+    }
+}
+
+impl CameraController for PanController {
+    fn update(&mut self, camera: &mut Camera, input: &ControllerInput, delta: f32) {
+        let zoom = camera.zoom();
+
+        // Dragging moves the view with the cursor, so the world moves against the pan direction
+        let offset = na::Vector2::new(input.movement.x, input.movement.y) * self.speed * delta
+            + na::Vector2::new(-input.pan.x, input.pan.y) * self.drag_speed;
+        let offset = offset / zoom;
+
+        let position = camera.position() + camera.right() * offset.x + camera.up() * offset.y;
+        camera.set_position(position);
+
+        if input.zoom != 0.0 {
+            let zoom = zoom * (1.0 + self.zoom_speed).powf(input.zoom);
+            camera.set_zoom(zoom.clamp(self.min_zoom, self.max_zoom));
+        }
+    }
+}
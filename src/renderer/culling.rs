@@ -0,0 +1,168 @@
+extern crate nalgebra as na;
+
+use super::{
+    bounds::{Aabb, BoundingSphere},
+    camera::Camera,
+    render_target::{vertex_array::Vertex, RenderTarget},
+};
+
+/// A plane `normal · p + distance = 0`, points on the side of the normal are inside
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    pub normal: na::Vector3<f32>,
+    pub distance: f32,
+}
+
+impl Plane {
+    /// Creates a plane from the coefficients `ax + by + cz + d`, normalized so distances are in world units. <br>
+    /// Degenerate planes, such as the far plane of an infinite projection, contain every point.
+    pub fn from_coefficients(coefficients: na::Vector4<f32>) -> Self {
+        let normal = coefficients.xyz();
+        let length = normal.norm();
+
+        if length <= f32::EPSILON {
+            return Plane {
+                normal: na::Vector3::zeros(),
+                distance: f32::INFINITY,
+            };
+        }
+
+        Plane {
+            normal: normal / length,
+            distance: coefficients.w / length,
+        }
+    }
+
+    /// Get the signed distance from the plane to a point, negative if the point is outside.
+    pub fn signed_distance(&self, point: &na::Point3<f32>) -> f32 {
+        self.normal.dot(&point.coords) + self.distance
+    }
+}
+
+/// The six planes enclosing the volume visible to a camera
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    /// Left, right, bottom, top, near and far plane
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Extracts the planes of a view-projection matrix. <br>
+    /// `zero_to_one` selects a clip space depth of `0..1` instead of OpenGL's `-1..1`.
+    pub fn from_matrix(matrix: &na::Matrix4<f32>, zero_to_one: bool) -> Self {
+        let row = |i: usize| matrix.row(i).transpose();
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+
+        let near = match zero_to_one {
+            true => z,
+            false => w + z,
+        };
+
+        Frustum {
+            planes: [
+                Plane::from_coefficients(w + x),
+                Plane::from_coefficients(w - x),
+                Plane::from_coefficients(w + y),
+                Plane::from_coefficients(w - y),
+                Plane::from_coefficients(near),
+                Plane::from_coefficients(w - z),
+            ],
+        }
+    }
+
+    /// Returns true if the point lies inside the frustum
+    pub fn contains_point(&self, point: &na::Point3<f32>) -> bool {
+        self.planes.iter().all(|p| p.signed_distance(point) >= 0.0)
+    }
+
+    /// Returns true if the sphere lies at least partially inside the frustum
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
+        self.planes
+            .iter()
+            .all(|p| p.signed_distance(&sphere.center) >= -sphere.radius)
+    }
+
+    /// Returns true if the box might lie inside the frustum. <br>
+    /// Boxes close to the frustum's corners can be reported as visible although they are not.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.planes.iter().all(|p| {
+            // The corner furthest along the plane's normal
+            let corner = na::Point3::new(
+                if p.normal.x >= 0.0 { aabb.max.x } else { aabb.min.x },
+                if p.normal.y >= 0.0 { aabb.max.y } else { aabb.min.y },
+                if p.normal.z >= 0.0 { aabb.max.z } else { aabb.min.z },
+            );
+            p.signed_distance(&corner) >= 0.0
+        })
+    }
+}
+
+/// Amount of objects drawn and skipped by a [CullingPass]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CullingStats {
+    pub drawn: usize,
+    pub culled: usize,
+}
+
+impl CullingStats {
+    /// Get the amount of tested objects.
+    pub fn total(&self) -> usize {
+        self.drawn + self.culled
+    }
+}
+
+/// Tests objects against a frustum and counts visible and culled objects
+pub struct CullingPass {
+    frustum: Frustum,
+    stats: CullingStats,
+}
+
+impl CullingPass {
+    pub fn new(frustum: Frustum) -> Self {
+        CullingPass {
+            frustum,
+            stats: CullingStats::default(),
+        }
+    }
+
+    pub fn from_camera(camera: &Camera) -> Self {
+        Self::new(camera.frustum())
+    }
+
+    /// Returns true if the bounds transformed by `model` are visible and counts the result. <br>
+    /// Objects without bounds are always visible.
+    pub fn test(&mut self, bounds: Option<&Aabb>, model: &na::Matrix4<f32>) -> bool {
+        let visible = match bounds {
+            Some(bounds) => self.frustum.intersects_aabb(&bounds.transform(model)),
+            None => true,
+        };
+
+        match visible {
+            true => self.stats.drawn += 1,
+            false => self.stats.culled += 1,
+        }
+        visible
+    }
+
+    /// Draws the render target if its bounds transformed by `model` are visible, returns true if it was drawn
+    pub fn draw<'a, VERTEX>(&mut self, target: &RenderTarget<'a, VERTEX>, model: &na::Matrix4<f32>) -> bool
+    where
+        VERTEX: Vertex<'a>,
+    {
+        let visible = self.test(target.bounds(), model);
+        if visible {
+            target.draw();
+        }
+        visible
+    }
+
+    /// Get a reference to the culling pass's frustum.
+    pub fn frustum(&self) -> &Frustum {
+        &self.frustum
+    }
+
+    /// Get the culling pass's stats.
+    pub fn stats(&self) -> CullingStats {
+        self.stats
+    }
+}
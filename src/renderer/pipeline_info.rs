@@ -1,23 +1,27 @@
 use std::ffi::CStr;
 
-use super::GLWindow;
+use super::Surface;
 
 #[derive(Debug, Clone)]
 pub struct PipelineInfo<'a> {
     version: Option<Box<&'a str>>,
     vendor: Option<Box<&'a str>>,
     renderer: Option<Box<&'a str>>,
+    major_version: gl::types::GLint,
+    minor_version: gl::types::GLint,
     max_vertex_attribs: gl::types::GLint,
     max_texture_image_units: gl::types::GLint,
     max_uniform_locations: gl::types::GLint,
 }
 
 impl<'a> PipelineInfo<'a> {
-    pub fn new(_: &GLWindow) -> PipelineInfo<'a> {
+    pub fn new(_: &Surface) -> PipelineInfo<'a> {
         let mut plinfo = PipelineInfo {
             version: None,
             vendor: None,
             renderer: None,
+            major_version: 0,
+            minor_version: 0,
             max_vertex_attribs: 0,
             max_texture_image_units: 0,
             max_uniform_locations: 0
@@ -35,6 +39,11 @@ impl<'a> PipelineInfo<'a> {
         let version = unsafe { gl::GetString(gl::VERSION) };
         let version: &CStr = unsafe { CStr::from_ptr(version as *const i8) };
         self.version = Some(Box::new(version.to_str().unwrap()));
+
+        unsafe {
+            gl::GetIntegerv(gl::MAJOR_VERSION, &mut self.major_version);
+            gl::GetIntegerv(gl::MINOR_VERSION, &mut self.minor_version);
+        }
     }
 
     /// Requests vendor and renderer information from OpenGL
@@ -64,6 +73,22 @@ impl<'a> PipelineInfo<'a> {
         self.version.as_ref().unwrap()
     }
 
+    /// Get the major and minor OpenGL version of the context.
+    /// Requires that [load_version](Self::load_version) has been called in advance
+    pub fn gl_version(&self) -> (gl::types::GLint, gl::types::GLint) {
+        (self.major_version, self.minor_version)
+    }
+
+    /// Returns true if the context supports at least the given OpenGL version
+    pub fn supports_version(&self, major: gl::types::GLint, minor: gl::types::GLint) -> bool {
+        self.gl_version() >= (major, minor)
+    }
+
+    /// Returns true if instanced draws can start at a base instance, requires OpenGL 4.2
+    pub fn supports_base_instance(&self) -> bool {
+        self.supports_version(4, 2) && gl::DrawElementsInstancedBaseInstance::is_loaded()
+    }
+
     /// Get a reference to the pipeline info's vendor.
     /// Requires that [load_version](Self::load_vendor) has been called in advance
     pub fn vendor(&self) -> &Box<&'a str> {
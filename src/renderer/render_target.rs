@@ -1,35 +1,225 @@
-use self::vertex_array::{VertexArrayObject, Vertex};
+use std::ops::Range;
 
+use self::vertex_array::{dynamicvertexbuffer::DynamicVertexBuffer, VertexArrayObject, Vertex};
+use super::{
+    bounds::{Aabb, POSITION_ATTRIBUTE},
+    buffer::BufferUsage,
+    pipeline_info::PipelineInfo,
+};
+
+pub use topology::PrimitiveTopology;
+
+pub mod topology;
 pub mod vertex_array;
 
 // Contains data for something to render
-pub struct RenderTarget<'a, VERTEX, const V: usize, const I: usize> where VERTEX: Vertex<'a> {
-    vertex_array: VertexArrayObject<'a, VERTEX, V, I>,
+pub struct RenderTarget<'a, VERTEX> where VERTEX: Vertex<'a> {
+    vertex_array: VertexArrayObject<'a, VERTEX>,
+    topology: PrimitiveTopology,
+    bounds: Option<Aabb>,
 }
 
-impl<'a, VERTEX, const V: usize, const I: usize> RenderTarget<'a, VERTEX, V, I> where VERTEX: Vertex<'a> {
-    pub fn new(vertices: [VERTEX; V], indices: [u32; I]) -> Self {
+impl<'a, VERTEX> RenderTarget<'a, VERTEX> where VERTEX: Vertex<'a> {
+    /// Creates a render target from vertices and indices which are uploaded once
+    pub fn new(vertices: impl Into<Vec<VERTEX>>, indices: impl Into<Vec<u32>>) -> Self {
+        Self::with_usage(vertices, indices, BufferUsage::Static)
+    }
+
+    /// Creates a render target whose buffers are allocated with the given usage hint
+    pub fn with_usage(vertices: impl Into<Vec<VERTEX>>, indices: impl Into<Vec<u32>>, usage: BufferUsage) -> Self {
         trace!("Initializing new render target");
-        let va = VertexArrayObject::new("RenderTarget", vertices, indices);
+        let va = VertexArrayObject::new("RenderTarget", vertices.into(), Some(indices.into()), usage);
 
+        let bounds = Aabb::from_vertices(va.vertex_buffer().vertices(), POSITION_ATTRIBUTE);
         RenderTarget {
             vertex_array: va,
+            topology: PrimitiveTopology::default(),
+            bounds,
         }
     }
 
+    /// Creates a render target without indices, vertices are drawn in the order they are stored
+    pub fn non_indexed(vertices: impl Into<Vec<VERTEX>>, usage: BufferUsage) -> Self {
+        trace!("Initializing new non-indexed render target");
+        let va = VertexArrayObject::new("RenderTarget", vertices.into(), None, usage);
+
+        let bounds = Aabb::from_vertices(va.vertex_buffer().vertices(), POSITION_ATTRIBUTE);
+        RenderTarget {
+            vertex_array: va,
+            topology: PrimitiveTopology::default(),
+            bounds,
+        }
+    }
+
+    /// Set the topology the render target is drawn with
+    pub fn with_topology(mut self, topology: PrimitiveTopology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Draws all elements of the render target
     pub fn draw(&self) {
+        self.draw_range(0..self.vertex_array.element_count());
+    }
+
+    /// Draws a range of the render target's elements, indices if it is indexed and vertices otherwise. <br>
+    /// The range is clamped to the amount of elements, nothing is drawn if the topology can not be applied.
+    pub fn draw_range(&self, range: Range<usize>) {
+        if let Err(e) = self.draw_elements(range, None) {
+            warn!("Skipping draw of vertex array {}, {}", self.vertex_array.identifier(), e);
+        }
+    }
+
+    /// Uploads per-instance data, the layout of INSTANCE is appended after the attributes of VERTEX. <br>
+    /// The instance buffer is reused if it has the same usage, otherwise a new buffer is allocated. <br>
+    /// Shaders drawing instances have to be created with [ShaderProgram::new_instanced](crate::renderer::shader::ShaderProgram::new_instanced).
+    pub fn set_instances<INSTANCE>(&mut self, instances: Vec<INSTANCE>, usage: BufferUsage) -> Result<(), String>
+    where
+        INSTANCE: Vertex<'static> + 'static,
+    {
+        if let Some(buffer) = self.vertex_array.instance_buffer_mut::<INSTANCE>() {
+            if buffer.usage() == usage {
+                buffer.set(instances);
+                return Ok(());
+            }
+        }
+
+        self.vertex_array
+            .attach_instance_buffer(DynamicVertexBuffer::new(instances, usage))
+    }
+
+    /// Get a mutable reference to the per-instance buffer, used for updating instances every frame.
+    pub fn instances_mut<INSTANCE>(&mut self) -> Option<&mut DynamicVertexBuffer<'static, INSTANCE>>
+    where
+        INSTANCE: Vertex<'static> + 'static,
+    {
+        self.vertex_array.instance_buffer_mut()
+    }
+
+    /// Get the amount of uploaded instances, 0 if no instances have been set.
+    pub fn instance_count(&self) -> usize {
+        self.vertex_array.instance_count().unwrap_or(0)
+    }
+
+    /// Draws all elements once for every uploaded instance
+    pub fn draw_instanced(&self) {
+        self.draw_instanced_range(0..self.vertex_array.element_count(), self.instance_count());
+    }
+
+    /// Draws a range of the render target's elements instances times. <br>
+    /// The range is clamped to the amount of elements, nothing is drawn if the topology can not be applied.
+    pub fn draw_instanced_range(&self, range: Range<usize>, instances: usize) {
+        if let Err(e) = self.draw_elements(range, Some((instances, 0))) {
+            warn!("Skipping draw of vertex array {}, {}", self.vertex_array.identifier(), e);
+        }
+    }
+
+    /// Draws a range of the render target's elements for the instances starting at `base_instance`. <br>
+    /// Fails if the context does not [support base instances](PipelineInfo::supports_base_instance),
+    /// a base instance of 0 is always supported.
+    pub fn draw_instanced_base(
+        &self,
+        plinfo: &PipelineInfo,
+        range: Range<usize>,
+        instances: usize,
+        base_instance: u32,
+    ) -> Result<(), String> {
+        if base_instance != 0 && !plinfo.supports_base_instance() {
+            let (major, minor) = plinfo.gl_version();
+            return Err(format!(
+                "Drawing from base instance {} requires OpenGL 4.2, the context is {}.{}",
+                base_instance, major, minor
+            ));
+        }
+
+        self.draw_elements(range, Some((instances, base_instance)))
+    }
+
+    /// Issues the draw call for a range of elements, `instances` holds the amount of instances and the base instance
+    /// of instanced draws. <br>
+    /// The range is clamped to the amount of elements, empty ranges and draws without instances are skipped.
+    fn draw_elements(&self, range: Range<usize>, instances: Option<(usize, u32)>) -> Result<(), String> {
+        let end = range.end.min(self.vertex_array.element_count());
+        if range.start >= end || matches!(instances, Some((0, _))) {
+            return Ok(());
+        }
+
+        let first = range.start as gl::types::GLint;
+        let count = (end - range.start) as gl::types::GLsizei;
+        let offset = (range.start * std::mem::size_of::<u32>()) as *const gl::types::GLvoid;
+        let indexed = self.vertex_array.index_buffer().is_some();
+        let mode = self.topology.get_opengl_enum();
+        self.topology.apply()?;
+
         unsafe {
             self.vertex_array.bind();
-            gl::DrawElements(
-                gl::TRIANGLES,
-                6,
-                gl::UNSIGNED_INT,
-                0 as *const gl::types::GLvoid,
-            )
+            match (indexed, instances) {
+                (true, None) => gl::DrawElements(mode, count, gl::UNSIGNED_INT, offset),
+                (false, None) => gl::DrawArrays(mode, first, count),
+                (true, Some((instances, 0))) => {
+                    gl::DrawElementsInstanced(mode, count, gl::UNSIGNED_INT, offset, instances as gl::types::GLsizei)
+                }
+                (false, Some((instances, 0))) => {
+                    gl::DrawArraysInstanced(mode, first, count, instances as gl::types::GLsizei)
+                }
+                (true, Some((instances, base_instance))) => gl::DrawElementsInstancedBaseInstance(
+                    mode,
+                    count,
+                    gl::UNSIGNED_INT,
+                    offset,
+                    instances as gl::types::GLsizei,
+                    base_instance,
+                ),
+                (false, Some((instances, base_instance))) => gl::DrawArraysInstancedBaseInstance(
+                    mode,
+                    first,
+                    count,
+                    instances as gl::types::GLsizei,
+                    base_instance,
+                ),
+            }
         }
+
+        Ok(())
     }
 
-    pub fn vertices(&self) -> &[VERTEX; V] {
+    pub fn vertices(&self) -> &[VERTEX] {
         self.vertex_array.vertex_buffer().vertices()
     }
+
+    /// Get the render target's bounds in model space, `None` if they could not be computed. <br>
+    /// Bounds are computed from the `Position` attribute on creation.
+    pub fn bounds(&self) -> Option<&Aabb> {
+        self.bounds.as_ref()
+    }
+
+    /// Set the render target's bounds, used if the vertices have no `Position` attribute.
+    pub fn set_bounds(&mut self, bounds: Option<Aabb>) {
+        self.bounds = bounds;
+    }
+
+    /// Recomputes the bounds from the `Position` attribute, required after the vertices have been updated
+    pub fn compute_bounds(&mut self) {
+        self.bounds = Aabb::from_vertices(self.vertices(), POSITION_ATTRIBUTE);
+    }
+
+    /// Get the render target's topology.
+    pub fn topology(&self) -> PrimitiveTopology {
+        self.topology
+    }
+
+    /// Set the render target's topology.
+    pub fn set_topology(&mut self, topology: PrimitiveTopology) {
+        self.topology = topology;
+    }
+
+    /// Get a reference to the render target's vertex array.
+    pub fn vertex_array(&self) -> &VertexArrayObject<'a, VERTEX> {
+        &self.vertex_array
+    }
+
+    /// Get a mutable reference to the render target's vertex array, used for updating its buffers.
+    pub fn vertex_array_mut(&mut self) -> &mut VertexArrayObject<'a, VERTEX> {
+        &mut self.vertex_array
+    }
 }
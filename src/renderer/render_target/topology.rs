@@ -0,0 +1,97 @@
+use std::sync::atomic::{AtomicI32, Ordering};
+
+use crate::renderer::shader::ShaderProgram;
+
+/// `GL_MAX_PATCH_VERTICES` of the context, 0 until it is queried on the first patch draw
+static MAX_PATCH_VERTICES: AtomicI32 = AtomicI32::new(0);
+
+/// Describes how vertices are assembled into primitives when drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrimitiveTopology {
+    Points,
+    Lines,
+    LineStrip,
+    LineLoop,
+    #[default]
+    Triangles,
+    TriangleStrip,
+    TriangleFan,
+    /// Patches of the given amount of vertices, consumed by tessellation shaders
+    Patches(u32),
+}
+
+impl PrimitiveTopology {
+    pub fn get_opengl_enum(&self) -> gl::types::GLenum {
+        match self {
+            PrimitiveTopology::Points => gl::POINTS,
+            PrimitiveTopology::Lines => gl::LINES,
+            PrimitiveTopology::LineStrip => gl::LINE_STRIP,
+            PrimitiveTopology::LineLoop => gl::LINE_LOOP,
+            PrimitiveTopology::Triangles => gl::TRIANGLES,
+            PrimitiveTopology::TriangleStrip => gl::TRIANGLE_STRIP,
+            PrimitiveTopology::TriangleFan => gl::TRIANGLE_FAN,
+            PrimitiveTopology::Patches(_) => gl::PATCHES,
+        }
+    }
+
+    /// Get the amount of vertices per patch, `None` for other topologies.
+    pub fn patch_vertices(&self) -> Option<u32> {
+        match self {
+            PrimitiveTopology::Patches(vertices) => Some(*vertices),
+            _ => None,
+        }
+    }
+
+    /// Applies the state the topology needs before drawing, the amount of vertices per patch for patches. <br>
+    /// Fails for patches without vertices or with more than `GL_MAX_PATCH_VERTICES`.
+    pub fn apply(&self) -> Result<(), String> {
+        let vertices = match self.patch_vertices() {
+            Some(0) => return Err("Patches need at least one vertex".to_string()),
+            Some(vertices) => vertices,
+            None => return Ok(()),
+        };
+
+        // Patches are core since OpenGL 4.0
+        if !gl::PatchParameteri::is_loaded() {
+            return Err("Drawing patches requires OpenGL 4.0".to_string());
+        }
+
+        let max = max_patch_vertices();
+        if vertices > max as u32 {
+            return Err(format!(
+                "Patches of {} vertices exceed the maximum of {}",
+                vertices, max
+            ));
+        }
+
+        unsafe {
+            gl::PatchParameteri(gl::PATCH_VERTICES, vertices as gl::types::GLint);
+        }
+        Ok(())
+    }
+
+    /// Checks that a program can draw the topology, tessellated programs only draw patches and patches
+    /// need tessellation shaders.
+    pub fn check_program(&self, program: &ShaderProgram) -> Result<(), String> {
+        match (program.is_tessellated(), self.patch_vertices().is_some()) {
+            (true, false) => Err(format!("Tessellated programs have to draw patches, not {:?}", self)),
+            (false, true) => Err("Patches can only be drawn by tessellated programs".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Get the maximum amount of vertices per patch, only queried once as it is fixed by the implementation
+fn max_patch_vertices() -> gl::types::GLint {
+    match MAX_PATCH_VERTICES.load(Ordering::Relaxed) {
+        0 => {
+            let mut max = 0;
+            unsafe {
+                gl::GetIntegerv(gl::MAX_PATCH_VERTICES, &mut max);
+            }
+            MAX_PATCH_VERTICES.store(max, Ordering::Relaxed);
+            max
+        }
+        max => max,
+    }
+}
@@ -1,5 +1,7 @@
-use self::{indexbuffer::IndexBuffer, vertexbuffer::VertexBuffer};
-use crate::renderer::buffer::Buffer;
+use std::any::Any;
+
+use self::{dynamicindexbuffer::DynamicIndexBuffer, dynamicvertexbuffer::DynamicVertexBuffer};
+use crate::renderer::buffer::{Buffer, BufferUsage};
 
 mod vertex;
 
@@ -8,27 +10,81 @@ pub use vertex::VertexDefiner;
 pub use vertex::VertexField;
 pub use vertex::VertexFieldKind;
 pub use vertex::DefaultVertex;
+pub use vertex::VertexFieldKindConverter;
+pub use vertex::PackedNormal;
+pub use half::f16;
+pub use vertex::VertexFieldFormat;
+pub use vertex::VertexBinding;
+/// Derives [Vertex] for a `#[repr(C)]` struct, see the derive crate for the supported attributes.
+///
+/// Structs without `#[repr(C)]` are rejected, the offsets of their fields are not stable:
+/// ```compile_fail
+/// #[derive(engine::renderer::render_target::vertex_array::Vertex)]
+/// struct Unordered {
+///     position: [f32; 3],
+/// }
+/// ```
+///
+/// Generic structs are rejected:
+/// ```compile_fail
+/// #[repr(C)]
+/// #[derive(engine::renderer::render_target::vertex_array::Vertex)]
+/// struct Generic<T> {
+///     position: [T; 3],
+/// }
+/// ```
+///
+/// Tuple fields are rejected as their layout is unspecified, arrays are used instead:
+/// ```compile_fail
+/// #[repr(C)]
+/// #[derive(engine::renderer::render_target::vertex_array::Vertex)]
+/// struct Tupled {
+///     position: (f32, f32, f32),
+/// }
+/// ```
+///
+/// ```
+/// #[repr(C)]
+/// #[derive(engine::renderer::render_target::vertex_array::Vertex)]
+/// struct Colored {
+///     position: [f32; 3],
+///     color: [u8; 4],
+/// }
+/// ```
+pub use engine_derive::Vertex;
 
+pub mod dynamicindexbuffer;
+pub mod dynamicvertexbuffer;
 pub mod indexbuffer;
 pub mod vertexbuffer;
 
 /// VertexArrayObject stores data about vertices and indices, it owns the vertex array
 /// and therefore also owns the vertex buffer and index buffer
-pub struct VertexArrayObject<'a, VERTEX, const V: usize, const I: usize>
+pub struct VertexArrayObject<'a, VERTEX>
 where
     VERTEX: Vertex<'a>,
 {
     identifier: &'a str,
     vertex_array: gl::types::GLuint,
-    vertex_buffer: VertexBuffer<'a, VERTEX, V>,
-    index_buffer: IndexBuffer<I>,
+    vertex_buffer: DynamicVertexBuffer<'a, VERTEX>,
+    index_buffer: Option<DynamicIndexBuffer>,
+    streams: Vec<Stream>,
+}
+
+/// A buffer attached to one of the additional bindings of a vertex layout
+struct Stream {
+    binding: usize,
+    buffer: Box<dyn Any>,
+    /// Reads the element count of the type erased buffer
+    len: fn(&dyn Any) -> usize,
 }
 
-impl<'a, VERTEX, const V: usize, const I: usize> VertexArrayObject<'a, VERTEX, V, I>
+impl<'a, VERTEX> VertexArrayObject<'a, VERTEX>
 where
     VERTEX: Vertex<'a>,
 {
-    pub fn new(identifier: &'a str, vertices: [VERTEX; V], indices: [u32; I]) -> Self {
+    /// Creates a vertex array, without indices the vertices are drawn in order
+    pub fn new(identifier: &'a str, vertices: Vec<VERTEX>, indices: Option<Vec<u32>>, usage: BufferUsage) -> Self {
         trace!("Creating vertex array with identifier {}", identifier);
         // Generate and assign vertex array
         let mut vertex_array = 0;
@@ -41,10 +97,15 @@ where
         let vao = VertexArrayObject {
             identifier,
             vertex_array,
-            vertex_buffer: VertexBuffer::new(vertices),
-            index_buffer: IndexBuffer::new(indices),
+            vertex_buffer: DynamicVertexBuffer::new(vertices, usage),
+            index_buffer: indices.map(|indices| DynamicIndexBuffer::new(indices, usage)),
+            streams: Vec::new(),
         };
 
+        // Vertices are read from the first binding of the layout
+        vao.vertex_buffer.bind();
+        VERTEX::get_definition().assign_binding_attrib_pointers(0);
+
         // Rebind buffers
         vao.unbind_all();
         vao.bind_all();
@@ -66,18 +127,117 @@ where
         }
     }
 
+    /// Attaches a buffer to an additional binding of the vertex layout, replacing any buffer previously attached to it. <br>
+    /// The layout of the binding is read from VERTEX, the element type of the buffer only has to match its size.
+    pub fn attach_stream<T>(&mut self, binding: usize, buffer: DynamicVertexBuffer<'static, T>) -> Result<(), String>
+    where
+        T: Vertex<'static> + 'static,
+    {
+        self.attach_with_definer(VERTEX::get_definition(), binding, buffer)
+    }
+
+    /// Attaches a buffer of per-instance data, its layout is appended after the bindings of VERTEX. <br>
+    /// Replaces any instance buffer previously attached.
+    pub fn attach_instance_buffer<INSTANCE>(&mut self, buffer: DynamicVertexBuffer<'static, INSTANCE>) -> Result<(), String>
+    where
+        INSTANCE: Vertex<'static> + 'static,
+    {
+        let definer = VERTEX::get_definition().with_instance_bindings(INSTANCE::get_definition());
+        let binding = self.instance_binding();
+        self.attach_with_definer(definer, binding, buffer)
+    }
+
+    /// Get a mutable reference to the attached instance buffer, `None` if no buffer of type INSTANCE is attached.
+    pub fn instance_buffer_mut<INSTANCE>(&mut self) -> Option<&mut DynamicVertexBuffer<'static, INSTANCE>>
+    where
+        INSTANCE: Vertex<'static> + 'static,
+    {
+        let binding = self.instance_binding();
+        self.stream_mut(binding)
+    }
+
+    /// Get the amount of instances in the attached instance buffer, `None` if no instance buffer is attached.
+    pub fn instance_count(&self) -> Option<usize> {
+        let binding = self.instance_binding();
+        self.streams.iter().find(|s| s.binding == binding).map(|s| (s.len)(s.buffer.as_ref()))
+    }
+
+    /// The binding instance buffers are attached to, directly after the bindings of VERTEX
+    fn instance_binding(&self) -> usize {
+        VERTEX::get_definition().binding_count()
+    }
+
+    fn attach_with_definer<T>(
+        &mut self,
+        definer: VertexDefiner<'_>,
+        binding: usize,
+        buffer: DynamicVertexBuffer<'static, T>,
+    ) -> Result<(), String>
+    where
+        T: Vertex<'static> + 'static,
+    {
+        let stride = match definer.bindings().nth(binding) {
+            Some(b) if binding > 0 => b.stride(),
+            _ => return Err(format!("Vertex layout has no additional binding {}", binding)),
+        };
+
+        if stride != std::mem::size_of::<T>() {
+            return Err(format!(
+                "Stream element size {} does not match the stride {} of binding {}",
+                std::mem::size_of::<T>(),
+                stride,
+                binding
+            ));
+        }
+
+        trace!("Attaching stream to binding {} of vertex array {}", binding, self.identifier);
+        self.bind();
+        buffer.bind();
+        definer.assign_binding_attrib_pointers(binding);
+        self.unbind();
+        buffer.unbind();
+
+        self.streams.retain(|s| s.binding != binding);
+        self.streams.push(Stream {
+            binding,
+            buffer: Box::new(buffer),
+            len: |buffer| {
+                buffer
+                    .downcast_ref::<DynamicVertexBuffer<'static, T>>()
+                    .map_or(0, |b| b.len())
+            },
+        });
+
+        Ok(())
+    }
+
+    /// Get a mutable reference to the buffer attached to a binding, `None` if no buffer of type T is attached.
+    pub fn stream_mut<T>(&mut self, binding: usize) -> Option<&mut DynamicVertexBuffer<'static, T>>
+    where
+        T: Vertex<'static> + 'static,
+    {
+        self.streams
+            .iter_mut()
+            .find(|s| s.binding == binding)
+            .and_then(|s| s.buffer.downcast_mut())
+    }
+
     /// Binds all buffers with vertex array. Used for resetting all bindings
     pub fn bind_all(&self) {
         self.bind();
         self.vertex_buffer.bind();
-        self.index_buffer.bind();
+        if let Some(index_buffer) = &self.index_buffer {
+            index_buffer.bind();
+        }
     }
 
     /// Unbinds all buffers with vertex array. Used for resetting all bindings
     pub fn unbind_all(&self) {
         self.unbind();
         self.vertex_buffer.unbind();
-        self.index_buffer.unbind();
+        if let Some(index_buffer) = &self.index_buffer {
+            index_buffer.unbind();
+        }
     }
 
     /// Get a reference to the vertex array object's identifier.
@@ -86,12 +246,41 @@ where
     }
 
     /// Get a reference to the vertex array object's vertex buffer.
-    pub fn vertex_buffer(&self) -> &VertexBuffer<'a, VERTEX, V> {
+    pub fn vertex_buffer(&self) -> &DynamicVertexBuffer<'a, VERTEX> {
         &self.vertex_buffer
     }
 
-    /// Get a reference to the vertex array object's index buffer.
-    pub fn index_buffer(&self) -> &IndexBuffer<I> {
-        &self.index_buffer
+    /// Get a mutable reference to the vertex array object's vertex buffer.
+    pub fn vertex_buffer_mut(&mut self) -> &mut DynamicVertexBuffer<'a, VERTEX> {
+        &mut self.vertex_buffer
+    }
+
+    /// Get a reference to the vertex array object's index buffer, `None` if it isn't indexed.
+    pub fn index_buffer(&self) -> Option<&DynamicIndexBuffer> {
+        self.index_buffer.as_ref()
+    }
+
+    /// Get a mutable reference to the vertex array object's index buffer, `None` if it isn't indexed.
+    pub fn index_buffer_mut(&mut self) -> Option<&mut DynamicIndexBuffer> {
+        self.index_buffer.as_mut()
+    }
+
+    /// Get the amount of elements drawn, the index count if indexed and the vertex count otherwise.
+    pub fn element_count(&self) -> usize {
+        match &self.index_buffer {
+            Some(index_buffer) => index_buffer.len(),
+            None => self.vertex_buffer.len(),
+        }
+    }
+}
+
+impl<'a, VERTEX> Drop for VertexArrayObject<'a, VERTEX>
+where
+    VERTEX: Vertex<'a>,
+{
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.vertex_array);
+        }
     }
 }
@@ -0,0 +1,145 @@
+use std::ops::Range;
+
+use crate::renderer::buffer::{self, Buffer, BufferUsage};
+
+/// Heap-backed index buffer sized at runtime, its indices can be modified after creation
+pub struct DynamicIndexBuffer {
+    id: gl::types::GLuint,
+    usage: BufferUsage,
+    /// The amount of indices the OpenGL buffer has storage for
+    capacity: usize,
+    indices: Vec<u32>,
+}
+
+impl DynamicIndexBuffer {
+    pub fn new(indices: Vec<u32>, usage: BufferUsage) -> Self {
+        let mut idb = DynamicIndexBuffer {
+            id: 0,
+            usage,
+            capacity: indices.len(),
+            indices,
+        };
+
+        trace!("Initializing dynamic indexbuffer");
+        unsafe {
+            gl::GenBuffers(1, &mut idb.id);
+        }
+        buffer::allocate(idb.id, idb.capacity, &idb.indices, idb.usage);
+
+        idb
+    }
+
+    /// Overwrites the indices within range with data of the same length
+    pub fn update(&mut self, range: Range<usize>, data: &[u32]) -> Result<(), String> {
+        if range.start > range.end || range.end > self.indices.len() {
+            return Err(format!(
+                "Range {:?} is out of bounds for index buffer of length {}",
+                range,
+                self.indices.len()
+            ));
+        }
+
+        if range.len() != data.len() {
+            return Err(format!(
+                "Range {:?} does not match the {} indices given",
+                range,
+                data.len()
+            ));
+        }
+
+        self.indices[range.clone()].copy_from_slice(data);
+        buffer::sub_data(self.id, range.start, data);
+
+        Ok(())
+    }
+
+    /// Replaces all indices, the buffer is reallocated if it is too small
+    pub fn set(&mut self, indices: Vec<u32>) {
+        self.indices = indices;
+        if self.indices.len() > self.capacity {
+            self.capacity = self.indices.len();
+            buffer::allocate(self.id, self.capacity, &self.indices, self.usage);
+        } else {
+            buffer::sub_data(self.id, 0, &self.indices);
+        }
+    }
+
+    /// Appends indices to the end of the buffer, growing it if required
+    pub fn extend(&mut self, data: &[u32]) {
+        let offset = self.indices.len();
+        self.indices.extend_from_slice(data);
+
+        if self.indices.len() > self.capacity {
+            self.capacity = self.indices.len().max(self.capacity * 2);
+            buffer::allocate(self.id, self.capacity, &self.indices, self.usage);
+        } else {
+            buffer::sub_data(self.id, offset, data);
+        }
+    }
+
+    /// Reallocates the buffer to hold at least additional more indices
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.indices.len() + additional;
+        if required > self.capacity {
+            self.capacity = required;
+            buffer::allocate(self.id, self.capacity, &self.indices, self.usage);
+        }
+    }
+
+    /// Shortens the buffer to len indices, the allocated storage is kept
+    pub fn truncate(&mut self, len: usize) {
+        self.indices.truncate(len);
+    }
+
+    /// Get the amount of indices in the buffer.
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Returns true if the buffer holds no indices.
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Get the amount of indices the buffer has storage for.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get the index buffer's usage.
+    pub fn usage(&self) -> BufferUsage {
+        self.usage
+    }
+
+    /// Get a reference to the index buffer's id.
+    pub fn id(&self) -> &gl::types::GLuint {
+        &self.id
+    }
+
+    /// Get a reference to the index buffer's indices.
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+}
+
+impl Buffer for DynamicIndexBuffer {
+    fn bind(&self) {
+        unsafe {
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.id);
+        }
+    }
+
+    fn unbind(&self) {
+        unsafe {
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
+        }
+    }
+}
+
+impl Drop for DynamicIndexBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.id);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::{marker::PhantomData, ops::Range};
+
+use crate::renderer::buffer::{self, Buffer, BufferUsage};
+
+use super::vertex::Vertex;
+
+/// Heap-backed vertex buffer sized at runtime, its vertices can be modified after creation. <br>
+/// Attrib pointers are assigned by the [VertexArrayObject](super::VertexArrayObject) owning the buffer.
+pub struct DynamicVertexBuffer<'a, VERTEX>
+where
+    VERTEX: Vertex<'a>,
+{
+    id: gl::types::GLuint,
+    usage: BufferUsage,
+    /// The amount of vertices the OpenGL buffer has storage for
+    capacity: usize,
+    vertices: Vec<VERTEX>,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a, VERTEX> DynamicVertexBuffer<'a, VERTEX>
+where
+    VERTEX: Vertex<'a>,
+{
+    pub fn new(vertices: Vec<VERTEX>, usage: BufferUsage) -> Self {
+        let mut vb = DynamicVertexBuffer {
+            id: 0,
+            usage,
+            capacity: vertices.len(),
+            vertices,
+            _lifetime: PhantomData,
+        };
+
+        // Generate the buffer and assign data
+        trace!("Initializing dynamic vertexbuffer");
+        unsafe {
+            gl::GenBuffers(1, &mut vb.id);
+        }
+        buffer::allocate(vb.id, vb.capacity, &vb.vertices, vb.usage);
+
+        vb
+    }
+
+    /// Overwrites the vertices within range with data of the same length
+    pub fn update(&mut self, range: Range<usize>, data: &[VERTEX]) -> Result<(), String>
+    where
+        VERTEX: Clone,
+    {
+        if range.start > range.end || range.end > self.vertices.len() {
+            return Err(format!(
+                "Range {:?} is out of bounds for vertex buffer of length {}",
+                range,
+                self.vertices.len()
+            ));
+        }
+
+        if range.len() != data.len() {
+            return Err(format!(
+                "Range {:?} does not match the {} vertices given",
+                range,
+                data.len()
+            ));
+        }
+
+        self.vertices[range.clone()].clone_from_slice(data);
+        buffer::sub_data(self.id, range.start, data);
+
+        Ok(())
+    }
+
+    /// Replaces all vertices, the buffer is reallocated if it is too small
+    pub fn set(&mut self, vertices: Vec<VERTEX>) {
+        self.vertices = vertices;
+        if self.vertices.len() > self.capacity {
+            self.capacity = self.vertices.len();
+            buffer::allocate(self.id, self.capacity, &self.vertices, self.usage);
+        } else {
+            buffer::sub_data(self.id, 0, &self.vertices);
+        }
+    }
+
+    /// Appends vertices to the end of the buffer, growing it if required
+    pub fn extend(&mut self, data: &[VERTEX])
+    where
+        VERTEX: Clone,
+    {
+        let offset = self.vertices.len();
+        self.vertices.extend_from_slice(data);
+
+        if self.vertices.len() > self.capacity {
+            self.capacity = self.vertices.len().max(self.capacity * 2);
+            buffer::allocate(self.id, self.capacity, &self.vertices, self.usage);
+        } else {
+            buffer::sub_data(self.id, offset, data);
+        }
+    }
+
+    /// Reallocates the buffer to hold at least additional more vertices
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.vertices.len() + additional;
+        if required > self.capacity {
+            self.capacity = required;
+            buffer::allocate(self.id, self.capacity, &self.vertices, self.usage);
+        }
+    }
+
+    /// Shortens the buffer to len vertices, the allocated storage is kept
+    pub fn truncate(&mut self, len: usize) {
+        self.vertices.truncate(len);
+    }
+
+    /// Get the amount of vertices in the buffer.
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Returns true if the buffer holds no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Get the amount of vertices the buffer has storage for.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get the vertex buffer's usage.
+    pub fn usage(&self) -> BufferUsage {
+        self.usage
+    }
+
+    /// Get a reference to the vertex buffer's id.
+    pub fn id(&self) -> &gl::types::GLuint {
+        &self.id
+    }
+
+    /// Get a reference to the vertex buffer's vertices.
+    pub fn vertices(&self) -> &[VERTEX] {
+        &self.vertices
+    }
+}
+
+impl<'a, VERTEX> Buffer for DynamicVertexBuffer<'a, VERTEX>
+where
+    VERTEX: Vertex<'a>,
+{
+    fn bind(&self) {
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.id);
+        }
+    }
+
+    fn unbind(&self) {
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+    }
+}
+
+impl<'a, VERTEX> Drop for DynamicVertexBuffer<'a, VERTEX>
+where
+    VERTEX: Vertex<'a>,
+{
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.id);
+        }
+    }
+}
@@ -1,29 +1,110 @@
+pub use converter::{PackedNormal, VertexFieldKindConverter};
 pub use default::DefaultVertex;
 
+pub mod converter;
 pub mod default;
 
 /// Defines a Vertex field for a generic vertex
+#[derive(Clone)]
 pub struct VertexField<'a> {
     name: &'a str,
     kind: VertexFieldKind,
 
-    /// The count of values inside field inside Vertex
+    /// The count of components inside field inside Vertex
     count: usize,
 
+    /// The amount of components of one column, fields wider than a vec4 are read column by column
+    column_count: usize,
+
     /// The byte size of one field
     size: usize,
+
+    /// The byte offset of the field inside Vertex, packed after the previous field if not set
+    offset: Option<usize>,
+
+    /// Integer values are normalized when read by the shader
+    normalized: bool,
+
+    /// How the values are exposed to the shader
+    format: VertexFieldFormat,
+}
+
+/// Describes how the values of a field are exposed to the shader
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexFieldFormat {
+    /// Values are converted to floats, `vec` inputs (`glVertexAttribPointer`)
+    Float,
+    /// Integer values are kept as integers, `ivec`/`uvec` inputs (`glVertexAttribIPointer`)
+    Integer,
+    /// Double values are kept as doubles, `dvec` inputs (`glVertexAttribLPointer`)
+    Double,
 }
 
 impl<'a> VertexField<'a> {
+    /// Creates a field holding a single value of T, the component count is inferred from T
+    pub fn from_type<T>(name: &'a str) -> Self where T: VertexFieldKindConverter {
+        Self::new::<T>(name, 1)
+    }
+
+    /// Creates a field holding count values of T
     pub fn new<T>(name: &'a str, count: usize) -> Self where T: VertexFieldKindConverter {
+        let kind = T::get_vertex_field_kind();
+        let format = match kind {
+            VertexFieldKind::Double => VertexFieldFormat::Double,
+            _ => VertexFieldFormat::Float,
+        };
+
+        // Scalars are packed into a vector, flat fields wider than a vec4 are split into columns of 4
+        let column_count = match T::get_component_count() {
+            1 => count,
+            components => components / T::get_column_count().max(1),
+        };
+
         VertexField {
             name,
-            kind: T::get_vertex_field_kind(),
-            count,
+            kind,
+            count: T::get_component_count() * count,
+            column_count: column_count.min(4),
             size: std::mem::size_of::<T>() * count,
+            offset: None,
+            normalized: false,
+            format,
         }
     }
 
+    /// Expose integer values as integers to the shader instead of converting them to floats
+    pub fn integer(mut self) -> Self {
+        self.format = VertexFieldFormat::Integer;
+        self
+    }
+
+    /// Get the vertex definer field's format.
+    pub fn format(&self) -> VertexFieldFormat {
+        self.format
+    }
+
+    /// Set the byte offset of the field inside Vertex
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Normalize integer values to `0..1` or `-1..1` when read by the shader
+    pub fn normalized(mut self) -> Self {
+        self.normalized = true;
+        self
+    }
+
+    /// Get the vertex definer field's offset.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// Returns true if the field's values are normalized.
+    pub fn is_normalized(&self) -> bool {
+        self.normalized
+    }
+
     /// Get the vertex definer field's size.
     pub fn size(&self) -> usize {
         self.size
@@ -34,6 +115,28 @@ impl<'a> VertexField<'a> {
         self.count
     }
 
+    /// Get the amount of components of each column. <br>
+    /// Matrices have the column size reported by their [converter](VertexFieldKindConverter), other fields of more
+    /// than 4 components are split into columns of 4 followed by the remainder.
+    pub fn column_count(&self) -> usize {
+        self.column_count
+    }
+
+    /// Get the amount of columns the field is read as, each column is assigned its own attribute pointer.
+    pub fn columns(&self) -> usize {
+        let column_count = self.column_count.max(1);
+        self.count.div_ceil(column_count).max(1)
+    }
+
+    /// Get the amount of consecutive attribute locations the field occupies. <br>
+    /// Every column occupies one location, columns of 3 or 4 doubles (`dvec3`, `dvec4`) occupy two.
+    pub fn locations(&self) -> usize {
+        match self.format == VertexFieldFormat::Double && self.column_count > 2 {
+            true => self.columns() * 2,
+            false => self.columns(),
+        }
+    }
+
     /// Get a reference to the vertex definer field's name.
     pub fn name(&self) -> &&'a str {
         &self.name
@@ -45,6 +148,7 @@ impl<'a> VertexField<'a> {
     }
 }
 
+#[derive(Clone)]
 pub enum VertexFieldKind {
     Byte,
     UnsignedByte,
@@ -52,7 +156,13 @@ pub enum VertexFieldKind {
     UnsignedShort,
     Int,
     UnsignedInt,
+    HalfFloat,
     Float,
+    Double,
+    /// Four signed components packed into 32 bits, 10 bits each for xyz and 2 for w
+    Int2101010Rev,
+    /// Four unsigned components packed into 32 bits, 10 bits each for xyz and 2 for w
+    UnsignedInt2101010Rev,
 }
 
 impl VertexFieldKind {
@@ -64,62 +174,251 @@ impl VertexFieldKind {
             VertexFieldKind::UnsignedShort => gl::UNSIGNED_SHORT,
             VertexFieldKind::Int => gl::INT,
             VertexFieldKind::UnsignedInt => gl::UNSIGNED_INT,
+            VertexFieldKind::HalfFloat => gl::HALF_FLOAT,
             VertexFieldKind::Float => gl::FLOAT,
+            VertexFieldKind::Double => gl::DOUBLE,
+            VertexFieldKind::Int2101010Rev => gl::INT_2_10_10_10_REV,
+            VertexFieldKind::UnsignedInt2101010Rev => gl::UNSIGNED_INT_2_10_10_10_REV,
         }
     }
 }
 
-// Trait for auto conversion
-pub trait VertexFieldKindConverter {
-    fn get_vertex_field_kind() -> VertexFieldKind;
+/// A group of fields read from the same buffer
+#[derive(Clone)]
+pub struct VertexBinding<'a> {
+    fields: Vec<VertexField<'a>>,
+
+    /// The byte distance between two elements, the packed size of all fields if not set
+    stride: Option<usize>,
+
+    /// The amount of instances drawn before advancing to the next element, 0 advances every vertex
+    divisor: u32,
 }
 
-impl VertexFieldKindConverter for f32 {
-    fn get_vertex_field_kind() -> VertexFieldKind {
-        VertexFieldKind::Float
+impl<'a> VertexBinding<'a> {
+    pub fn new(fields: Vec<VertexField<'a>>) -> Self {
+        VertexBinding {
+            fields,
+            stride: None,
+            divisor: 0,
+        }
+    }
+
+    /// Set the byte distance between two elements, required if the element contains padding
+    pub fn with_stride(mut self, stride: usize) -> Self {
+        self.stride = Some(stride);
+        self
+    }
+
+    /// Advance to the next element after divisor instances have been drawn instead of every vertex
+    pub fn with_divisor(mut self, divisor: u32) -> Self {
+        self.divisor = divisor;
+        self
+    }
+
+    /// Get the packed byte size of all fields.
+    pub fn size(&self) -> usize {
+        self.fields.iter().map(|f| f.size()).sum()
     }
-}
 
-impl VertexFieldKindConverter for i32 {
-    fn get_vertex_field_kind() -> VertexFieldKind {
-        VertexFieldKind::Int
+    /// Get the byte distance between two elements.
+    pub fn stride(&self) -> usize {
+        self.stride.unwrap_or_else(|| self.size())
+    }
+
+    /// Get the vertex binding's divisor.
+    pub fn divisor(&self) -> u32 {
+        self.divisor
+    }
+
+    /// Get a reference to the vertex binding's fields as an iterator.
+    pub fn fields(&self) -> std::slice::Iter<'_, VertexField<'a>> {
+        self.fields.iter()
+    }
+
+    /// Get the byte offset of every field, fields without an explicit offset are packed after the previous field.
+    pub fn field_offsets(&self) -> impl Iterator<Item = (usize, &VertexField<'a>)> {
+        self.fields().scan(0, |offset, field| {
+            let field_offset = field.offset().unwrap_or(*offset);
+            *offset = field_offset + field.size();
+            Some((field_offset, field))
+        })
+    }
+
+    /// Get the amount of attribute locations occupied by the binding's fields.
+    pub fn locations(&self) -> usize {
+        self.fields.iter().map(|f| f.locations()).sum()
+    }
+
+    /// Assigns attrib pointers for the currently bound array buffer starting at attribute index `base`. <br>
+    /// Fields of more than 4 components are read column by column from consecutive indices.
+    pub fn assign_vertex_attrib_pointers(&self, base: gl::types::GLuint) {
+        let stride = self.stride() as gl::types::GLint;
+
+        let mut index = base;
+        for (field_offset, field) in self.field_offsets() {
+            let columns = field.column_count();
+            let column_size = field.size() / field.count().max(1) * columns;
+            let column_locations = (field.locations() / field.columns()) as gl::types::GLuint;
+
+            for column in 0..field.columns() {
+                let pointer = (field_offset + column * column_size) as *const gl::types::GLvoid;
+                let count = columns.min(field.count() - column * columns) as gl::types::GLint;
+                assign_attrib_pointer(field, index, count, stride, pointer, self.divisor);
+                index += column_locations;
+            }
+        }
     }
 }
 
-impl VertexFieldKindConverter for u32 {
-    fn get_vertex_field_kind() -> VertexFieldKind {
-        VertexFieldKind::UnsignedInt
+/// Assigns the attrib pointer of a single attribute index reading `count` components of the field and enables it
+fn assign_attrib_pointer(
+    field: &VertexField,
+    index: gl::types::GLuint,
+    count: gl::types::GLint,
+    stride: gl::types::GLint,
+    pointer: *const gl::types::GLvoid,
+    divisor: u32,
+) {
+    let kind = field.kind().get_opengl_enum();
+
+    unsafe {
+        match field.format() {
+            VertexFieldFormat::Float => {
+                let normalized = match field.is_normalized() {
+                    true => gl::TRUE,
+                    false => gl::FALSE,
+                };
+                gl::VertexAttribPointer(index, count, kind, normalized, stride, pointer);
+            }
+            VertexFieldFormat::Integer => {
+                gl::VertexAttribIPointer(index, count, kind, stride, pointer);
+            }
+            VertexFieldFormat::Double => {
+                gl::VertexAttribLPointer(index, count, kind, stride, pointer);
+            }
+        }
+        gl::VertexAttribDivisor(index, divisor);
+        gl::EnableVertexAttribArray(index);
     }
 }
 
 /// Defines a vertex and explains it's inner data.
-/// Used for vertex attrib pointers.
+/// Used for vertex attrib pointers. <br>
+/// A vertex is read from one or more bindings, each backed by its own buffer.
+/// Attribute indices are assigned in order across all bindings.
+#[derive(Clone)]
 pub struct VertexDefiner<'a> {
-    fields: Vec<VertexField<'a>>,
+    bindings: Vec<VertexBinding<'a>>,
 }
 
 impl<'a> VertexDefiner<'a> {
+    /// Creates a definer reading all fields from a single interleaved buffer
     pub fn new(fields: Vec<VertexField<'a>>) -> Self {
-        VertexDefiner { fields }
+        VertexDefiner {
+            bindings: vec![VertexBinding::new(fields)],
+        }
+    }
+
+    /// Creates a definer reading fields from multiple buffers
+    pub fn with_bindings(bindings: Vec<VertexBinding<'a>>) -> Self {
+        VertexDefiner { bindings }
+    }
+
+    /// Appends the bindings of a per-instance layout, bindings without a divisor advance once per instance
+    pub fn with_instance_bindings(mut self, instance: VertexDefiner<'a>) -> Self {
+        for binding in instance.bindings {
+            let divisor = binding.divisor.max(1);
+            self.bindings.push(binding.with_divisor(divisor));
+        }
+        self
+    }
+
+    /// Creates the definer of VERTEX followed by the per-instance bindings of INSTANCE
+    pub fn instanced<VERTEX, INSTANCE>() -> Self
+    where
+        VERTEX: Vertex<'a>,
+        INSTANCE: Vertex<'a>,
+    {
+        VERTEX::get_definition().with_instance_bindings(INSTANCE::get_definition())
     }
 
+    /// Set the byte distance between two vertices of the first binding, required if Vertex contains padding
+    pub fn with_stride(mut self, stride: usize) -> Self {
+        if let Some(binding) = self.bindings.first_mut() {
+            binding.stride = Some(stride);
+        }
+        self
+    }
+
+    /// Get the packed byte size of the first binding's fields.
     pub fn size(&self) -> usize {
-        let mut size = 0;
-        for f in self.fields.iter() {
-            size += f.size();
+        self.bindings.first().map_or(0, |b| b.size())
+    }
+
+    /// Get the byte distance between two vertices of the first binding.
+    pub fn stride(&self) -> usize {
+        self.bindings.first().map_or(0, |b| b.stride())
+    }
+
+    /// Assigns vertex attrib pointers of the first binding for the currently bound array buffer and enables their indices
+    pub fn assign_vertex_attrib_pointers(&self) {
+        self.assign_binding_attrib_pointers(0);
+    }
+
+    /// Assigns vertex attrib pointers of a binding for the currently bound array buffer and enables their indices
+    pub fn assign_binding_attrib_pointers(&self, binding: usize) {
+        trace!("Assigning vertex attribute pointers of binding {}", binding);
+        let base = self.binding_base_index(binding);
+        if let Some(binding) = self.bindings.get(binding) {
+            binding.assign_vertex_attrib_pointers(base);
         }
+    }
 
-        return size;
+    /// Get the attribute index of the first field of a binding.
+    pub fn binding_base_index(&self, binding: usize) -> gl::types::GLuint {
+        self.bindings
+            .iter()
+            .take(binding)
+            .map(|b| b.locations() as gl::types::GLuint)
+            .sum()
     }
 
-    /// Get a reference to the vertex definer's fields as an iterator.
-    pub fn fields(&self) -> std::slice::Iter<'_, VertexField<'_>> {
-        self.fields.iter()
+    /// Get the attribute index of every field of all bindings, fields occupy as many consecutive indices as they
+    /// have [locations](VertexField::locations).
+    pub fn field_locations(&self) -> impl Iterator<Item = (gl::types::GLuint, &VertexField<'a>)> {
+        self.fields().scan(0, |index, field| {
+            let location = *index;
+            *index += field.locations() as gl::types::GLuint;
+            Some((location, field))
+        })
+    }
+
+    /// Get the amount of bindings.
+    pub fn binding_count(&self) -> usize {
+        self.bindings.len()
+    }
+
+    /// Get a reference to the vertex definer's bindings as an iterator.
+    pub fn bindings(&self) -> std::slice::Iter<'_, VertexBinding<'a>> {
+        self.bindings.iter()
+    }
+
+    /// Get a reference to the vertex definer's fields of all bindings as an iterator, in attribute index order.
+    pub fn fields(&self) -> impl Iterator<Item = &VertexField<'a>> {
+        self.bindings.iter().flat_map(|b| b.fields())
     }
 }
 
 // Todo: Refactor with associated types defaults once available
-pub trait Vertex<'a> {
+/// Describes the memory layout of a vertex, usually implemented with `#[derive(Vertex)]`.
+///
+/// # Safety
+/// The definition of the first binding has to match the layout of the implementing type: every field,
+/// at its offset or packed after the previous field, has to lie within the type and hold `count` values
+/// of its kind. Vertices are read through these offsets, such as for computing [bounds](crate::renderer::bounds),
+/// so the type should be `#[repr(C)]`.
+pub unsafe trait Vertex<'a> {
     /// Get the inner defintion of the vertex data structure. <br>
     /// **Fields shall have the same name as the corresponding shader variable**
     fn get_definition() -> VertexDefiner<'a>;
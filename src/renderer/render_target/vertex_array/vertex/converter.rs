@@ -0,0 +1,128 @@
+extern crate nalgebra as na;
+
+use super::VertexFieldKind;
+
+// Trait for auto conversion
+pub trait VertexFieldKindConverter {
+    fn get_vertex_field_kind() -> VertexFieldKind;
+
+    /// The amount of components a single value consists of
+    fn get_component_count() -> usize {
+        1
+    }
+
+    /// The amount of columns a single value consists of, matrices are read from one attribute location per column
+    fn get_column_count() -> usize {
+        1
+    }
+}
+
+macro_rules! impl_scalar_converter {
+    ($($ty:ty => $kind:ident),* $(,)?) => {
+        $(
+            impl VertexFieldKindConverter for $ty {
+                fn get_vertex_field_kind() -> VertexFieldKind {
+                    VertexFieldKind::$kind
+                }
+            }
+        )*
+    };
+}
+
+impl_scalar_converter! {
+    i8 => Byte,
+    u8 => UnsignedByte,
+    i16 => Short,
+    u16 => UnsignedShort,
+    i32 => Int,
+    u32 => UnsignedInt,
+    half::f16 => HalfFloat,
+    f32 => Float,
+    f64 => Double,
+}
+
+impl<T, const N: usize> VertexFieldKindConverter for [T; N]
+where
+    T: VertexFieldKindConverter,
+{
+    fn get_vertex_field_kind() -> VertexFieldKind {
+        T::get_vertex_field_kind()
+    }
+
+    fn get_component_count() -> usize {
+        T::get_component_count() * N
+    }
+
+    /// Arrays of scalars form a single column, arrays of vectors or matrices have the columns of every element
+    fn get_column_count() -> usize {
+        match T::get_component_count() {
+            1 => 1,
+            _ => T::get_column_count() * N,
+        }
+    }
+}
+
+/// Vectors are matrices of a single column
+impl<T, const R: usize, const C: usize> VertexFieldKindConverter for na::SMatrix<T, R, C>
+where
+    T: VertexFieldKindConverter + na::Scalar,
+{
+    fn get_vertex_field_kind() -> VertexFieldKind {
+        T::get_vertex_field_kind()
+    }
+
+    fn get_component_count() -> usize {
+        T::get_component_count() * R * C
+    }
+
+    fn get_column_count() -> usize {
+        T::get_column_count() * C
+    }
+}
+
+impl<T, const D: usize> VertexFieldKindConverter for na::Point<T, D>
+where
+    T: VertexFieldKindConverter + na::Scalar,
+{
+    fn get_vertex_field_kind() -> VertexFieldKind {
+        T::get_vertex_field_kind()
+    }
+
+    fn get_component_count() -> usize {
+        T::get_component_count() * D
+    }
+}
+
+/// A vector packed into 32 bits as `INT_2_10_10_10_REV`, 10 bits each for xyz and 2 bits for w. <br>
+/// Fields of this type should be [normalized](super::VertexField::normalized) to read `-1..1` in the shader.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PackedNormal(pub u32);
+
+impl PackedNormal {
+    /// Packs the components of a vector, values are clamped to `-1..1`
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        fn pack(value: f32, bits: u32) -> u32 {
+            let max = ((1 << (bits - 1)) - 1) as f32;
+            let value = (value.clamp(-1.0, 1.0) * max).round() as i32;
+            value as u32 & ((1 << bits) - 1)
+        }
+
+        PackedNormal(pack(x, 10) | pack(y, 10) << 10 | pack(z, 10) << 20 | pack(w, 2) << 30)
+    }
+
+    /// Packs a normal, w is set to 0
+    pub fn from_normal(normal: na::Vector3<f32>) -> Self {
+        Self::new(normal.x, normal.y, normal.z, 0.0)
+    }
+}
+
+impl VertexFieldKindConverter for PackedNormal {
+    fn get_vertex_field_kind() -> VertexFieldKind {
+        VertexFieldKind::Int2101010Rev
+    }
+
+    fn get_component_count() -> usize {
+        4
+    }
+}
@@ -1,27 +1,24 @@
-use super::{VertexDefiner, VertexField, Vertex};
+use engine_derive::Vertex;
 
 /// Defaul vertex layout
-#[derive(Debug)]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Vertex)]
+#[vertex(crate = "crate")]
 pub struct DefaultVertex {
-    pos: (f32, f32, f32),
-    col: (f32, f32, f32),
-    tex: (f32, f32)
+    #[vertex(name = "Position")]
+    pos: [f32; 3],
+    #[vertex(name = "Color")]
+    col: [f32; 3],
+    #[vertex(name = "TexCoord")]
+    tex: [f32; 2]
 }
 
 impl DefaultVertex {
     pub fn new(pos: (f32, f32, f32), col: (f32, f32, f32), tex: (f32, f32)) -> Self {
-        DefaultVertex { pos, col, tex }
+        DefaultVertex {
+            pos: [pos.0, pos.1, pos.2],
+            col: [col.0, col.1, col.2],
+            tex: [tex.0, tex.1],
+        }
     }
 }
-
-impl<'a> Vertex<'a> for DefaultVertex {
-    fn get_definition() -> VertexDefiner<'a> {
-        let fields = vec![
-            VertexField::new::<f32>("Position", 3),
-            VertexField::new::<f32>("Color", 3),
-            VertexField::new::<f32>("TexCoord", 2),
-        ];
-
-        VertexDefiner::new(fields)
-    }
-}
\ No newline at end of file
@@ -53,30 +53,7 @@ where
 
     /// Assigns vertex attrib pointers and enables index
     pub fn assign_vertex_attrib_pointer(&self) {
-        trace!("Assigning vertex attribute pointers");
-        let mut index = 0;
-        let mut stride = 0;
-
-        // Fetch definition of vertex
-        let definer = VERTEX::get_definition();
-
-        // Todo: Support vertex attrib pointer with size larger than 4
-        for field in definer.fields() {
-            unsafe {
-                gl::VertexAttribPointer(
-                    index,
-                    field.count() as gl::types::GLint,
-                    field.kind().get_opengl_enum(),
-                    gl::FALSE,
-                    definer.size() as gl::types::GLint,
-                    stride as *const gl::types::GLvoid,
-                );
-                gl::EnableVertexAttribArray(index);
-            }
-
-            stride += field.size();
-            index += 1;
-        }
+        VERTEX::get_definition().assign_vertex_attrib_pointers();
     }
 
     /// Get a reference to the index buffer's id.
@@ -1,23 +1,84 @@
+extern crate nalgebra as na;
+
 use std::{collections::HashMap, ffi::CString};
 
-use crate::assets::Asset;
+use uniform::StoredUniform;
+
+use crate::{assets::Asset, renderer::buffer::BindingPoints};
 
 use super::render_target::vertex_array::{Vertex, VertexDefiner};
 
+pub use compute::{memory_barrier, Barrier, ComputeProgram};
+pub use error::ShaderError;
+pub use file::{ShaderFile, STAGE_PRAGMA};
 pub use kind::ShaderKind;
-pub use shader::{FragmentShader, Shader, VertexShader};
-pub use uniform::Uniform;
-
+pub use preprocessor::{Defines, IncludeResolver, Preprocessor, ProcessedSource, DEFAULT_VERSION};
+pub use shader::{ComputeShader, FragmentShader, Shader, ShaderStage, VertexShader};
+pub use reflection::{AttributeInfo, BlockInfo, BlockKind, BlockMember, Reflection, UniformError, UniformInfo};
+pub use uniform::{is_compatible, TextureUnit, UniformData, UniformElement, UniformType, UniformValue};
+
+mod compute;
+mod error;
+mod file;
 mod kind;
+mod preprocessor;
+mod reflection;
 mod shader;
 mod uniform;
 
+/// Name of the `mat4` uniform the model matrix is uploaded to when entities are drawn
+pub const MODEL_UNIFORM: &str = "model";
+
+/// Name of the `mat4` uniform the camera's view projection matrix is uploaded to when entities are drawn
+pub const VIEW_PROJECTION_UNIFORM: &str = "view_projection";
+
 pub struct ShaderProgram<'a> {
     shaders: HashMap<ShaderKind, Box<dyn Shader>>,
     // shaders: Vec<Box<dyn Shader>>,
-    locations: HashMap<&'a str, gl::types::GLuint>,
+    reflection: Reflection,
+    // Binding points applied to the program's blocks since the last link
+    block_bindings: HashMap<(BlockKind, String), gl::types::GLuint>,
     id: gl::types::GLuint,
     definer: Option<VertexDefiner<'a>>,
+    // Values uploaded with set_uniform, uploaded again when the program is relinked
+    uniform_values: HashMap<String, StoredUniform>,
+    // Set for programs built from preprocessed sources
+    sources: Option<ProgramSources>,
+    variants: HashMap<Defines, ShaderProgram<'a>>,
+}
+
+/// Files and preprocessor a program was built from, kept to rebuild the program and its variants
+#[derive(Clone)]
+struct ProgramSources {
+    // Shader file holding every stage, its sections are read again when the program is rebuilt
+    file: Option<String>,
+    stages: Vec<(ShaderKind, String)>,
+    preprocessor: Preprocessor,
+    processed: Vec<ProcessedSource>,
+}
+
+/// Program object linked from rebuilt sources, not yet swapped in
+struct LinkedProgram {
+    shaders: HashMap<ShaderKind, Box<dyn Shader>>,
+    id: gl::types::GLuint,
+    reflection: Reflection,
+    sources: ProgramSources,
+}
+
+/// Programs linked while rebuilding a program and its variants, deleted unless they were swapped in
+struct Rebuilt {
+    program: Option<LinkedProgram>,
+    variants: Vec<(Defines, Rebuilt)>,
+}
+
+impl Drop for Rebuilt {
+    fn drop(&mut self) {
+        if let Some(linked) = &self.program {
+            unsafe {
+                gl::DeleteProgram(linked.id);
+            }
+        }
+    }
 }
 
 impl<'a> ShaderProgram<'a> {
@@ -25,97 +86,248 @@ impl<'a> ShaderProgram<'a> {
     where
         T: Vertex<'a>,
     {
+        Self::with_definer(shaders, T::get_definition())
+    }
+
+    /// Creates a shader program whose attributes are bound to the fields of VERTEX followed by the fields of INSTANCE
+    pub fn new_instanced<VERTEX, INSTANCE>(shaders: Vec<Box<dyn Shader>>) -> Result<Self, String>
+    where
+        VERTEX: Vertex<'a>,
+        INSTANCE: Vertex<'a>,
+    {
+        Self::with_definer(shaders, VertexDefiner::instanced::<VERTEX, INSTANCE>())
+    }
+
+    /// Creates a shader program whose attributes are bound in the order of the definer's fields
+    pub fn with_definer(shaders: Vec<Box<dyn Shader>>, definer: VertexDefiner<'a>) -> Result<Self, String> {
+        let kinds: Vec<_> = shaders.iter().map(|shader| shader.kind()).collect();
+        validate_stages(&kinds)?;
+
         let mut hash = HashMap::new();
         for s in shaders {
             hash.insert(s.kind(), s);
         }
 
-        let mut sp = ShaderProgram {
-            shaders: hash,
-            locations: HashMap::new(),
-            id: 0,
-            definer: None,
-        };
-
         // Create shader program
         trace!("Creating shader program");
-        unsafe {
-            sp.id = gl::CreateProgram();
-        }
-
-        let def = T::get_definition();
-        sp.definer = Some(def);
-        sp.internal_new()?;
+        let (id, reflection) = create_program(hash.values().map(|shader| shader.as_ref()), &definer)?;
 
-        Ok(sp)
+        Ok(ShaderProgram {
+            shaders: hash,
+            reflection,
+            block_bindings: HashMap::new(),
+            id,
+            definer: Some(definer),
+            uniform_values: HashMap::new(),
+            sources: None,
+            variants: HashMap::new(),
+        })
+    }
+
+    /// Creates a shader program from the files of its stages, expanding includes and defines with the preprocessor. <br>
+    /// Compiler errors refer to the original files and lines.
+    pub fn from_sources<T>(
+        stages: &[(ShaderKind, &str)],
+        preprocessor: &Preprocessor,
+        resolver: &mut dyn IncludeResolver,
+    ) -> Result<Self, String>
+    where
+        T: Vertex<'a>,
+    {
+        let stages = stages.iter().map(|(kind, file)| (*kind, file.to_string())).collect();
+        Self::from_program_sources(None, stages, preprocessor.clone(), resolver, T::get_definition())
     }
 
-    fn internal_new(&mut self) -> Result<(), String> {
-        // Attach the shader objects to the program
-        for shader in self.shaders.iter() {
-            unsafe {
-                gl::AttachShader(self.id(), shader.1.id());
-            }
-        }
+    /// Creates a shader program from a single [ShaderFile] holding the sections of every stage. <br>
+    /// The program depends on the file as a whole, reloading it rebuilds every stage.
+    pub fn from_file<T>(
+        file: &str,
+        preprocessor: &Preprocessor,
+        resolver: &mut dyn IncludeResolver,
+    ) -> Result<Self, String>
+    where
+        T: Vertex<'a>,
+    {
+        let stages = file_stages(file, resolver)?;
+        let file = Some(file.to_string());
+        Self::from_program_sources(file, stages, preprocessor.clone(), resolver, T::get_definition())
+    }
+
+    fn from_program_sources(
+        file: Option<String>,
+        stages: Vec<(ShaderKind, String)>,
+        preprocessor: Preprocessor,
+        resolver: &mut dyn IncludeResolver,
+        definer: VertexDefiner<'a>,
+    ) -> Result<Self, String> {
+        let (shaders, processed) = compile_sources(&stages, &preprocessor, resolver)?.into_iter().unzip();
+        let mut program = Self::with_definer(shaders, definer)?;
+        program.sources = Some(ProgramSources {
+            file,
+            stages,
+            preprocessor,
+            processed,
+        });
+        Ok(program)
+    }
+
+    /// Get the variant of the program compiled with additional defines, compiling it on first use. <br>
+    /// Only programs created with [from_sources](Self::from_sources) or [from_file](Self::from_file) have variants.
+    pub fn variant(
+        &mut self,
+        defines: &Defines,
+        resolver: &mut dyn IncludeResolver,
+    ) -> Result<&mut ShaderProgram<'a>, String> {
+        if !self.variants.contains_key(defines) {
+            let sources = match self.sources.as_ref() {
+                Some(sources) => sources,
+                None => return Err("Shader program was not created from sources and has no variants".to_string()),
+            };
 
-        for (i, field) in self.definer.as_ref().unwrap().fields().enumerate() {
-            let raw = std::ffi::CString::new(field.name().to_string())
-                .expect("Failed at conveting shader to CString");
-            unsafe {
-                gl::BindAttribLocation(self.id, i as gl::types::GLuint, raw.as_c_str().as_ptr());
-            }
+            trace!("Compiling shader program variant {:?}", defines);
+            let variant = Self::from_program_sources(
+                sources.file.clone(),
+                sources.stages.clone(),
+                sources.preprocessor.clone().with_defines(defines),
+                resolver,
+                self.definer.clone().unwrap(),
+            )?;
+            self.variants.insert(defines.clone(), variant);
         }
 
-        // Link shaders to shaderprogram
-        self.link()?;
+        Ok(self.variants.get_mut(defines).unwrap())
+    }
 
-        // Detach shaders to allow OpenGL to delete shaders
-        for shader in self.shaders.iter() {
-            unsafe {
-                gl::DetachShader(self.id(), shader.1.id());
-            }
-        }
+    /// Get the compiled variants of the program.
+    pub fn variants(&self) -> &HashMap<Defines, ShaderProgram<'a>> {
+        &self.variants
+    }
+
+    /// Get mutable references to the compiled variants of the program.
+    pub fn variants_mut(&mut self) -> impl Iterator<Item = &mut ShaderProgram<'a>> {
+        self.variants.values_mut()
+    }
 
+    /// Returns true if the program or one of its variants was built from the file, directly or through an include
+    pub fn depends_on(&self, file: &str) -> bool {
+        let sources = self.sources.iter().flat_map(|sources| sources.processed.iter());
+        sources.clone().any(|source| source.depends_on(file))
+            || self.variants.values().any(|variant| variant.depends_on(file))
+    }
+
+    /// Preprocesses and compiles the sources of the program and its variants again. <br>
+    /// The program and its variants are only replaced once every one of them linked,
+    /// if any fails to compile or link all of them keep running with their previous shaders.
+    pub fn rebuild(&mut self, resolver: &mut dyn IncludeResolver) -> Result<(), ShaderError> {
+        let rebuilt = self.build(resolver)?;
+        self.replace(rebuilt);
         Ok(())
     }
 
-    /// Link shader program
-    pub fn link(&self) -> Result<(), String> {
-        // Link program
-        unsafe {
-            gl::LinkProgram(self.id);
+    /// Links the sources of the program and its variants into new program objects without replacing any of them
+    fn build(&self, resolver: &mut dyn IncludeResolver) -> Result<Rebuilt, ShaderError> {
+        let mut rebuilt = Rebuilt {
+            program: None,
+            variants: Vec::new(),
+        };
+
+        if let Some(sources) = self.sources.clone() {
+            trace!("Rebuilding shader program from {:?}", sources.stages);
+            let stages = match sources.file.as_ref() {
+                Some(file) => file_stages(file, resolver)?,
+                None => sources.stages.clone(),
+            };
+            validate_stages(&stages.iter().map(|(kind, _)| *kind).collect::<Vec<_>>()).map_err(ShaderError::Invalid)?;
+
+            let compiled = compile_sources(&stages, &sources.preprocessor, resolver)?;
+            let (shaders, processed): (Vec<_>, _) = compiled.into_iter().unzip();
+            let (id, reflection) = create_program(shaders.iter().map(|shader| shader.as_ref()), self.definer()?)?;
+
+            rebuilt.program = Some(LinkedProgram {
+                shaders: shaders.into_iter().map(|shader| (shader.kind(), shader)).collect(),
+                id,
+                reflection,
+                sources: ProgramSources {
+                    stages,
+                    processed,
+                    ..sources
+                },
+            });
+        }
+
+        // Dropping the programs linked so far deletes them if a variant fails
+        for (defines, variant) in self.variants.iter() {
+            let linked = variant.build(resolver)?;
+            rebuilt.variants.push((defines.clone(), linked));
+        }
+        Ok(rebuilt)
+    }
+
+    /// Swaps in the program objects linked by [build](Self::build)
+    fn replace(&mut self, mut rebuilt: Rebuilt) {
+        if let Some(linked) = rebuilt.program.take() {
+            self.shaders = linked.shaders;
+            self.swap(linked.id, linked.reflection);
+            self.sources = Some(linked.sources);
+        }
+
+        for (defines, linked) in std::mem::take(&mut rebuilt.variants) {
+            if let Some(variant) = self.variants.get_mut(&defines) {
+                variant.replace(linked);
+            }
         }
+    }
 
-        // Get linking status
-        let mut success: gl::types::GLint = 1;
-        unsafe {
-            gl::GetProgramiv(self.id(), gl::LINK_STATUS, &mut success);
+    /// Recompiles the stage of the program the asset holds the source of, selected by the asset's kind identifier. <br>
+    /// The program is relinked into a new program object which replaces the current one once it linked,
+    /// a program which fails to compile or link keeps running with its previous shaders.
+    pub fn reload(&mut self, asset: &Asset) -> Result<(), ShaderError> {
+        trace!("Triggered internal reload of shader-program");
+        let kind = ShaderKind::from_u8(*asset.kind_identifier());
+        if !self.has_stage(kind) {
+            return Err(ShaderError::Invalid(format!(
+                "Shader program has no {:?} to reload",
+                kind
+            )));
         }
 
-        if success == 0 {
-            // Get info about non successfull error
-            let mut len: gl::types::GLint = 0;
-            unsafe {
-                gl::GetProgramiv(self.id(), gl::INFO_LOG_LENGTH, &mut len);
-            }
+        let source = asset.raw_to_cstr().map_err(ShaderError::Source)?;
+        let mut stage = ShaderStage::new(kind);
+        stage
+            .compile(source.as_c_str())
+            .map_err(|log| ShaderError::Compile { kind, log })?;
 
-            let mut buffer: Vec<u8> = Vec::with_capacity(len as usize + 1);
-            buffer.extend([b' '].iter().cycle().take(len as usize));
-            let error = unsafe { CString::from_vec_unchecked(buffer) };
+        let shaders = self.shaders.iter().map(|(other, shader)| match *other == kind {
+            true => &stage as &dyn Shader,
+            false => shader.as_ref(),
+        });
+        let (id, reflection) = create_program(shaders, self.definer()?)?;
 
-            unsafe {
-                gl::GetProgramInfoLog(
-                    self.id(),
-                    len,
-                    std::ptr::null_mut(),
-                    error.as_ptr() as *mut gl::types::GLchar,
-                );
-            }
+        self.shaders.insert(kind, Box::new(stage));
+        self.swap(id, reflection);
+        Ok(())
+    }
 
-            return Err(error.to_string_lossy().into_owned());
+    fn definer(&self) -> Result<&VertexDefiner<'a>, ShaderError> {
+        match self.definer.as_ref() {
+            Some(definer) => Ok(definer),
+            None => Err(ShaderError::Invalid(
+                "Shader program has no vertex definition".to_string(),
+            )),
         }
+    }
 
-        Ok(())
+    /// Replaces the program object with a newly linked one, uniform values and block bindings set before are
+    /// applied again
+    fn swap(&mut self, id: gl::types::GLuint, reflection: Reflection) {
+        self.reflection = reflection;
+        swap_program(self.id, id, &self.reflection, &mut self.uniform_values, &mut self.block_bindings);
+        self.id = id;
+    }
+
+    /// Link shader program
+    pub fn link(&self) -> Result<(), String> {
+        link_program(self.id)
     }
 
     /// Bind program
@@ -130,485 +342,338 @@ impl<'a> ShaderProgram<'a> {
         }
     }
 
-    fn get_uniform_location(&mut self, location: &'a str) -> Result<gl::types::GLuint, String> {
-        let loc = self.locations.get(location);
-        match loc {
-            Some(l) => Ok(*l),
-            None => {
-                let location = CString::new(location);
-                let location = match location {
-                    Ok(c) => c,
-                    Err(e) => return Err(format!("Failed to parse location {}", e)),
-                };
-                let loc = unsafe { gl::GetUniformLocation(self.id(), location.as_ptr()) };
-
-                Ok(loc as gl::types::GLuint)
-            }
-        }
+    /// Uploads a value to a uniform, checked against the type and array size reflected from the program. <br>
+    /// The program has to be bound.
+    pub fn set_uniform<T>(&mut self, name: &str, value: &T) -> Result<(), UniformError>
+    where
+        T: UniformValue + ?Sized,
+    {
+        uniform::set(&self.reflection, &mut self.uniform_values, name, value)
     }
 
-    /// Get a reference to the shader program's id.
-    pub fn id(&self) -> gl::types::GLuint {
-        self.id
+    /// Get the reflected uniform with the given name, arrays are named without their index.
+    pub fn uniform(&self, name: &str) -> Option<&UniformInfo> {
+        self.reflection.uniform(name)
     }
 
-    /// Reload shaders
-    pub fn reload(&mut self, asset: &Asset) {
-        trace!("Triggered internal reload of shader-program");
-        for s in self.shaders.iter_mut() {
-            let kind = ShaderKind::from_u8(*asset.kind_identifier());
-            if *s.0 == kind {
-                let raw = asset.raw_to_cstr();
-                s.1.recompile(raw.as_c_str());
-            }
-        }
-
-        self.internal_new()
-            .expect("Failed relinking shader program after hotreload");
+    /// Returns true if the program contains a shader of the given stage
+    pub fn has_stage(&self, kind: ShaderKind) -> bool {
+        self.shaders.contains_key(&kind)
     }
-}
-
-impl<'a> Uniform<'a> for ShaderProgram<'a> {
-    fn uniform1f(&mut self, location: &'a str, v0: f32) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform1f(loc as gl::types::GLint, v0);
-        }
 
-        Ok(())
+    /// Returns true if the program tessellates, it has to be drawn with [PrimitiveTopology::Patches](super::render_target::PrimitiveTopology::Patches)
+    pub fn is_tessellated(&self) -> bool {
+        self.has_stage(ShaderKind::TessEvaluationShader)
     }
 
-    fn uniform2f(&mut self, location: &'a str, v0: f32, v1: f32) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform2f(loc as gl::types::GLint, v0, v1);
-        }
-
-        Ok(())
+    /// Get a reference to the shader program's reflection, queried after every link.
+    pub fn reflection(&self) -> &Reflection {
+        &self.reflection
     }
 
-    fn uniform3f(&mut self, location: &'a str, v0: f32, v1: f32, v2: f32) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform3f(loc as gl::types::GLint, v0, v1, v2);
-        }
-
-        Ok(())
-    }
-
-    fn uniform4f(
-        &mut self,
-        location: &'a str,
-        v0: f32,
-        v1: f32,
-        v2: f32,
-        v3: f32,
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform4f(loc as gl::types::GLint, v0, v1, v2, v3);
-        }
-
-        Ok(())
+    /// Binds the uniform blocks of the program to the binding points assigned to their names. <br>
+    /// Blocks the program does not declare are skipped, bindings are only applied once per link.
+    pub fn bind_blocks(&mut self, points: &BindingPoints) {
+        bind_program_blocks(self.id, BlockKind::Uniform, points, &mut self.block_bindings);
     }
 
-    fn uniform1i(&mut self, location: &'a str, v0: i32) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform1i(loc as gl::types::GLint, v0);
-        }
-
-        Ok(())
+    /// Binds the shader storage blocks of the program to the binding points assigned to their names
+    pub fn bind_storage_blocks(&mut self, points: &BindingPoints) {
+        bind_program_blocks(self.id, BlockKind::ShaderStorage, points, &mut self.block_bindings);
     }
 
-    fn uniform2i(&mut self, location: &'a str, v0: i32, v1: i32) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform2i(loc as gl::types::GLint, v0, v1);
+    /// Uploads a model matrix to [MODEL_UNIFORM], programs without the uniform ignore it. <br>
+    /// The program has to be bound.
+    pub fn set_model_matrix(&mut self, model: &na::Matrix4<f32>) -> Result<(), UniformError> {
+        match self.reflection.uniforms().contains_key(MODEL_UNIFORM) {
+            true => self.set_uniform(MODEL_UNIFORM, model),
+            false => Ok(()),
         }
-
-        Ok(())
     }
 
-    fn uniform3i(&mut self, location: &'a str, v0: i32, v1: i32, v2: i32) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform3i(loc as gl::types::GLint, v0, v1, v2);
+    /// Uploads a view projection matrix to [VIEW_PROJECTION_UNIFORM], programs without the uniform ignore it. <br>
+    /// The program has to be bound.
+    pub fn set_view_projection(&mut self, view_projection: &na::Matrix4<f32>) -> Result<(), UniformError> {
+        match self.reflection.uniforms().contains_key(VIEW_PROJECTION_UNIFORM) {
+            true => self.set_uniform(VIEW_PROJECTION_UNIFORM, view_projection),
+            false => Ok(()),
         }
-
-        Ok(())
     }
 
-    fn uniform4i(
-        &mut self,
-        location: &'a str,
-        v0: i32,
-        v1: i32,
-        v2: i32,
-        v3: i32,
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform4i(loc as gl::types::GLint, v0, v1, v2, v3);
-        }
-
-        Ok(())
+    /// Get a reference to the shader program's id.
+    pub fn id(&self) -> gl::types::GLuint {
+        self.id
     }
+}
 
-    fn uniform1ui(&mut self, location: &'a str, v0: u32) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
+impl<'a> Drop for ShaderProgram<'a> {
+    fn drop(&mut self) {
         unsafe {
-            gl::Uniform1ui(loc as gl::types::GLint, v0);
+            gl::DeleteProgram(self.id());
+            for shader in self.shaders.iter() {
+                drop(shader);
+            }
         }
-
-        Ok(())
     }
+}
 
-    fn uniform2ui(&mut self, location: &'a str, v0: u32, v1: u32) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform2ui(loc as gl::types::GLint, v0, v1);
+/// Checks that the stages form a drawable program. <br>
+/// Programs need exactly one vertex shader, every stage at most once,
+/// a tessellation control shader only together with an evaluation shader and no compute shader.
+pub fn validate_stages(stages: &[ShaderKind]) -> Result<(), String> {
+    for (i, kind) in stages.iter().enumerate() {
+        if stages[..i].contains(kind) {
+            return Err(format!("Shader program contains more than one {:?}", kind));
         }
-
-        Ok(())
     }
 
-    fn uniform3ui(&mut self, location: &'a str, v0: u32, v1: u32, v2: u32) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform3ui(loc as gl::types::GLint, v0, v1, v2);
-        }
-
-        Ok(())
+    if stages.contains(&ShaderKind::ComputeShader) {
+        return Err("Compute shaders can not be combined with other stages, use a ComputeProgram".to_string());
     }
 
-    fn uniform4ui(
-        &mut self,
-        location: &'a str,
-        v0: u32,
-        v1: u32,
-        v2: u32,
-        v3: u32,
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform4ui(loc as gl::types::GLint, v0, v1, v2, v3);
-        }
-
-        Ok(())
+    if !stages.contains(&ShaderKind::VertexShader) {
+        return Err("Shader program has no VertexShader".to_string());
     }
 
-    fn uniform1fv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        value: [f32; 1],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform1fv(loc as gl::types::GLint, count, value.as_ptr());
-        }
-
-        Ok(())
+    if stages.contains(&ShaderKind::TessControlShader) && !stages.contains(&ShaderKind::TessEvaluationShader) {
+        return Err("A TessControlShader requires a TessEvaluationShader".to_string());
     }
 
-    fn uniform2fv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        value: [f32; 2],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform2fv(loc as gl::types::GLint, count, value.as_ptr());
-        }
-
-        Ok(())
-    }
+    Ok(())
+}
 
-    fn uniform3fv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        value: [f32; 3],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform3fv(loc as gl::types::GLint, count, value.as_ptr());
-        }
+type CompiledStage = (Box<dyn Shader>, ProcessedSource);
+
+/// Get the program stages declared by the sections of a shader file, each read from the file itself
+fn file_stages(file: &str, resolver: &mut dyn IncludeResolver) -> Result<Vec<(ShaderKind, String)>, ShaderError> {
+    let source = resolver.resolve(file).map_err(ShaderError::Source)?;
+    let shader_file = ShaderFile::parse(file, &source).map_err(ShaderError::Source)?;
+    Ok(shader_file
+        .program_kinds()
+        .into_iter()
+        .map(|kind| (kind, file.to_string()))
+        .collect())
+}
 
-        Ok(())
-    }
+/// Preprocesses and compiles the file of every stage, compiler logs are mapped back to the original files
+fn compile_sources(
+    stages: &[(ShaderKind, String)],
+    preprocessor: &Preprocessor,
+    resolver: &mut dyn IncludeResolver,
+) -> Result<Vec<CompiledStage>, ShaderError> {
+    let mut compiled: Vec<CompiledStage> = Vec::new();
 
-    fn uniform4fv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        value: [f32; 4],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform4fv(loc as gl::types::GLint, count, value.as_ptr());
-        }
+    for (kind, file) in stages {
+        let source = stage_source(*kind, file, preprocessor, resolver).map_err(ShaderError::Source)?;
+        let raw = source.to_cstring().map_err(ShaderError::Source)?;
 
-        Ok(())
+        let mut shader = ShaderStage::new(*kind);
+        shader.compile(raw.as_c_str()).map_err(|log| ShaderError::Compile {
+            kind: *kind,
+            log: source.map_log(&log),
+        })?;
+        compiled.push((Box::new(shader), source));
     }
 
-    fn uniform1iv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        value: [i32; 1],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform1iv(loc as gl::types::GLint, count, value.as_ptr());
-        }
+    Ok(compiled)
+}
 
-        Ok(())
+/// Preprocesses the source of a stage, files with stage sections only contribute the section of the stage
+fn stage_source(
+    kind: ShaderKind,
+    file: &str,
+    preprocessor: &Preprocessor,
+    resolver: &mut dyn IncludeResolver,
+) -> Result<ProcessedSource, String> {
+    let mut source = resolver.resolve(file)?;
+    if ShaderFile::is_shader_file(&source) {
+        source = match ShaderFile::parse(file, &source)?.stage(kind) {
+            Some(section) => section.to_string(),
+            None => return Err(format!("{}: no {:?} section", file, kind)),
+        };
     }
 
-    fn uniform2iv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        value: [i32; 2],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform2iv(loc as gl::types::GLint, count, value.as_ptr());
-        }
+    preprocessor.process_source(file, &source, resolver)
+}
 
-        Ok(())
-    }
+/// Links the shaders into a new program object with the attributes bound in the order of the definer's fields. <br>
+/// The program object is deleted again if linking or validating the program fails.
+fn create_program<'s>(
+    shaders: impl Iterator<Item = &'s dyn Shader>,
+    definer: &VertexDefiner,
+) -> Result<(gl::types::GLuint, Reflection), ShaderError> {
+    let shaders: Vec<_> = shaders.collect();
+    let id = unsafe { gl::CreateProgram() };
 
-    fn uniform3iv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        value: [i32; 3],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
+    // Attach the shader objects to the program
+    for shader in shaders.iter() {
         unsafe {
-            gl::Uniform3iv(loc as gl::types::GLint, count, value.as_ptr());
+            gl::AttachShader(id, shader.id());
         }
-
-        Ok(())
     }
 
-    fn uniform4iv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        value: [i32; 4],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform4iv(loc as gl::types::GLint, count, value.as_ptr());
+    // Fields wider than a vec4, such as matrices, occupy a location per column
+    for (location, field) in definer.field_locations() {
+        if let Ok(raw) = CString::new(field.name().to_string()) {
+            unsafe {
+                gl::BindAttribLocation(id, location, raw.as_ptr());
+            }
         }
-
-        Ok(())
     }
 
-    fn uniform1uiv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        value: [u32; 1],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform1uiv(loc as gl::types::GLint, count, value.as_ptr());
-        }
-
-        Ok(())
-    }
+    // Link shaders to shaderprogram
+    let linked = link_program(id).map_err(ShaderError::Link).and_then(|_| {
+        let reflection = unsafe { Reflection::query(id) };
+        reflection.validate_attributes(definer).map_err(ShaderError::Invalid)?;
+        Ok(reflection)
+    });
 
-    fn uniform2uiv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        value: [u32; 2],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
+    // Detach shaders to allow OpenGL to delete shaders
+    for shader in shaders.iter() {
         unsafe {
-            gl::Uniform2uiv(loc as gl::types::GLint, count, value.as_ptr());
+            gl::DetachShader(id, shader.id());
         }
-
-        Ok(())
     }
 
-    fn uniform3uiv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        value: [u32; 3],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform3uiv(loc as gl::types::GLint, count, value.as_ptr());
+    match linked {
+        Ok(reflection) => Ok((id, reflection)),
+        Err(e) => {
+            unsafe {
+                gl::DeleteProgram(id);
+            }
+            Err(e)
         }
-
-        Ok(())
     }
+}
 
-    fn uniform4uiv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        value: [u32; 4],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::Uniform4uiv(loc as gl::types::GLint, count, value.as_ptr());
-        }
-
-        Ok(())
+/// Links a program, returning the info log on failure
+fn link_program(id: gl::types::GLuint) -> Result<(), String> {
+    // Link program
+    unsafe {
+        gl::LinkProgram(id);
     }
 
-    fn uniform_matrix2fv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        transpose: gl::types::GLboolean,
-        value: [f32; 4],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::UniformMatrix2fv(loc as gl::types::GLint, count, transpose, value.as_ptr());
-        }
-
-        Ok(())
+    // Get linking status
+    let mut success: gl::types::GLint = 1;
+    unsafe {
+        gl::GetProgramiv(id, gl::LINK_STATUS, &mut success);
     }
 
-    fn uniform_matrix3fv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        transpose: gl::types::GLboolean,
-        value: [f32; 9],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
+    if success == 0 {
+        // Get info about non successfull error
+        let mut len: gl::types::GLint = 0;
         unsafe {
-            gl::UniformMatrix3fv(loc as gl::types::GLint, count, transpose, value.as_ptr());
+            gl::GetProgramiv(id, gl::INFO_LOG_LENGTH, &mut len);
         }
 
-        Ok(())
-    }
+        let mut buffer: Vec<u8> = Vec::with_capacity(len as usize + 1);
+        buffer.extend([b' '].iter().cycle().take(len as usize));
+        let error = unsafe { CString::from_vec_unchecked(buffer) };
 
-    fn uniform_matrix4fv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        transpose: gl::types::GLboolean,
-        value: [f32; 16],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
         unsafe {
-            gl::UniformMatrix4fv(loc as gl::types::GLint, count, transpose, value.as_ptr());
+            gl::GetProgramInfoLog(
+                id,
+                len,
+                std::ptr::null_mut(),
+                error.as_ptr() as *mut gl::types::GLchar,
+            );
         }
 
-        Ok(())
+        return Err(error.to_string_lossy().into_owned());
     }
 
-    fn uniform_matrix2x3fv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        transpose: gl::types::GLboolean,
-        value: [f32; 6],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::UniformMatrix2x3fv(loc as gl::types::GLint, count, transpose, value.as_ptr());
-        }
+    Ok(())
+}
 
-        Ok(())
+/// Replaces the program object `old` with the newly linked `new`, which stays bound if `old` was. <br>
+/// Uniform values and block bindings applied to the old program are applied to the new one,
+/// values and blocks the new program does not declare are dropped.
+fn swap_program(
+    old: gl::types::GLuint,
+    new: gl::types::GLuint,
+    reflection: &Reflection,
+    uniform_values: &mut HashMap<String, StoredUniform>,
+    block_bindings: &mut HashMap<(BlockKind, String), gl::types::GLuint>,
+) {
+    let mut current = 0;
+    unsafe {
+        gl::GetIntegerv(gl::CURRENT_PROGRAM, &mut current);
+        gl::UseProgram(new);
+        gl::DeleteProgram(old);
+    }
+
+    uniform_values.retain(|name, value| match uniform::apply(reflection, name, value) {
+        Ok(_) => true,
+        Err(e) => {
+            trace!("Dropping value of uniform {:?} after relinking, {}", name, e);
+            false
+        }
+    });
+    block_bindings.retain(|(kind, block), point| bind_program_block(new, *kind, block, *point));
+
+    // The new program takes the place of the old one if it was bound
+    let current = match current as gl::types::GLuint == old {
+        true => new,
+        false => current as gl::types::GLuint,
+    };
+    unsafe {
+        gl::UseProgram(current);
     }
+}
 
-    fn uniform_matrix3x2fv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        transpose: gl::types::GLboolean,
-        value: [f32; 6],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::UniformMatrix3x2fv(loc as gl::types::GLint, count, transpose, value.as_ptr());
-        }
-
-        Ok(())
+/// Binds the blocks of one kind to the binding points assigned to their names, skipping bindings which were already applied
+fn bind_program_blocks(
+    id: gl::types::GLuint,
+    kind: BlockKind,
+    points: &BindingPoints,
+    applied: &mut HashMap<(BlockKind, String), gl::types::GLuint>,
+) {
+    // Shader storage blocks are only available from OpenGL 4.3
+    if kind == BlockKind::ShaderStorage && !gl::ShaderStorageBlockBinding::is_loaded() {
+        return;
     }
 
-    fn uniform_matrix2x4fv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        transpose: gl::types::GLboolean,
-        value: [f32; 8],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::UniformMatrix2x4fv(loc as gl::types::GLint, count, transpose, value.as_ptr());
+    for (block, point) in points.iter() {
+        let key = (kind, block.to_string());
+        if applied.get(&key) == Some(&point) {
+            continue;
         }
 
-        Ok(())
+        bind_program_block(id, kind, block, point);
+        applied.insert(key, point);
     }
+}
 
-    fn uniform_matrix4x2fv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        transpose: gl::types::GLboolean,
-        value: [f32; 8],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::UniformMatrix4x2fv(loc as gl::types::GLint, count, transpose, value.as_ptr());
-        }
-
-        Ok(())
+/// Binds a block of the program to a binding point, returns false if the program does not declare the block
+fn bind_program_block(id: gl::types::GLuint, kind: BlockKind, block: &str, point: gl::types::GLuint) -> bool {
+    // Shader storage blocks are only available from OpenGL 4.3
+    if kind == BlockKind::ShaderStorage && !gl::ShaderStorageBlockBinding::is_loaded() {
+        return false;
     }
 
-    fn uniform_matrix3x4fv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        transpose: gl::types::GLboolean,
-        value: [f32; 12],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::UniformMatrix3x4fv(loc as gl::types::GLint, count, transpose, value.as_ptr());
+    let name = match CString::new(block) {
+        Ok(name) => name,
+        Err(_) => {
+            warn!("Skipping binding of block {:?}, its name contains a nul byte", block);
+            return false;
         }
+    };
 
-        Ok(())
-    }
-
-    fn uniform_matrix4x3fv(
-        &mut self,
-        location: &'a str,
-        count: gl::types::GLsizei,
-        transpose: gl::types::GLboolean,
-        value: [f32; 12],
-    ) -> Result<(), String> {
-        let loc = self.get_uniform_location(location)?;
-        unsafe {
-            gl::UniformMatrix4x3fv(loc as gl::types::GLint, count, transpose, value.as_ptr());
+    let index = unsafe {
+        match kind {
+            BlockKind::Uniform => gl::GetUniformBlockIndex(id, name.as_ptr()),
+            BlockKind::ShaderStorage => gl::GetProgramResourceIndex(id, gl::SHADER_STORAGE_BLOCK, name.as_ptr()),
         }
-
-        Ok(())
+    };
+    if index == gl::INVALID_INDEX {
+        return false;
     }
-}
 
-impl<'a> Drop for ShaderProgram<'a> {
-    fn drop(&mut self) {
-        unsafe {
-            gl::DeleteProgram(self.id());
-            for shader in self.shaders.iter() {
-                drop(shader);
-            }
+    unsafe {
+        match kind {
+            BlockKind::Uniform => gl::UniformBlockBinding(id, index, point),
+            BlockKind::ShaderStorage => gl::ShaderStorageBlockBinding(id, index, point),
         }
     }
+    true
 }
@@ -0,0 +1,245 @@
+use std::{collections::HashMap, ops::BitOr};
+
+use crate::renderer::buffer::{layout::BlockLayout, BindingPoints, StorageBuffer};
+
+use super::{
+    uniform::{self, StoredUniform},
+    BlockKind, ComputeShader, IncludeResolver, Preprocessor, Reflection, Shader, ShaderKind, UniformError,
+    UniformInfo, UniformValue,
+};
+
+/// A program made of a single compute shader, dispatched in work groups instead of drawn
+pub struct ComputeProgram {
+    shader: ComputeShader,
+    reflection: Reflection,
+    // Binding points applied to the program's blocks, applied again after relinking
+    block_bindings: HashMap<(BlockKind, String), gl::types::GLuint>,
+    // Values set through set_uniform, uploaded again after relinking
+    uniform_values: HashMap<String, StoredUniform>,
+    work_group_size: [u32; 3],
+    id: gl::types::GLuint,
+}
+
+impl ComputeProgram {
+    pub fn new(shader: ComputeShader) -> Result<Self, String> {
+        trace!("Creating compute program");
+        let (id, reflection) = link(&shader)?;
+
+        Ok(ComputeProgram {
+            shader,
+            reflection,
+            block_bindings: HashMap::new(),
+            uniform_values: HashMap::new(),
+            work_group_size: work_group_size(id),
+            id,
+        })
+    }
+
+    /// Creates a compute program from a file, which may be a [ShaderFile](super::ShaderFile) with a compute section
+    pub fn from_file(
+        file: &str,
+        preprocessor: &Preprocessor,
+        resolver: &mut dyn IncludeResolver,
+    ) -> Result<Self, String> {
+        let source = super::stage_source(ShaderKind::ComputeShader, file, preprocessor, resolver)?;
+        let raw = source.to_cstring()?;
+        let shader = ComputeShader::from_source(raw.as_c_str()).map_err(|e| source.map_log(&e))?;
+        Self::new(shader)
+    }
+
+    /// Compiles the compute shader into a new program which replaces this one once it linked. <br>
+    /// The program keeps running with its previous shader if compiling or linking fails,
+    /// uniform values and block bindings set before are applied to the new program.
+    pub fn reload(&mut self, source: &std::ffi::CStr) -> Result<(), String> {
+        let shader = ComputeShader::from_source(source)?;
+        let (id, reflection) = link(&shader)?;
+
+        self.shader = shader;
+        self.reflection = reflection;
+        self.work_group_size = work_group_size(id);
+        super::swap_program(self.id, id, &self.reflection, &mut self.uniform_values, &mut self.block_bindings);
+        self.id = id;
+        Ok(())
+    }
+
+    /// Bind program
+    pub fn bind(&self) {
+        unsafe { gl::UseProgram(self.id) }
+    }
+
+    // Unbind program
+    pub fn unbind(&self) {
+        unsafe {
+            gl::UseProgram(0);
+        }
+    }
+
+    /// Binds the program and runs `x * y * z` work groups
+    pub fn dispatch(&self, x: u32, y: u32, z: u32) {
+        self.bind();
+        unsafe {
+            gl::DispatchCompute(x, y, z);
+        }
+    }
+
+    /// Runs enough work groups to cover `x * y * z` invocations, rounded up to whole work groups
+    pub fn dispatch_invocations(&self, x: u32, y: u32, z: u32) {
+        let [gx, gy, gz] = self.work_groups([x, y, z]);
+        self.dispatch(gx, gy, gz);
+    }
+
+    /// Runs the work group counts stored as three `uint`s at byte `offset` of a buffer,
+    /// letting earlier dispatches decide how much work follows
+    pub fn dispatch_indirect<T>(&self, buffer: &StorageBuffer<T>, offset: usize)
+    where
+        T: BlockLayout + ?Sized,
+    {
+        self.bind();
+        unsafe {
+            gl::BindBuffer(gl::DISPATCH_INDIRECT_BUFFER, buffer.id());
+            gl::DispatchComputeIndirect(offset as gl::types::GLintptr);
+            gl::BindBuffer(gl::DISPATCH_INDIRECT_BUFFER, 0);
+        }
+    }
+
+    /// Get the amount of work groups needed to cover the given invocations.
+    pub fn work_groups(&self, invocations: [u32; 3]) -> [u32; 3] {
+        let mut groups = [0; 3];
+        for (i, group) in groups.iter_mut().enumerate() {
+            let size = self.work_group_size[i].max(1);
+            *group = match invocations[i] % size {
+                0 => invocations[i] / size,
+                _ => invocations[i] / size + 1,
+            };
+        }
+        groups
+    }
+
+    /// Get the local work group size declared by the shader.
+    pub fn work_group_size(&self) -> [u32; 3] {
+        self.work_group_size
+    }
+
+    /// Uploads a value to a uniform, checked against the type and array size reflected from the program. <br>
+    /// The program has to be bound.
+    pub fn set_uniform<T>(&mut self, name: &str, value: &T) -> Result<(), UniformError>
+    where
+        T: UniformValue + ?Sized,
+    {
+        uniform::set(&self.reflection, &mut self.uniform_values, name, value)
+    }
+
+    /// Get the reflected uniform with the given name, arrays are named without their index.
+    pub fn uniform(&self, name: &str) -> Option<&UniformInfo> {
+        self.reflection.uniform(name)
+    }
+
+    /// Get a reference to the compute program's reflection, queried after every link.
+    pub fn reflection(&self) -> &Reflection {
+        &self.reflection
+    }
+
+    /// Binds the uniform blocks of the program to the binding points assigned to their names
+    pub fn bind_blocks(&mut self, points: &BindingPoints) {
+        super::bind_program_blocks(self.id, BlockKind::Uniform, points, &mut self.block_bindings);
+    }
+
+    /// Binds the shader storage blocks of the program to the binding points assigned to their names
+    pub fn bind_storage_blocks(&mut self, points: &BindingPoints) {
+        super::bind_program_blocks(self.id, BlockKind::ShaderStorage, points, &mut self.block_bindings);
+    }
+
+    /// Get the compute program's id.
+    pub fn id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+/// Links a compute shader into a new program object, which is deleted again if linking fails
+fn link(shader: &ComputeShader) -> Result<(gl::types::GLuint, Reflection), String> {
+    let id = unsafe { gl::CreateProgram() };
+    unsafe {
+        gl::AttachShader(id, shader.id());
+    }
+
+    let linked = super::link_program(id);
+    unsafe {
+        gl::DetachShader(id, shader.id());
+    }
+
+    match linked {
+        Ok(_) => Ok((id, unsafe { Reflection::query(id) })),
+        Err(e) => {
+            unsafe {
+                gl::DeleteProgram(id);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Get the local work group size declared by the compute shader of a linked program
+fn work_group_size(id: gl::types::GLuint) -> [u32; 3] {
+    let mut size = [1; 3];
+    unsafe {
+        gl::GetProgramiv(id, gl::COMPUTE_WORK_GROUP_SIZE, size.as_mut_ptr());
+    }
+    size.map(|size| size as u32)
+}
+
+impl Drop for ComputeProgram {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteProgram(self.id);
+        }
+    }
+}
+
+/// Kinds of accesses which have to see the writes of earlier shaders, combined with `|`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Barrier(gl::types::GLbitfield);
+
+impl Barrier {
+    /// Vertex attributes sourced from buffers written by shaders
+    pub const VERTEX_ATTRIB_ARRAY: Barrier = Barrier(gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT);
+    /// Indices sourced from buffers written by shaders
+    pub const ELEMENT_ARRAY: Barrier = Barrier(gl::ELEMENT_ARRAY_BARRIER_BIT);
+    /// Uniform blocks sourced from buffers written by shaders
+    pub const UNIFORM: Barrier = Barrier(gl::UNIFORM_BARRIER_BIT);
+    /// Texture sampling of images written by shaders
+    pub const TEXTURE_FETCH: Barrier = Barrier(gl::TEXTURE_FETCH_BARRIER_BIT);
+    /// Image load and store of images written by shaders
+    pub const SHADER_IMAGE_ACCESS: Barrier = Barrier(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT);
+    /// Indirect draw and dispatch arguments written by shaders
+    pub const COMMAND: Barrier = Barrier(gl::COMMAND_BARRIER_BIT);
+    /// Buffer reads and writes through the API, such as reading back a buffer
+    pub const BUFFER_UPDATE: Barrier = Barrier(gl::BUFFER_UPDATE_BARRIER_BIT);
+    /// Shader storage blocks sourced from buffers written by shaders
+    pub const SHADER_STORAGE: Barrier = Barrier(gl::SHADER_STORAGE_BARRIER_BIT);
+    pub const ALL: Barrier = Barrier(gl::ALL_BARRIER_BITS);
+
+    /// Get the barrier's OpenGL bitfield.
+    pub fn bits(&self) -> gl::types::GLbitfield {
+        self.0
+    }
+
+    /// Returns true if all accesses of `other` are part of the barrier
+    pub fn contains(&self, other: Barrier) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for Barrier {
+    type Output = Barrier;
+
+    fn bitor(self, rhs: Barrier) -> Barrier {
+        Barrier(self.0 | rhs.0)
+    }
+}
+
+/// Orders the given accesses after the incoherent writes of earlier shaders, such as compute dispatches
+pub fn memory_barrier(barrier: Barrier) {
+    unsafe {
+        gl::MemoryBarrier(barrier.bits());
+    }
+}
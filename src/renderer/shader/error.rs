@@ -0,0 +1,24 @@
+use thiserror::Error;
+
+use super::ShaderKind;
+
+/// Error returned when a shader program can not be built or reloaded, carrying the compiler or linker log
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ShaderError {
+    /// The sources could not be loaded, preprocessed or split into stages
+    #[error("failed to load shader sources: {0}")]
+    Source(String),
+    #[error("failed to compile {kind:?}:\n{log}")]
+    Compile { kind: ShaderKind, log: String },
+    #[error("failed to link shader program:\n{0}")]
+    Link(String),
+    /// The program linked but its stages or attributes do not match what it is drawn with
+    #[error("invalid shader program: {0}")]
+    Invalid(String),
+}
+
+impl From<ShaderError> for String {
+    fn from(error: ShaderError) -> Self {
+        error.to_string()
+    }
+}
@@ -0,0 +1,97 @@
+use super::ShaderKind;
+
+/// Directive starting the section of a stage, followed by the stage's name
+pub const STAGE_PRAGMA: &str = "#pragma stage";
+
+/// A shader file holding the sources of several stages, each section starts with `#pragma stage <name>`. <br>
+/// Lines before the first section are shared by all stages. The source of a stage keeps the line numbers of the file,
+/// the lines of other sections are left empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderFile {
+    stages: Vec<(ShaderKind, String)>,
+}
+
+impl ShaderFile {
+    /// Splits a file into its stage sections, `file` is used in error messages
+    pub fn parse(file: &str, source: &str) -> Result<Self, String> {
+        let lines: Vec<_> = source.lines().collect();
+
+        // Stage and first line of every section
+        let mut sections: Vec<(ShaderKind, usize)> = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            let stage = match line.trim().strip_prefix(STAGE_PRAGMA) {
+                Some(stage) => stage.trim(),
+                None => continue,
+            };
+
+            let kind = match ShaderKind::from_name(stage) {
+                Some(kind) => kind,
+                None => return Err(format!("{}:{}: unknown shader stage {:?}", file, i + 1, stage)),
+            };
+
+            if sections.iter().any(|(other, _)| *other == kind) {
+                return Err(format!(
+                    "{}:{}: stage {:?} is declared more than once",
+                    file,
+                    i + 1,
+                    stage
+                ));
+            }
+            sections.push((kind, i));
+        }
+
+        let shared = match sections.first() {
+            Some((_, start)) => *start,
+            None => return Err(format!("{}: no `{} <name>` sections", file, STAGE_PRAGMA)),
+        };
+
+        let mut stages = Vec::new();
+        for (i, (kind, start)) in sections.iter().enumerate() {
+            let end = sections.get(i + 1).map_or(lines.len(), |(_, next)| *next);
+
+            let mut stage = String::new();
+            for (line, text) in lines.iter().enumerate() {
+                if line < shared || (line > *start && line < end) {
+                    stage.push_str(text);
+                }
+                stage.push('\n');
+            }
+            stages.push((*kind, stage));
+        }
+
+        Ok(ShaderFile { stages })
+    }
+
+    /// Returns true if the source contains stage sections
+    pub fn is_shader_file(source: &str) -> bool {
+        source.lines().any(|line| line.trim().starts_with(STAGE_PRAGMA))
+    }
+
+    /// Get the source of a stage.
+    pub fn stage(&self, kind: ShaderKind) -> Option<&str> {
+        self.stages
+            .iter()
+            .find(|(other, _)| *other == kind)
+            .map(|(_, source)| source.as_str())
+    }
+
+    /// Get the stages and their sources in the order they are declared.
+    pub fn stages(&self) -> impl Iterator<Item = (ShaderKind, &str)> {
+        self.stages.iter().map(|(kind, source)| (*kind, source.as_str()))
+    }
+
+    /// Get the declared stages.
+    pub fn kinds(&self) -> Vec<ShaderKind> {
+        self.stages.iter().map(|(kind, _)| *kind).collect()
+    }
+
+    /// Get the declared stages of a shader program, a compute section is left to a
+    /// [ComputeProgram](super::ComputeProgram) built from the same file.
+    pub fn program_kinds(&self) -> Vec<ShaderKind> {
+        self.stages
+            .iter()
+            .map(|(kind, _)| *kind)
+            .filter(|kind| *kind != ShaderKind::ComputeShader)
+            .collect()
+    }
+}
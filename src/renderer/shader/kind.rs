@@ -32,6 +32,19 @@ impl ShaderKind {
         }
     }
 
+    /// Get the stage of a name used by `#pragma stage`, such as `vertex` or `tess_control`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "vertex" => Some(ShaderKind::VertexShader),
+            "tess_control" => Some(ShaderKind::TessControlShader),
+            "tess_evaluation" => Some(ShaderKind::TessEvaluationShader),
+            "geometry" => Some(ShaderKind::GeometryShader),
+            "fragment" => Some(ShaderKind::FragmentShader),
+            "compute" => Some(ShaderKind::ComputeShader),
+            _ => None
+        }
+    }
+
     pub fn to_u8(&self) -> u8 {
         match self {
             ShaderKind::VertexShader => 0,
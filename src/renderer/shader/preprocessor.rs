@@ -0,0 +1,294 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::CString,
+};
+
+use crate::assets::AssetManager;
+
+/// Set of `#define`s a shader is compiled with, ordered so equal sets compare and hash equal
+pub type Defines = BTreeMap<String, String>;
+
+/// Version injected into sources without a `#version` directive
+pub const DEFAULT_VERSION: &str = "330 core";
+
+/// Loads the sources of included files
+pub trait IncludeResolver {
+    fn resolve(&mut self, name: &str) -> Result<String, String>;
+
+    /// Get the name a file is resolved with, `from` is the file including it. <br>
+    /// Names are used as written by default.
+    fn locate(&self, name: &str, _from: Option<&str>) -> String {
+        name.to_string()
+    }
+}
+
+/// Includes are looked up relative to the including file, the way assets are keyed by their path
+impl IncludeResolver for AssetManager {
+    fn resolve(&mut self, name: &str) -> Result<String, String> {
+        let asset = match self.asset(name) {
+            Some(asset) => asset,
+            None => return Err(format!("No asset at {:?}", name)),
+        };
+        String::from_utf8(asset.raw.clone()).map_err(|e| format!("Asset {:?} is not valid UTF-8: {}", name, e))
+    }
+
+    fn locate(&self, name: &str, from: Option<&str>) -> String {
+        match from.and_then(|from| std::path::Path::new(from).parent()) {
+            Some(directory) => AssetManager::key(directory.join(name)),
+            None => AssetManager::key(name),
+        }
+    }
+}
+
+/// Sources kept in memory, keyed by the name they are included with
+impl IncludeResolver for HashMap<String, String> {
+    fn resolve(&mut self, name: &str) -> Result<String, String> {
+        match self.get(name) {
+            Some(source) => Ok(source.clone()),
+            None => Err(format!("No source named {:?}", name)),
+        }
+    }
+}
+
+/// Expands `#include "file"` directives and prepends the `#version` and `#define` header of a shader source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Preprocessor {
+    version: String,
+    defines: Defines,
+}
+
+impl Preprocessor {
+    pub fn new() -> Self {
+        Preprocessor {
+            version: DEFAULT_VERSION.to_string(),
+            defines: Defines::new(),
+        }
+    }
+
+    /// Set the version injected into sources without their own `#version` directive
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = version.to_string();
+        self
+    }
+
+    /// Adds a `#define name value`, an empty value defines the name without a value
+    pub fn define(mut self, name: &str, value: &str) -> Self {
+        self.defines.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Adds all defines of a set, replacing defines of the same name
+    pub fn with_defines(mut self, defines: &Defines) -> Self {
+        self.defines
+            .extend(defines.iter().map(|(name, value)| (name.clone(), value.clone())));
+        self
+    }
+
+    /// Get a reference to the preprocessor's version.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Get a reference to the preprocessor's defines.
+    pub fn defines(&self) -> &Defines {
+        &self.defines
+    }
+
+    /// Loads a file and expands its includes. <br>
+    /// Every file is included at most once, include cycles are reported as errors.
+    pub fn process(&self, name: &str, resolver: &mut dyn IncludeResolver) -> Result<ProcessedSource, String> {
+        let source = resolver.resolve(name)?;
+        self.process_source(name, &source, resolver)
+    }
+
+    /// Expands the includes of a source which has already been loaded, `name` is used in error messages
+    pub fn process_source(
+        &self,
+        name: &str,
+        source: &str,
+        resolver: &mut dyn IncludeResolver,
+    ) -> Result<ProcessedSource, String> {
+        let name = resolver.locate(name, None);
+        let mut expansion = Expansion::default();
+        expansion.expand(&name, source, resolver, &mut vec![name.clone()])?;
+
+        let version = expansion.version.take().unwrap_or_else(|| self.version.clone());
+        let mut processed = ProcessedSource {
+            source: format!("#version {}\n", version),
+            lines: vec![None],
+            files: expansion.files,
+        };
+
+        for (name, value) in self.defines.iter() {
+            let define = match value.is_empty() {
+                true => format!("#define {}\n", name),
+                false => format!("#define {} {}\n", name, value),
+            };
+            processed.source.push_str(&define);
+            processed.lines.push(None);
+        }
+
+        for (line, location) in expansion.lines {
+            processed.source.push_str(&line);
+            processed.source.push('\n');
+            processed.lines.push(Some(location));
+        }
+
+        Ok(processed)
+    }
+}
+
+impl Default for Preprocessor {
+    fn default() -> Self {
+        Preprocessor::new()
+    }
+}
+
+/// Lines collected while expanding includes
+#[derive(Default)]
+struct Expansion {
+    lines: Vec<(String, (usize, usize))>,
+    files: Vec<String>,
+    version: Option<String>,
+}
+
+impl Expansion {
+    fn expand(
+        &mut self,
+        name: &str,
+        source: &str,
+        resolver: &mut dyn IncludeResolver,
+        stack: &mut Vec<String>,
+    ) -> Result<(), String> {
+        let file = self.files.len();
+        self.files.push(name.to_string());
+
+        for (i, line) in source.lines().enumerate() {
+            let directive = line.trim_start();
+
+            if let Some(version) = directive.strip_prefix("#version") {
+                // Only the version of the root file is kept, it has to be the first line of the output
+                match file {
+                    0 => self.version = Some(version.trim().to_string()),
+                    _ => trace!("Ignoring #version in included file {:?}", name),
+                }
+                continue;
+            }
+
+            let include = match directive.strip_prefix("#include") {
+                Some(include) => include.trim(),
+                None => {
+                    self.lines.push((line.to_string(), (file, i + 1)));
+                    continue;
+                }
+            };
+
+            let included = match include.strip_prefix('"').and_then(|include| include.strip_suffix('"')) {
+                Some(included) => included,
+                None => return Err(format!("{}:{}: expected #include \"file\"", name, i + 1)),
+            };
+
+            let path = resolver.locate(included, Some(name));
+            if stack.contains(&path) {
+                stack.push(path);
+                return Err(format!("{}:{}: include cycle {}", name, i + 1, stack.join(" -> ")));
+            }
+
+            if self.files.contains(&path) {
+                continue;
+            }
+
+            let source = resolver
+                .resolve(&path)
+                .map_err(|e| format!("{}:{}: failed to include {:?}, {}", name, i + 1, included, e))?;
+
+            stack.push(path.clone());
+            self.expand(&path, &source, resolver, stack)?;
+            stack.pop();
+        }
+
+        Ok(())
+    }
+}
+
+/// Source produced by a [Preprocessor], remembering which file and line every line came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessedSource {
+    source: String,
+    // Location of every output line, `None` for the injected header
+    lines: Vec<Option<(usize, usize)>>,
+    files: Vec<String>,
+}
+
+impl ProcessedSource {
+    /// Get a reference to the processed source.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Get the processed source as a CString for compilation.
+    pub fn to_cstring(&self) -> Result<CString, String> {
+        CString::new(self.source.as_str()).map_err(|e| format!("Shader source contains a nul byte: {}", e))
+    }
+
+    /// Get the files the source was built from, the root file first followed by its includes.
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    /// Returns true if the source was built from the file, named the way its [IncludeResolver] located it
+    pub fn depends_on(&self, file: &str) -> bool {
+        self.files.iter().any(|f| f == file)
+    }
+
+    /// Get the file and line a line of the processed source came from, `None` for injected lines. <br>
+    /// Lines are counted from 1 as in compiler logs.
+    pub fn location(&self, line: usize) -> Option<(&str, usize)> {
+        let (file, line) = (*self.lines.get(line.checked_sub(1)?)?)?;
+        Some((self.files[file].as_str(), line))
+    }
+
+    /// Rewrites the line numbers of a compiler log to the files they came from. <br>
+    /// Understands the `0:12(5)`, `ERROR: 0:12:` and `0(12) :` formats used by the common drivers.
+    pub fn map_log(&self, log: &str) -> String {
+        log.lines()
+            .map(|line| self.map_log_line(line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn map_log_line(&self, line: &str) -> String {
+        let bytes = line.as_bytes();
+        for (i, _) in line.match_indices('0') {
+            if i > 0 && bytes[i - 1].is_ascii_alphanumeric() {
+                continue;
+            }
+
+            let (open, close) = match bytes.get(i + 1) {
+                Some(b':') => (i + 2, None),
+                Some(b'(') => (i + 2, Some(b')')),
+                _ => continue,
+            };
+
+            let digits = bytes[open..].iter().take_while(|b| b.is_ascii_digit()).count();
+            if digits == 0 {
+                continue;
+            }
+
+            let mut end = open + digits;
+            if let Some(close) = close {
+                if bytes.get(end) != Some(&close) {
+                    continue;
+                }
+                end += 1;
+            }
+
+            let number = line[open..open + digits].parse().unwrap_or(0);
+            return match self.location(number) {
+                Some((file, original)) => format!("{}{}:{}{}", &line[..i], file, original, &line[end..]),
+                None => line.to_string(),
+            };
+        }
+        line.to_string()
+    }
+}
@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::renderer::render_target::vertex_array::{VertexDefiner, VertexFieldFormat};
+
+use super::UniformType;
+
+/// Error returned when a value can not be uploaded to a uniform
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum UniformError {
+    #[error("shader program has no active uniform {0:?}")]
+    Unknown(String),
+    #[error("uniform {name:?} is {expected:?} but {given:?} was given")]
+    TypeMismatch {
+        name: String,
+        expected: UniformType,
+        given: UniformType,
+    },
+    #[error("uniform {name:?} holds {size} elements but {given} were given")]
+    TooManyElements { name: String, size: usize, given: usize },
+}
+
+impl From<UniformError> for String {
+    fn from(error: UniformError) -> Self {
+        error.to_string()
+    }
+}
+
+/// An active uniform of a linked program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UniformInfo {
+    pub location: gl::types::GLint,
+    pub kind: UniformType,
+    /// Amount of array elements, 1 for single values
+    pub size: usize,
+}
+
+/// An active vertex attribute of a linked program, attribute types share their enums with uniforms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttributeInfo {
+    pub location: gl::types::GLint,
+    pub kind: UniformType,
+    /// Amount of array elements, 1 for single values
+    pub size: usize,
+}
+
+/// Interface a block is backed by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockKind {
+    /// `uniform` block, backed by a uniform buffer
+    Uniform,
+    /// `buffer` block, backed by a shader storage buffer
+    ShaderStorage,
+}
+
+impl BlockKind {
+    fn interfaces(&self) -> (gl::types::GLenum, gl::types::GLenum) {
+        match self {
+            BlockKind::Uniform => (gl::UNIFORM_BLOCK, gl::UNIFORM),
+            BlockKind::ShaderStorage => (gl::SHADER_STORAGE_BLOCK, gl::BUFFER_VARIABLE),
+        }
+    }
+}
+
+/// A member of an active block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockMember {
+    pub name: String,
+    pub kind: UniformType,
+    /// Byte offset of the member from the start of the block
+    pub offset: usize,
+}
+
+/// An active uniform or shader storage block of a linked program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockInfo {
+    pub kind: BlockKind,
+    pub index: gl::types::GLuint,
+    /// Buffer binding point the block reads from
+    pub binding: gl::types::GLuint,
+    /// Minimum byte size of the buffer backing the block
+    pub data_size: usize,
+    /// Members ordered by their offset
+    pub members: Vec<BlockMember>,
+}
+
+/// Everything a linked program declares and uses, queried once after linking
+#[derive(Debug, Clone, Default)]
+pub struct Reflection {
+    uniforms: HashMap<String, UniformInfo>,
+    attributes: HashMap<String, AttributeInfo>,
+    blocks: HashMap<String, BlockInfo>,
+}
+
+impl Reflection {
+    /// Queries the active uniforms, attributes and blocks of a linked program
+    ///
+    /// # Safety
+    /// `program` has to be a successfully linked program.
+    pub(super) unsafe fn query(program: gl::types::GLuint) -> Self {
+        let mut reflection = Reflection::default();
+
+        // Uniforms inside blocks have no location and are reflected as block members instead
+        for (name, location, kind, size) in active(program, gl::ACTIVE_UNIFORMS, gl::ACTIVE_UNIFORM_MAX_LENGTH) {
+            reflection.uniforms.insert(name, UniformInfo { location, kind, size });
+        }
+
+        // Built-in attributes such as gl_VertexID have no location
+        for (name, location, kind, size) in active(program, gl::ACTIVE_ATTRIBUTES, gl::ACTIVE_ATTRIBUTE_MAX_LENGTH) {
+            reflection
+                .attributes
+                .insert(name, AttributeInfo { location, kind, size });
+        }
+
+        // Program interface queries are only available from OpenGL 4.3
+        match gl::GetProgramResourceiv::is_loaded() {
+            true => {
+                for kind in [BlockKind::Uniform, BlockKind::ShaderStorage] {
+                    reflection.blocks.extend(blocks(program, kind));
+                }
+            }
+            false => trace!("Skipping block reflection, program interface queries are not supported"),
+        }
+
+        reflection
+    }
+
+    /// Get the uniform with the given name, arrays are named without their index.
+    pub fn uniform(&self, name: &str) -> Option<&UniformInfo> {
+        self.uniforms.get(name)
+    }
+
+    /// Get a reference to the reflection's uniforms.
+    pub fn uniforms(&self) -> &HashMap<String, UniformInfo> {
+        &self.uniforms
+    }
+
+    /// Get the vertex attribute with the given name.
+    pub fn attribute(&self, name: &str) -> Option<&AttributeInfo> {
+        self.attributes.get(name)
+    }
+
+    /// Get a reference to the reflection's attributes.
+    pub fn attributes(&self) -> &HashMap<String, AttributeInfo> {
+        &self.attributes
+    }
+
+    /// Get the uniform or shader storage block with the given name.
+    pub fn block(&self, name: &str) -> Option<&BlockInfo> {
+        self.blocks.get(name)
+    }
+
+    /// Get a reference to the reflection's blocks.
+    pub fn blocks(&self) -> &HashMap<String, BlockInfo> {
+        &self.blocks
+    }
+
+    /// Checks that every active attribute is fed by a field of the definer at the location it was bound to,
+    /// with a format matching the attribute's type. <br>
+    /// Fields without an active attribute are allowed, the compiler removes unused inputs.
+    pub fn validate_attributes(&self, definer: &VertexDefiner) -> Result<(), String> {
+        let fields: HashMap<&str, _> = definer
+            .field_locations()
+            .map(|(location, field)| (*field.name(), (location, field)))
+            .collect();
+
+        for (name, attribute) in self.attributes.iter() {
+            let (location, field) = match fields.get(name.as_str()) {
+                Some(field) => *field,
+                None => return Err(format!("Active attribute {:?} has no matching vertex field", name)),
+            };
+
+            if attribute.location != location as gl::types::GLint {
+                return Err(format!(
+                    "Attribute {:?} is at location {} but its vertex field is bound to {}",
+                    name, attribute.location, location
+                ));
+            }
+
+            if let Some(format) = attribute.kind.format() {
+                if format != field.format() {
+                    return Err(format!(
+                        "Attribute {:?} is {:?} but its vertex field is read as {:?}",
+                        name,
+                        attribute.kind,
+                        field.format()
+                    ));
+                }
+            }
+        }
+
+        for name in fields.keys().filter(|name| !self.attributes.contains_key(**name)) {
+            trace!("Vertex field {:?} has no active attribute", name);
+        }
+
+        Ok(())
+    }
+}
+
+impl UniformType {
+    /// Get the vertex field format an attribute of this type is read with, `None` for types without a format.
+    fn format(&self) -> Option<VertexFieldFormat> {
+        use UniformType::*;
+
+        match self {
+            Float | Vec2 | Vec3 | Vec4 | Mat2 | Mat3 | Mat4 | Mat2x3 | Mat2x4 | Mat3x2 | Mat3x4 | Mat4x2 | Mat4x3 => {
+                Some(VertexFieldFormat::Float)
+            }
+            Int | IVec2 | IVec3 | IVec4 | UInt | UVec2 | UVec3 | UVec4 => Some(VertexFieldFormat::Integer),
+            _ => None,
+        }
+    }
+}
+
+/// Queries the active uniforms or attributes of a program as (name, location, type, size), skipping those without a location
+unsafe fn active(
+    program: gl::types::GLuint,
+    count: gl::types::GLenum,
+    max_length: gl::types::GLenum,
+) -> Vec<(String, gl::types::GLint, UniformType, usize)> {
+    let (mut active, mut length) = (0, 0);
+    gl::GetProgramiv(program, count, &mut active);
+    gl::GetProgramiv(program, max_length, &mut length);
+
+    let mut name = vec![0u8; length.max(1) as usize];
+    let mut result = Vec::new();
+    for index in 0..active as gl::types::GLuint {
+        let (mut length, mut size, mut kind) = (0, 0, 0);
+        let buffer_size = name.len() as gl::types::GLsizei;
+        let ptr = name.as_mut_ptr() as *mut gl::types::GLchar;
+
+        let location = match count {
+            gl::ACTIVE_ATTRIBUTES => {
+                gl::GetActiveAttrib(program, index, buffer_size, &mut length, &mut size, &mut kind, ptr);
+                gl::GetAttribLocation(program, ptr)
+            }
+            _ => {
+                gl::GetActiveUniform(program, index, buffer_size, &mut length, &mut size, &mut kind, ptr);
+                gl::GetUniformLocation(program, ptr)
+            }
+        };
+
+        if location < 0 {
+            continue;
+        }
+
+        result.push((
+            plain_name(&name[..length as usize]),
+            location,
+            UniformType::from_gl(kind),
+            size as usize,
+        ));
+    }
+    result
+}
+
+/// Queries the blocks of one kind with their members through the program interface
+unsafe fn blocks(program: gl::types::GLuint, kind: BlockKind) -> Vec<(String, BlockInfo)> {
+    let (block_interface, member_interface) = kind.interfaces();
+
+    let mut count = 0;
+    gl::GetProgramInterfaceiv(program, block_interface, gl::ACTIVE_RESOURCES, &mut count);
+
+    let mut result = Vec::new();
+    for index in 0..count as gl::types::GLuint {
+        let [binding, data_size, members] = resource(
+            program,
+            block_interface,
+            index,
+            [gl::BUFFER_BINDING, gl::BUFFER_DATA_SIZE, gl::NUM_ACTIVE_VARIABLES],
+        );
+
+        let mut variables = vec![0; members as usize];
+        let property = gl::ACTIVE_VARIABLES;
+        gl::GetProgramResourceiv(
+            program,
+            block_interface,
+            index,
+            1,
+            &property,
+            variables.len() as gl::types::GLsizei,
+            std::ptr::null_mut(),
+            variables.as_mut_ptr(),
+        );
+
+        let mut members: Vec<_> = variables
+            .into_iter()
+            .map(|variable| {
+                let variable = variable as gl::types::GLuint;
+                let [kind, offset] = resource(program, member_interface, variable, [gl::TYPE, gl::OFFSET]);
+                BlockMember {
+                    name: resource_name(program, member_interface, variable),
+                    kind: UniformType::from_gl(kind as gl::types::GLenum),
+                    offset: offset as usize,
+                }
+            })
+            .collect();
+        members.sort_by_key(|member| member.offset);
+
+        let info = BlockInfo {
+            kind,
+            index,
+            binding: binding as gl::types::GLuint,
+            data_size: data_size as usize,
+            members,
+        };
+        result.push((resource_name(program, block_interface, index), info));
+    }
+    result
+}
+
+/// Queries a fixed set of integer properties of a program resource
+unsafe fn resource<const N: usize>(
+    program: gl::types::GLuint,
+    interface: gl::types::GLenum,
+    index: gl::types::GLuint,
+    properties: [gl::types::GLenum; N],
+) -> [gl::types::GLint; N] {
+    let mut values = [0; N];
+    gl::GetProgramResourceiv(
+        program,
+        interface,
+        index,
+        N as gl::types::GLsizei,
+        properties.as_ptr(),
+        N as gl::types::GLsizei,
+        std::ptr::null_mut(),
+        values.as_mut_ptr(),
+    );
+    values
+}
+
+unsafe fn resource_name(program: gl::types::GLuint, interface: gl::types::GLenum, index: gl::types::GLuint) -> String {
+    let [length] = resource(program, interface, index, [gl::NAME_LENGTH]);
+
+    let mut name = vec![0u8; length.max(1) as usize];
+    let mut written = 0;
+    gl::GetProgramResourceName(
+        program,
+        interface,
+        index,
+        name.len() as gl::types::GLsizei,
+        &mut written,
+        name.as_mut_ptr() as *mut gl::types::GLchar,
+    );
+    plain_name(&name[..written as usize])
+}
+
+/// Arrays are reported as `name[0]`, they are looked up through their plain name
+fn plain_name(name: &[u8]) -> String {
+    let name = String::from_utf8_lossy(name);
+    name.strip_suffix("[0]").unwrap_or(&name).to_string()
+}
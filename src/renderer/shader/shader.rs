@@ -4,15 +4,20 @@ use super::kind::ShaderKind;
 
 mod vertexshader;
 mod fragmentshader;
+mod computeshader;
+mod stage;
 
 pub use vertexshader::VertexShader;
 pub use fragmentshader::FragmentShader;
+pub use computeshader::ComputeShader;
+pub use stage::ShaderStage;
 
 pub trait Shader {
     fn id(&self) -> gl::types::GLuint;
     fn kind(&self) -> ShaderKind;
 
-    fn recompile(&mut self, src: &CStr);
+    /// Compiles the shader again from a new source, a failed compilation leaves the shader without a binary
+    fn recompile(&mut self, src: &CStr) -> Result<(), String>;
 
     /// Compiles the program from source
     fn compile(&mut self, source: &CStr) -> Result<(), String> {
@@ -0,0 +1,43 @@
+use std::ffi::CStr;
+
+use crate::renderer::shader::kind::ShaderKind;
+
+use super::Shader;
+
+pub struct ComputeShader {
+    id: gl::types::GLuint,
+}
+
+impl ComputeShader {
+    pub fn from_source(src: &CStr) -> Result<Self, String> {
+        // Create shader
+        let id = unsafe { gl::CreateShader(ShaderKind::as_opengl_enum(&ShaderKind::ComputeShader)) };
+
+        let mut cs = ComputeShader { id };
+        cs.compile(src)?;
+
+        Ok(cs)
+    }
+}
+
+impl Shader for ComputeShader {
+    fn id(&self) -> gl::types::GLuint {
+        self.id
+    }
+
+    fn kind(&self) -> ShaderKind {
+        ShaderKind::ComputeShader
+    }
+
+    fn recompile(&mut self, src: &CStr) -> Result<(), String> {
+        self.compile(src)
+    }
+}
+
+impl Drop for ComputeShader {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteShader(self.id());
+        }
+    }
+}
@@ -30,8 +30,8 @@ impl Shader for FragmentShader {
         ShaderKind::FragmentShader
     }
 
-    fn recompile(&mut self, src: &CStr) {
-        self.compile(src).expect("Failed to recompile shader");
+    fn recompile(&mut self, src: &CStr) -> Result<(), String> {
+        self.compile(src)
     }
 }
 
@@ -0,0 +1,51 @@
+use std::ffi::CStr;
+
+use crate::renderer::shader::kind::ShaderKind;
+
+use super::Shader;
+
+/// A shader of any stage, used for the stages without a dedicated type such as geometry and tessellation shaders
+pub struct ShaderStage {
+    id: gl::types::GLuint,
+    kind: ShaderKind,
+}
+
+impl ShaderStage {
+    /// Creates a shader object without a source, it has to be compiled before it is linked
+    pub fn new(kind: ShaderKind) -> Self {
+        let id = unsafe { gl::CreateShader(kind.as_opengl_enum()) };
+        ShaderStage { id, kind }
+    }
+
+    pub fn from_source(kind: ShaderKind, src: &CStr) -> Result<Self, String> {
+        // Create shader
+        let mut stage = ShaderStage::new(kind);
+        stage
+            .compile(src)
+            .map_err(|e| format!("Failed to compile {:?}: {}", kind, e))?;
+
+        Ok(stage)
+    }
+}
+
+impl Shader for ShaderStage {
+    fn id(&self) -> gl::types::GLuint {
+        self.id
+    }
+
+    fn kind(&self) -> ShaderKind {
+        self.kind
+    }
+
+    fn recompile(&mut self, src: &CStr) -> Result<(), String> {
+        self.compile(src)
+    }
+}
+
+impl Drop for ShaderStage {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteShader(self.id());
+        }
+    }
+}
@@ -29,8 +29,8 @@ impl Shader for VertexShader {
         ShaderKind::VertexShader
     }
 
-    fn recompile(&mut self, src: &CStr) {
-        self.compile(src).expect("Failed to recompile shader");
+    fn recompile(&mut self, src: &CStr) -> Result<(), String> {
+        self.compile(src)
     }
 }
 
@@ -1,42 +1,405 @@
-#[cfg_attr(rustfmt, rustfmt_skip)]
-pub trait Uniform<'a> {
-    fn uniform1f(&mut self, location: &'a str, v0: f32) -> Result<(), String>;
-    fn uniform2f(&mut self, location: &'a str, v0: f32, v1: f32) -> Result<(), String>;
-    fn uniform3f(&mut self, location: &'a str, v0: f32, v1: f32, v2: f32) -> Result<(), String>;
-    fn uniform4f(&mut self, location: &'a str, v0: f32, v1: f32, v2: f32, v3: f32) -> Result<(), String>;
-
-    fn uniform1i(&mut self, location: &'a str, v0: i32) -> Result<(), String>;
-    fn uniform2i(&mut self, location: &'a str, v0: i32, v1: i32) -> Result<(), String>;
-    fn uniform3i(&mut self, location: &'a str, v0: i32, v1: i32, v2: i32) -> Result<(), String>;
-    fn uniform4i(&mut self, location: &'a str, v0: i32, v1: i32, v2: i32, v3: i32) -> Result<(), String>;
-
-    fn uniform1ui(&mut self, location: &'a str, v0: u32) -> Result<(), String>;
-    fn uniform2ui(&mut self, location: &'a str, v0: u32, v1: u32) -> Result<(), String>;
-    fn uniform3ui(&mut self, location: &'a str, v0: u32, v1: u32, v2: u32) -> Result<(), String>;
-    fn uniform4ui(&mut self, location: &'a str, v0: u32, v1: u32, v2: u32, v3: u32) -> Result<(), String>;
-
-    fn uniform1fv(&mut self, location: &'a str, count: gl::types::GLsizei, value: [f32; 1]) -> Result<(), String>;
-    fn uniform2fv(&mut self, location: &'a str, count: gl::types::GLsizei, value: [f32; 2]) -> Result<(), String>;
-    fn uniform3fv(&mut self, location: &'a str, count: gl::types::GLsizei, value: [f32; 3]) -> Result<(), String>;
-    fn uniform4fv(&mut self, location: &'a str, count: gl::types::GLsizei, value: [f32; 4]) -> Result<(), String>;
-
-    fn uniform1iv(&mut self, location: &'a str, count: gl::types::GLsizei, value: [i32; 1]) -> Result<(), String>;
-    fn uniform2iv(&mut self, location: &'a str, count: gl::types::GLsizei, value: [i32; 2]) -> Result<(), String>;
-    fn uniform3iv(&mut self, location: &'a str, count: gl::types::GLsizei, value: [i32; 3]) -> Result<(), String>;
-    fn uniform4iv(&mut self, location: &'a str, count: gl::types::GLsizei, value: [i32; 4]) -> Result<(), String>;
-
-    fn uniform1uiv(&mut self, location: &'a str, count: gl::types::GLsizei, value: [u32; 1]) -> Result<(), String>;
-    fn uniform2uiv(&mut self, location: &'a str, count: gl::types::GLsizei, value: [u32; 2]) -> Result<(), String>;
-    fn uniform3uiv(&mut self, location: &'a str, count: gl::types::GLsizei, value: [u32; 3]) -> Result<(), String>;
-    fn uniform4uiv(&mut self, location: &'a str, count: gl::types::GLsizei, value: [u32; 4]) -> Result<(), String>;
-
-    fn uniform_matrix2fv(&mut self, location: &'a str, count: gl::types::GLsizei, transpose: gl::types::GLboolean, value: [f32; 4]) -> Result<(), String>;
-    fn uniform_matrix3fv(&mut self, location: &'a str, count: gl::types::GLsizei, transpose: gl::types::GLboolean, value: [f32; 9]) -> Result<(), String>;
-    fn uniform_matrix4fv(&mut self, location: &'a str, count: gl::types::GLsizei, transpose: gl::types::GLboolean, value: [f32; 16]) -> Result<(), String>;
-    fn uniform_matrix2x3fv(&mut self, location: &'a str, count: gl::types::GLsizei, transpose: gl::types::GLboolean, value: [f32; 6]) -> Result<(), String>;
-    fn uniform_matrix3x2fv(&mut self, location: &'a str, count: gl::types::GLsizei, transpose: gl::types::GLboolean, value: [f32; 6]) -> Result<(), String>;
-    fn uniform_matrix2x4fv(&mut self, location: &'a str, count: gl::types::GLsizei, transpose: gl::types::GLboolean, value: [f32; 8]) -> Result<(), String>;
-    fn uniform_matrix4x2fv(&mut self, location: &'a str, count: gl::types::GLsizei, transpose: gl::types::GLboolean, value: [f32; 8]) -> Result<(), String>;
-    fn uniform_matrix3x4fv(&mut self, location: &'a str, count: gl::types::GLsizei, transpose: gl::types::GLboolean, value: [f32; 12]) -> Result<(), String>;
-    fn uniform_matrix4x3fv(&mut self, location: &'a str, count: gl::types::GLsizei, transpose: gl::types::GLboolean, value: [f32; 12]) -> Result<(), String>;
+extern crate nalgebra as na;
+
+use std::collections::HashMap;
+
+use crate::color::prelude::{RGBAColor, RGBColor};
+
+use super::{Reflection, UniformError};
+
+/// GLSL type of a uniform, reflected from linked programs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UniformType {
+    Float,
+    Vec2,
+    Vec3,
+    Vec4,
+    Int,
+    IVec2,
+    IVec3,
+    IVec4,
+    UInt,
+    UVec2,
+    UVec3,
+    UVec4,
+    Bool,
+    BVec2,
+    BVec3,
+    BVec4,
+    Mat2,
+    Mat3,
+    Mat4,
+    Mat2x3,
+    Mat2x4,
+    Mat3x2,
+    Mat3x4,
+    Mat4x2,
+    Mat4x3,
+    /// Any sampler, assigned a texture unit
+    Sampler,
+    /// A type without a [UniformValue], such as images or doubles
+    Other(gl::types::GLenum),
+}
+
+impl UniformType {
+    pub fn from_gl(kind: gl::types::GLenum) -> Self {
+        match kind {
+            gl::FLOAT => UniformType::Float,
+            gl::FLOAT_VEC2 => UniformType::Vec2,
+            gl::FLOAT_VEC3 => UniformType::Vec3,
+            gl::FLOAT_VEC4 => UniformType::Vec4,
+            gl::INT => UniformType::Int,
+            gl::INT_VEC2 => UniformType::IVec2,
+            gl::INT_VEC3 => UniformType::IVec3,
+            gl::INT_VEC4 => UniformType::IVec4,
+            gl::UNSIGNED_INT => UniformType::UInt,
+            gl::UNSIGNED_INT_VEC2 => UniformType::UVec2,
+            gl::UNSIGNED_INT_VEC3 => UniformType::UVec3,
+            gl::UNSIGNED_INT_VEC4 => UniformType::UVec4,
+            gl::BOOL => UniformType::Bool,
+            gl::BOOL_VEC2 => UniformType::BVec2,
+            gl::BOOL_VEC3 => UniformType::BVec3,
+            gl::BOOL_VEC4 => UniformType::BVec4,
+            gl::FLOAT_MAT2 => UniformType::Mat2,
+            gl::FLOAT_MAT3 => UniformType::Mat3,
+            gl::FLOAT_MAT4 => UniformType::Mat4,
+            gl::FLOAT_MAT2x3 => UniformType::Mat2x3,
+            gl::FLOAT_MAT2x4 => UniformType::Mat2x4,
+            gl::FLOAT_MAT3x2 => UniformType::Mat3x2,
+            gl::FLOAT_MAT3x4 => UniformType::Mat3x4,
+            gl::FLOAT_MAT4x2 => UniformType::Mat4x2,
+            gl::FLOAT_MAT4x3 => UniformType::Mat4x3,
+            gl::SAMPLER_1D
+            | gl::SAMPLER_2D
+            | gl::SAMPLER_3D
+            | gl::SAMPLER_CUBE
+            | gl::SAMPLER_1D_SHADOW
+            | gl::SAMPLER_2D_SHADOW
+            | gl::SAMPLER_1D_ARRAY
+            | gl::SAMPLER_2D_ARRAY
+            | gl::SAMPLER_1D_ARRAY_SHADOW
+            | gl::SAMPLER_2D_ARRAY_SHADOW
+            | gl::SAMPLER_2D_MULTISAMPLE
+            | gl::SAMPLER_2D_MULTISAMPLE_ARRAY
+            | gl::SAMPLER_CUBE_SHADOW
+            | gl::SAMPLER_BUFFER
+            | gl::SAMPLER_2D_RECT
+            | gl::SAMPLER_2D_RECT_SHADOW
+            | gl::INT_SAMPLER_1D
+            | gl::INT_SAMPLER_2D
+            | gl::INT_SAMPLER_3D
+            | gl::INT_SAMPLER_CUBE
+            | gl::INT_SAMPLER_1D_ARRAY
+            | gl::INT_SAMPLER_2D_ARRAY
+            | gl::INT_SAMPLER_2D_MULTISAMPLE
+            | gl::INT_SAMPLER_2D_MULTISAMPLE_ARRAY
+            | gl::INT_SAMPLER_BUFFER
+            | gl::INT_SAMPLER_2D_RECT
+            | gl::UNSIGNED_INT_SAMPLER_1D
+            | gl::UNSIGNED_INT_SAMPLER_2D
+            | gl::UNSIGNED_INT_SAMPLER_3D
+            | gl::UNSIGNED_INT_SAMPLER_CUBE
+            | gl::UNSIGNED_INT_SAMPLER_1D_ARRAY
+            | gl::UNSIGNED_INT_SAMPLER_2D_ARRAY
+            | gl::UNSIGNED_INT_SAMPLER_2D_MULTISAMPLE
+            | gl::UNSIGNED_INT_SAMPLER_2D_MULTISAMPLE_ARRAY
+            | gl::UNSIGNED_INT_SAMPLER_BUFFER
+            | gl::UNSIGNED_INT_SAMPLER_2D_RECT => UniformType::Sampler,
+            other => UniformType::Other(other),
+        }
+    }
+}
+
+/// Components of one or more uniform values, laid out the way `glUniform*v` expects them
+#[derive(Debug, Clone, PartialEq)]
+pub enum UniformData {
+    Float(Vec<f32>),
+    Int(Vec<i32>),
+    UInt(Vec<u32>),
+}
+
+/// A single value which can be uploaded to a uniform, slices and arrays of it upload uniform arrays
+pub trait UniformElement {
+    /// GLSL type the value is uploaded as
+    const TYPE: UniformType;
+
+    /// Appends the value's components, `data` is created from an empty value of the same type
+    fn write(&self, data: &mut UniformData);
+
+    fn empty() -> UniformData;
+}
+
+/// A value or array of values which can be uploaded with [ShaderProgram::set_uniform](super::ShaderProgram::set_uniform)
+pub trait UniformValue {
+    fn uniform_type(&self) -> UniformType;
+
+    /// Get the amount of array elements, 1 for single values.
+    fn count(&self) -> usize;
+
+    fn data(&self) -> UniformData;
+}
+
+impl<T> UniformValue for T
+where
+    T: UniformElement,
+{
+    fn uniform_type(&self) -> UniformType {
+        T::TYPE
+    }
+
+    fn count(&self) -> usize {
+        1
+    }
+
+    fn data(&self) -> UniformData {
+        let mut data = T::empty();
+        self.write(&mut data);
+        data
+    }
+}
+
+impl<T> UniformValue for [T]
+where
+    T: UniformElement,
+{
+    fn uniform_type(&self) -> UniformType {
+        T::TYPE
+    }
+
+    fn count(&self) -> usize {
+        self.len()
+    }
+
+    fn data(&self) -> UniformData {
+        let mut data = T::empty();
+        for value in self {
+            value.write(&mut data);
+        }
+        data
+    }
+}
+
+impl<T, const N: usize> UniformValue for [T; N]
+where
+    T: UniformElement,
+{
+    fn uniform_type(&self) -> UniformType {
+        T::TYPE
+    }
+
+    fn count(&self) -> usize {
+        N
+    }
+
+    fn data(&self) -> UniformData {
+        self[..].data()
+    }
+}
+
+impl<T> UniformValue for Vec<T>
+where
+    T: UniformElement,
+{
+    fn uniform_type(&self) -> UniformType {
+        T::TYPE
+    }
+
+    fn count(&self) -> usize {
+        self.len()
+    }
+
+    fn data(&self) -> UniformData {
+        self[..].data()
+    }
+}
+
+/// Texture unit a sampler uniform reads from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureUnit(pub u32);
+
+macro_rules! impl_uniform_element {
+    ($ty:ty, $uniform:ident, $data:ident, |$value:ident| $components:expr) => {
+        impl UniformElement for $ty {
+            const TYPE: UniformType = UniformType::$uniform;
+
+            fn write(&self, data: &mut UniformData) {
+                let $value = self;
+                if let UniformData::$data(data) = data {
+                    data.extend_from_slice(&$components);
+                }
+            }
+
+            fn empty() -> UniformData {
+                UniformData::$data(Vec::new())
+            }
+        }
+    };
+}
+
+impl_uniform_element!(f32, Float, Float, |v| [*v]);
+impl_uniform_element!(i32, Int, Int, |v| [*v]);
+impl_uniform_element!(u32, UInt, UInt, |v| [*v]);
+impl_uniform_element!(bool, Bool, Int, |v| [*v as i32]);
+impl_uniform_element!(TextureUnit, Sampler, Int, |v| [v.0 as i32]);
+
+impl_uniform_element!(na::Vector2<f32>, Vec2, Float, |v| v.as_slice());
+impl_uniform_element!(na::Vector3<f32>, Vec3, Float, |v| v.as_slice());
+impl_uniform_element!(na::Vector4<f32>, Vec4, Float, |v| v.as_slice());
+impl_uniform_element!(na::Point2<f32>, Vec2, Float, |v| v.coords.as_slice());
+impl_uniform_element!(na::Point3<f32>, Vec3, Float, |v| v.coords.as_slice());
+impl_uniform_element!(na::Vector2<i32>, IVec2, Int, |v| v.as_slice());
+impl_uniform_element!(na::Vector3<i32>, IVec3, Int, |v| v.as_slice());
+impl_uniform_element!(na::Vector4<i32>, IVec4, Int, |v| v.as_slice());
+impl_uniform_element!(na::Vector2<u32>, UVec2, UInt, |v| v.as_slice());
+impl_uniform_element!(na::Vector3<u32>, UVec3, UInt, |v| v.as_slice());
+impl_uniform_element!(na::Vector4<u32>, UVec4, UInt, |v| v.as_slice());
+
+// Both nalgebra and GLSL store matrices column major, GLSL's matCxR has C columns and R rows
+impl_uniform_element!(na::Matrix2<f32>, Mat2, Float, |v| v.as_slice());
+impl_uniform_element!(na::Matrix3<f32>, Mat3, Float, |v| v.as_slice());
+impl_uniform_element!(na::Matrix4<f32>, Mat4, Float, |v| v.as_slice());
+impl_uniform_element!(na::Matrix3x2<f32>, Mat2x3, Float, |v| v.as_slice());
+impl_uniform_element!(na::Matrix4x2<f32>, Mat2x4, Float, |v| v.as_slice());
+impl_uniform_element!(na::Matrix2x3<f32>, Mat3x2, Float, |v| v.as_slice());
+impl_uniform_element!(na::Matrix4x3<f32>, Mat3x4, Float, |v| v.as_slice());
+impl_uniform_element!(na::Matrix2x4<f32>, Mat4x2, Float, |v| v.as_slice());
+impl_uniform_element!(na::Matrix3x4<f32>, Mat4x3, Float, |v| v.as_slice());
+
+impl_uniform_element!(RGBColor<f32>, Vec3, Float, |v| [v.r, v.g, v.b]);
+impl_uniform_element!(RGBAColor<f32>, Vec4, Float, |v| [v.r, v.g, v.b, v.a]);
+
+/// Returns true if a value of type `given` can be uploaded to a uniform of type `expected`
+pub fn is_compatible(expected: UniformType, given: UniformType) -> bool {
+    use UniformType::*;
+
+    // Booleans accept both booleans and integers and samplers accept plain units, as glUniform*i does
+    match (expected, given) {
+        (Bool, Int) | (Int, Bool) => true,
+        (Sampler, Int) => true,
+        (BVec2, IVec2) | (BVec3, IVec3) | (BVec4, IVec4) => true,
+        _ => expected == given,
+    }
+}
+
+/// A value uploaded to a uniform, kept to upload it again after the program is relinked
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct StoredUniform {
+    kind: UniformType,
+    count: usize,
+    data: UniformData,
+}
+
+impl StoredUniform {
+    pub(super) fn new<T>(value: &T) -> Self
+    where
+        T: UniformValue + ?Sized,
+    {
+        StoredUniform {
+            kind: value.uniform_type(),
+            count: value.count(),
+            data: value.data(),
+        }
+    }
+}
+
+/// Uploads a value to a reflected uniform of the bound program, checked against its type and array size. <br>
+/// The value is stored in `values` to be uploaded again once the program is relinked.
+pub(super) fn set<T>(
+    reflection: &Reflection,
+    values: &mut HashMap<String, StoredUniform>,
+    name: &str,
+    value: &T,
+) -> Result<(), UniformError>
+where
+    T: UniformValue + ?Sized,
+{
+    let value = StoredUniform::new(value);
+    apply(reflection, name, &value)?;
+
+    match values.get_mut(name) {
+        Some(stored) => *stored = value,
+        None => {
+            values.insert(name.to_string(), value);
+        }
+    }
+    Ok(())
+}
+
+/// Uploads a stored value to a reflected uniform of the bound program, checked against its type and array size
+pub(super) fn apply(reflection: &Reflection, name: &str, value: &StoredUniform) -> Result<(), UniformError> {
+    let info = match reflection.uniform(name) {
+        Some(info) => *info,
+        None => return Err(UniformError::Unknown(name.to_string())),
+    };
+
+    if !is_compatible(info.kind, value.kind) {
+        return Err(UniformError::TypeMismatch {
+            name: name.to_string(),
+            expected: info.kind,
+            given: value.kind,
+        });
+    }
+
+    if value.count > info.size {
+        return Err(UniformError::TooManyElements {
+            name: name.to_string(),
+            size: info.size,
+            given: value.count,
+        });
+    }
+
+    if value.count > 0 {
+        unsafe { upload(info.location, value.kind, value.count, &value.data) };
+    }
+    Ok(())
+}
+
+/// Uploads data to a location of the bound program
+///
+/// # Safety
+/// `count` has to match the elements in `data` for the given uniform type.
+unsafe fn upload(location: gl::types::GLint, kind: UniformType, count: usize, data: &UniformData) {
+    use UniformType::*;
+
+    let count = count as gl::types::GLsizei;
+    match data {
+        UniformData::Float(data) => {
+            let ptr = data.as_ptr();
+            match kind {
+                Float => gl::Uniform1fv(location, count, ptr),
+                Vec2 => gl::Uniform2fv(location, count, ptr),
+                Vec3 => gl::Uniform3fv(location, count, ptr),
+                Vec4 => gl::Uniform4fv(location, count, ptr),
+                Mat2 => gl::UniformMatrix2fv(location, count, gl::FALSE, ptr),
+                Mat3 => gl::UniformMatrix3fv(location, count, gl::FALSE, ptr),
+                Mat4 => gl::UniformMatrix4fv(location, count, gl::FALSE, ptr),
+                Mat2x3 => gl::UniformMatrix2x3fv(location, count, gl::FALSE, ptr),
+                Mat2x4 => gl::UniformMatrix2x4fv(location, count, gl::FALSE, ptr),
+                Mat3x2 => gl::UniformMatrix3x2fv(location, count, gl::FALSE, ptr),
+                Mat3x4 => gl::UniformMatrix3x4fv(location, count, gl::FALSE, ptr),
+                Mat4x2 => gl::UniformMatrix4x2fv(location, count, gl::FALSE, ptr),
+                Mat4x3 => gl::UniformMatrix4x3fv(location, count, gl::FALSE, ptr),
+                _ => (),
+            }
+        }
+        UniformData::Int(data) => {
+            let ptr = data.as_ptr();
+            match kind {
+                Int | Bool | Sampler => gl::Uniform1iv(location, count, ptr),
+                IVec2 | BVec2 => gl::Uniform2iv(location, count, ptr),
+                IVec3 | BVec3 => gl::Uniform3iv(location, count, ptr),
+                IVec4 | BVec4 => gl::Uniform4iv(location, count, ptr),
+                _ => (),
+            }
+        }
+        UniformData::UInt(data) => {
+            let ptr = data.as_ptr();
+            match kind {
+                UInt => gl::Uniform1uiv(location, count, ptr),
+                UVec2 => gl::Uniform2uiv(location, count, ptr),
+                UVec3 => gl::Uniform3uiv(location, count, ptr),
+                UVec4 => gl::Uniform4uiv(location, count, ptr),
+                _ => (),
+            }
+        }
+    }
 }
@@ -0,0 +1,108 @@
+use glutin::{Context, PossiblyCurrent};
+
+use super::GLWindow;
+
+/// The OpenGL context a renderer draws into, either backed by a window or offscreen
+pub enum Surface {
+    Windowed(GLWindow),
+    Headless {
+        context: Context<PossiblyCurrent>,
+        size: (u32, u32),
+    },
+}
+
+impl Surface {
+    /// Creates an offscreen context with a framebuffer of the given size. <br>
+    /// On unix the context is backed by OSMesa and does not require a display.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))]
+    pub fn headless(width: u32, height: u32) -> Result<Self, String> {
+        use glutin::platform::unix::HeadlessContextExt;
+
+        let context = glutin::ContextBuilder::new()
+            .with_depth_buffer(24)
+            .build_osmesa(glutin::dpi::PhysicalSize::new(width, height))
+            .map_err(|e| e.to_string())?;
+
+        Self::make_headless_current(context, width, height)
+    }
+
+    /// Creates an offscreen context with a framebuffer of the given size.
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    )))]
+    pub fn headless(width: u32, height: u32) -> Result<Self, String> {
+        let event_loop = glutin::event_loop::EventLoop::new();
+        let context = glutin::ContextBuilder::new()
+            .with_depth_buffer(24)
+            .build_headless(&event_loop, glutin::dpi::PhysicalSize::new(width, height))
+            .map_err(|e| e.to_string())?;
+
+        Self::make_headless_current(context, width, height)
+    }
+
+    fn make_headless_current(
+        context: Context<glutin::NotCurrent>,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, String> {
+        let context = unsafe { context.make_current().map_err(|(_, e)| e.to_string())? };
+
+        Ok(Surface::Headless {
+            context,
+            size: (width, height),
+        })
+    }
+
+    /// Get the address of an OpenGL function
+    pub fn get_proc_address(&self, symbol: &str) -> *const std::ffi::c_void {
+        match self {
+            Surface::Windowed(window) => window.get_proc_address(symbol),
+            Surface::Headless { context, .. } => context.get_proc_address(symbol),
+        }
+    }
+
+    /// Present the current frame. Headless surfaces have nothing to present
+    /// and instead wait for all pending commands to finish.
+    pub fn swap_buffers(&self) -> Result<(), String> {
+        match self {
+            Surface::Windowed(window) => window.swap_buffers().map_err(|e| e.to_string()),
+            Surface::Headless { .. } => {
+                unsafe {
+                    gl::Finish();
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Get the size of the surface's framebuffer in pixels
+    pub fn size(&self) -> (u32, u32) {
+        match self {
+            Surface::Windowed(window) => window.window().inner_size().into(),
+            Surface::Headless { size, .. } => *size,
+        }
+    }
+
+    /// Get a reference to the surface's window, `None` if the surface is headless.
+    pub fn window(&self) -> Option<&GLWindow> {
+        match self {
+            Surface::Windowed(window) => Some(window),
+            Surface::Headless { .. } => None,
+        }
+    }
+
+    /// Returns true if the surface renders offscreen
+    pub fn is_headless(&self) -> bool {
+        matches!(self, Surface::Headless { .. })
+    }
+}
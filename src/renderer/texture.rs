@@ -79,25 +79,31 @@ impl Texture {
             gl::BindTexture(gl::TEXTURE_2D, self.id);
         }
 
-        // set correct internal format for opengl
-        let format = match self.format {
-            Format::RGB8 => gl::RGB,
-            Format::RGBA8 => gl::RGBA,
+        // set correct internal format for opengl, sized so the texture can be bound as an image
+        let (internal, format) = match self.format {
+            Format::RGB8 => (gl::RGB8, gl::RGB),
+            Format::RGBA8 => (gl::RGBA8, gl::RGBA),
             _ => unreachable!(),
         };
 
+        // textures without data are allocated uninitialized, such as outputs of compute shaders
+        let data = match self.data.as_ref() {
+            Some(data) => data.as_ptr() as *const gl::types::GLvoid,
+            None => std::ptr::null(),
+        };
+
         // upload texture data and generate mipmap
         unsafe {
             gl::TexImage2D(
                 gl::TEXTURE_2D,
                 0,
-                format as gl::types::GLint,
+                internal as gl::types::GLint,
                 self.width as gl::types::GLint,
                 self.height as gl::types::GLint,
                 0,
                 format,
                 gl::UNSIGNED_BYTE,
-                self.data.as_ref().unwrap().as_ptr() as *const gl::types::GLvoid,
+                data,
             );
             gl::GenerateMipmap(gl::TEXTURE_2D);
         }
@@ -110,4 +116,40 @@ impl Texture {
             gl::BindTexture(gl::TEXTURE_2D, self.id);
         }
     }
+
+    /// Binds the base level of the texture to an image unit for load and store in shaders
+    pub fn bind_image(&self, unit: u32, access: ImageAccess) -> Result<(), String> {
+        let format = match self.format {
+            Format::RGBA8 => gl::RGBA8,
+            format => return Err(format!("Textures of format {:?} can not be bound as images", format)),
+        };
+
+        unsafe {
+            gl::BindImageTexture(unit, self.id, 0, gl::FALSE, 0, access.get_opengl_enum(), format);
+        }
+        Ok(())
+    }
+
+    /// Get the texture's id.
+    pub fn id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+/// How a shader accesses an image bound with [Texture::bind_image]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageAccess {
+    ReadOnly,
+    WriteOnly,
+    ReadWrite,
+}
+
+impl ImageAccess {
+    pub fn get_opengl_enum(&self) -> gl::types::GLenum {
+        match self {
+            ImageAccess::ReadOnly => gl::READ_ONLY,
+            ImageAccess::WriteOnly => gl::WRITE_ONLY,
+            ImageAccess::ReadWrite => gl::READ_WRITE,
+        }
+    }
 }
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use image::io::Reader as ImageReader;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Format {
     Unknown = 0,
     RGB8 = 1,
@@ -18,6 +18,15 @@ pub struct Image {
 }
 
 impl Image {
+    pub fn new(format: Format, width: u32, height: u32, data: Vec<u8>) -> Image {
+        Image {
+            format,
+            width,
+            height,
+            data: Some(data),
+        }
+    }
+
     pub fn read_image(path: PathBuf) -> Result<Image, Box<dyn std::error::Error>> {
         let mut image = Image {
             format: Format::Unknown,
@@ -48,4 +57,71 @@ impl Image {
 
         Ok(image)
     }
+
+    /// Writes the image to disk as a png, parent directories are created if missing
+    pub fn save_png(&self, path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+        let color = match self.format {
+            Format::RGB8 => image::ColorType::Rgb8,
+            Format::RGBA8 => image::ColorType::Rgba8,
+            Format::Unknown => return Err("Cannot save an image of unknown format".into()),
+        };
+        let data = self.data.as_ref().ok_or("Cannot save an image without data")?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        image::save_buffer_with_format(
+            path,
+            data,
+            self.width,
+            self.height,
+            color,
+            image::ImageFormat::Png,
+        )?;
+
+        Ok(())
+    }
+
+    /// Get the RGBA value of the pixel at the given position, RGB images report an opaque alpha. <br>
+    /// Returns `None` if the position is out of bounds or the image holds no data.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let data = self.data.as_ref()?;
+        let index = (y * self.width + x) as usize;
+        match self.format {
+            Format::RGB8 => {
+                let p = data.get(index * 3..index * 3 + 3)?;
+                Some([p[0], p[1], p[2], 255])
+            }
+            Format::RGBA8 => {
+                let p = data.get(index * 4..index * 4 + 4)?;
+                Some([p[0], p[1], p[2], p[3]])
+            }
+            Format::Unknown => None,
+        }
+    }
+
+    /// Get the image's format.
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Get the image's width.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the image's height.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get a reference to the image's data.
+    pub fn data(&self) -> Option<&Vec<u8>> {
+        self.data.as_ref()
+    }
 }
@@ -1,7 +1,14 @@
 use crate::context::Context;
 
+pub use schedule::{Schedule, Stage, SystemDescriptor, Systems};
+
+mod schedule;
+
+/// Game logic run by the [App](crate::App), `update` runs in the [Stage] the system was added to
 pub trait System {
     fn awake(&mut self, _: &mut Context) {}
+    /// Called zero or more times per frame at the fixed rate of [Time](crate::time::Time), before `update`
+    fn fixed_update(&mut self, _: &mut Context) {}
     fn update(&mut self, _: &mut Context) {}
     fn late_update(&mut self, _: &mut Context) {}
 }
\ No newline at end of file
@@ -0,0 +1,355 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::context::Context;
+
+use super::System;
+
+/// Phases of a frame, systems run their `update` in the stage they were added to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    /// Runs once after every system has been awoken, before the first frame
+    Startup,
+    /// Runs after input and time have been updated, before fixed updates
+    PreUpdate,
+    /// Runs after fixed updates, the default stage
+    Update,
+    PostUpdate,
+    /// Runs after the entities of the world have been drawn, before the buffers are swapped
+    Render,
+    /// Runs after the buffers are swapped, before every system's `late_update`
+    LateUpdate,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 6] = [
+        Stage::Startup,
+        Stage::PreUpdate,
+        Stage::Update,
+        Stage::PostUpdate,
+        Stage::Render,
+        Stage::LateUpdate,
+    ];
+}
+
+/// A system along with its stage, label and ordering constraints
+pub struct SystemDescriptor {
+    system: Box<dyn System>,
+    stage: Stage,
+    label: Option<String>,
+    before: Vec<String>,
+    after: Vec<String>,
+    enabled: bool,
+}
+
+impl SystemDescriptor {
+    pub fn new(system: impl System + 'static) -> Self {
+        SystemDescriptor {
+            system: Box::new(system),
+            stage: Stage::Update,
+            label: None,
+            before: Vec::new(),
+            after: Vec::new(),
+            enabled: true,
+        }
+    }
+
+    /// Run the system's `update` in the given stage, defaults to [Stage::Update]
+    pub fn stage(mut self, stage: Stage) -> Self {
+        self.stage = stage;
+        self
+    }
+
+    /// Name the system so it can be ordered against and enabled or disabled at runtime. <br>
+    /// Several systems may share a label, ordering against it orders against all of them.
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Run before the systems with the given label in the same stage
+    pub fn before(mut self, label: &str) -> Self {
+        self.before.push(label.to_string());
+        self
+    }
+
+    /// Run after the systems with the given label in the same stage
+    pub fn after(mut self, label: &str) -> Self {
+        self.after.push(label.to_string());
+        self
+    }
+
+    /// Start the system disabled, it is still awoken. Only labelled systems can be disabled as they are enabled
+    /// through their label later, enabling or disabling a label applies to every system with it.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+impl<T> From<T> for SystemDescriptor
+where
+    T: System + 'static,
+{
+    fn from(system: T) -> Self {
+        SystemDescriptor::new(system)
+    }
+}
+
+/// Runtime state of labelled systems, used to enable and disable them between frames
+#[derive(Debug, Default, Clone)]
+pub struct Systems {
+    enabled: BTreeMap<String, bool>,
+    // Labels enabled or disabled at runtime, their systems no longer keep the state they started with
+    changed: BTreeSet<String>,
+}
+
+impl Systems {
+    /// Returns true if the systems with the given label run, false for unknown labels
+    pub fn is_enabled(&self, label: &str) -> bool {
+        self.enabled.get(label).copied().unwrap_or(false)
+    }
+
+    /// Enable or disable every system with the given label, returns false if no system has the label
+    pub fn set_enabled(&mut self, label: &str, enabled: bool) -> bool {
+        match self.enabled.get_mut(label) {
+            Some(state) => {
+                *state = enabled;
+                self.changed.insert(label.to_string());
+                true
+            }
+            None => {
+                warn!("Tried to {} unknown system {:?}", if enabled { "enable" } else { "disable" }, label);
+                false
+            }
+        }
+    }
+
+    pub fn enable(&mut self, label: &str) -> bool {
+        self.set_enabled(label, true)
+    }
+
+    pub fn disable(&mut self, label: &str) -> bool {
+        self.set_enabled(label, false)
+    }
+
+    /// Get the labels of all systems.
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.enabled.keys().map(|label| label.as_str())
+    }
+}
+
+struct Entry {
+    system: Box<dyn System>,
+    label: Option<String>,
+    /// State the system started with, overridden once its label is enabled or disabled
+    enabled: bool,
+}
+
+impl Entry {
+    fn is_enabled(&self, systems: &Systems) -> bool {
+        match &self.label {
+            Some(label) => systems.is_enabled(label) && (self.enabled || systems.changed.contains(label)),
+            None => self.enabled,
+        }
+    }
+}
+
+/// Systems sorted by stage and ordering constraints, built by the [App](crate::App) when it starts running
+#[derive(Default)]
+pub struct Schedule {
+    stages: Vec<(Stage, Vec<Entry>)>,
+    initial: Systems,
+}
+
+impl Schedule {
+    /// Sorts the systems of every stage, fails if the ordering constraints of a stage form a cycle
+    /// or an unlabelled system is disabled, as it could never be enabled
+    pub fn new(descriptors: Vec<SystemDescriptor>) -> Result<Self, String> {
+        let mut initial = Systems::default();
+        let mut label_stages: BTreeMap<&str, BTreeSet<Stage>> = BTreeMap::new();
+        for descriptor in descriptors.iter() {
+            match &descriptor.label {
+                Some(label) => {
+                    // A label stays enabled while any of its systems is
+                    let enabled = initial.enabled.entry(label.clone()).or_insert(false);
+                    *enabled |= descriptor.enabled;
+                    label_stages.entry(label).or_default().insert(descriptor.stage);
+                }
+                None if !descriptor.enabled => {
+                    return Err(format!(
+                        "Unlabelled system in stage {:?} is disabled, it could never be enabled",
+                        descriptor.stage
+                    ));
+                }
+                None => (),
+            }
+        }
+
+        // Systems are only ordered against systems of the same stage
+        for descriptor in descriptors.iter() {
+            for label in descriptor.before.iter().chain(descriptor.after.iter()) {
+                match label_stages.get(label.as_str()) {
+                    None => warn!("System ordering references unknown label {:?}", label),
+                    Some(stages) if !stages.contains(&descriptor.stage) => warn!(
+                        "System {:?} in stage {:?} is ordered against {:?} of stages {:?}, the constraint is ignored",
+                        descriptor.label.as_deref().unwrap_or("<unlabelled>"),
+                        descriptor.stage,
+                        label,
+                        stages
+                    ),
+                    Some(_) => (),
+                }
+            }
+        }
+
+        let mut stages = Vec::new();
+        let mut descriptors: Vec<_> = descriptors.into_iter().map(Some).collect();
+        for stage in Stage::ALL {
+            let indices: Vec<_> = (0..descriptors.len())
+                .filter(|&i| matches!(&descriptors[i], Some(d) if d.stage == stage))
+                .collect();
+
+            let order = sort(stage, &indices, &descriptors)?;
+            let entries = order
+                .into_iter()
+                .map(|i| {
+                    let descriptor = descriptors[i].take().unwrap();
+                    Entry {
+                        system: descriptor.system,
+                        label: descriptor.label,
+                        enabled: descriptor.enabled,
+                    }
+                })
+                .collect();
+
+            stages.push((stage, entries));
+        }
+
+        Ok(Schedule { stages, initial })
+    }
+
+    /// Get the labels of the systems in a stage in the order they run, `None` for unlabelled systems.
+    pub fn labels(&self, stage: Stage) -> Vec<Option<&str>> {
+        self.stages
+            .iter()
+            .filter(|(s, _)| *s == stage)
+            .flat_map(|(_, entries)| entries.iter().map(|entry| entry.label.as_deref()))
+            .collect()
+    }
+
+    /// Get whether the systems in a stage run given the state of the labelled systems, in the order they run.
+    pub fn enabled(&self, stage: Stage, systems: &Systems) -> Vec<bool> {
+        self.stages
+            .iter()
+            .filter(|(s, _)| *s == stage)
+            .flat_map(|(_, entries)| entries.iter().map(|entry| entry.is_enabled(systems)))
+            .collect()
+    }
+
+    /// Get the state the labelled systems start with, it is moved into the [Context] when the schedule is awoken.
+    pub fn systems(&self) -> &Systems {
+        &self.initial
+    }
+
+    /// Registers the labels of all systems and awakes them in schedule order
+    pub(crate) fn awake(&mut self, context: &mut Context) {
+        *context.systems_mut() = std::mem::take(&mut self.initial);
+
+        for (_, entries) in self.stages.iter_mut() {
+            for entry in entries.iter_mut() {
+                entry.system.awake(context);
+            }
+        }
+    }
+
+    /// Runs `update` of every enabled system in the stage
+    pub(crate) fn run(&mut self, stage: Stage, context: &mut Context) {
+        if let Some((_, entries)) = self.stages.iter_mut().find(|(s, _)| *s == stage) {
+            for entry in entries.iter_mut() {
+                if entry.is_enabled(context.systems()) {
+                    entry.system.update(context);
+                }
+            }
+        }
+    }
+
+    /// Runs `fixed_update` of every enabled system, except for the systems of [Stage::Startup] which only run once
+    pub(crate) fn fixed_update(&mut self, context: &mut Context) {
+        for (_, entries) in self.stages.iter_mut().filter(|(stage, _)| *stage != Stage::Startup) {
+            for entry in entries.iter_mut() {
+                if entry.is_enabled(context.systems()) {
+                    entry.system.fixed_update(context);
+                }
+            }
+        }
+    }
+
+    /// Runs `late_update` of every enabled system, except for the systems of [Stage::Startup] which only run once
+    pub(crate) fn late_update(&mut self, context: &mut Context) {
+        for (_, entries) in self.stages.iter_mut().filter(|(stage, _)| *stage != Stage::Startup) {
+            for entry in entries.iter_mut() {
+                if entry.is_enabled(context.systems()) {
+                    entry.system.late_update(context);
+                }
+            }
+        }
+    }
+}
+
+/// Orders the systems of a stage by their constraints, unconstrained systems keep the order they were added in
+fn sort(stage: Stage, indices: &[usize], descriptors: &[Option<SystemDescriptor>]) -> Result<Vec<usize>, String> {
+    let descriptor = |i: usize| descriptors[i].as_ref().unwrap();
+    let labelled = |label: &String| -> Vec<usize> {
+        indices
+            .iter()
+            .copied()
+            .filter(|&i| descriptor(i).label.as_ref() == Some(label))
+            .collect()
+    };
+
+    // Edges point from a system to the systems which have to run after it
+    let mut edges: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+    for &i in indices {
+        for label in descriptor(i).before.iter() {
+            edges.entry(i).or_default().extend(labelled(label).into_iter().filter(|&j| j != i));
+        }
+        for label in descriptor(i).after.iter() {
+            for j in labelled(label).into_iter().filter(|&j| j != i) {
+                edges.entry(j).or_default().insert(i);
+            }
+        }
+    }
+
+    let mut incoming: BTreeMap<usize, usize> = indices.iter().map(|&i| (i, 0)).collect();
+    for targets in edges.values() {
+        for target in targets {
+            *incoming.get_mut(target).unwrap() += 1;
+        }
+    }
+
+    let mut ready: BTreeSet<usize> = incoming.iter().filter(|(_, &n)| n == 0).map(|(&i, _)| i).collect();
+    let mut order = Vec::with_capacity(indices.len());
+    while let Some(&i) = ready.iter().next() {
+        ready.remove(&i);
+        order.push(i);
+
+        for target in edges.get(&i).into_iter().flatten() {
+            let count = incoming.get_mut(target).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                ready.insert(*target);
+            }
+        }
+    }
+
+    if order.len() != indices.len() {
+        let cyclic: Vec<_> = indices
+            .iter()
+            .filter(|i| !order.contains(i))
+            .map(|&i| descriptor(i).label.clone().unwrap_or_else(|| "<unlabelled>".to_string()))
+            .collect();
+        return Err(format!("Systems in stage {:?} have cyclic ordering: {}", stage, cyclic.join(", ")));
+    }
+
+    Ok(order)
+}
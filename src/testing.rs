@@ -0,0 +1,241 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use thiserror::Error;
+
+use crate::{
+    renderer::texture::image::{Format, Image},
+    App, AppError, Context, Stage, System, SystemDescriptor,
+};
+
+/// Environment variable which, when set to `1`, overwrites reference images with the rendered output
+pub const BLESS_ENV: &str = "GOLDEN_BLESS";
+
+#[derive(Debug, Error)]
+pub enum GoldenError {
+    #[error("failed to run offscreen application: {0}")]
+    Context(String),
+    /// No headless OpenGL backend is available, such as OSMesa on unix
+    #[error("no headless backend is available: {0}")]
+    Unavailable(String),
+    #[error("no frame was captured")]
+    NoCapture,
+    #[error("missing reference image {0:?}, run with GOLDEN_BLESS=1 to create it")]
+    MissingReference(PathBuf),
+    #[error("failed to access image {path:?}: {message}")]
+    Io { path: PathBuf, message: String },
+    #[error("rendered frame is {actual:?} but the reference is {expected:?}")]
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    #[error("image has no pixel at ({x}, {y}), its data does not match its size")]
+    MissingPixel { x: u32, y: u32 },
+    #[error("{mismatched} pixels differ from the reference by up to {max_difference}, see {diff:?}")]
+    Mismatch {
+        mismatched: usize,
+        max_difference: u8,
+        diff: PathBuf,
+    },
+}
+
+/// Result of comparing a rendered frame against a reference image
+pub struct Comparison {
+    /// Amount of pixels with at least one channel outside of the tolerance
+    pub mismatched: usize,
+    /// Largest difference of any channel
+    pub max_difference: u8,
+    /// Image highlighting mismatched pixels in red over a dimmed reference
+    pub diff: Image,
+}
+
+/// Compares two images channel by channel. `tolerance` holds the allowed difference for r, g, b and a.
+pub fn compare(actual: &Image, expected: &Image, tolerance: [u8; 4]) -> Result<Comparison, GoldenError> {
+    if (actual.width(), actual.height()) != (expected.width(), expected.height()) {
+        return Err(GoldenError::SizeMismatch {
+            expected: (expected.width(), expected.height()),
+            actual: (actual.width(), actual.height()),
+        });
+    }
+
+    let mut mismatched = 0;
+    let mut max_difference = 0;
+    let mut diff = Vec::with_capacity((actual.width() * actual.height() * 4) as usize);
+
+    for y in 0..actual.height() {
+        for x in 0..actual.width() {
+            let a = actual.pixel(x, y).ok_or(GoldenError::MissingPixel { x, y })?;
+            let e = expected.pixel(x, y).ok_or(GoldenError::MissingPixel { x, y })?;
+
+            let mut matches = true;
+            for c in 0..4 {
+                let difference = (a[c] as i16 - e[c] as i16).unsigned_abs() as u8;
+                max_difference = max_difference.max(difference);
+                matches &= difference <= tolerance[c];
+            }
+
+            if matches {
+                let gray = ((e[0] as u16 + e[1] as u16 + e[2] as u16) / 9) as u8;
+                diff.extend([gray, gray, gray, 255]);
+            } else {
+                mismatched += 1;
+                diff.extend([255, 0, 0, 255]);
+            }
+        }
+    }
+
+    Ok(Comparison {
+        mismatched,
+        max_difference,
+        diff: Image::new(Format::RGBA8, actual.width(), actual.height(), diff),
+    })
+}
+
+/// Renders a [System] offscreen and compares the final frame against a stored reference image. <br>
+/// Reference images are read from `tests/golden/<name>.png`, on failure the rendered frame and a diff
+/// image are written to `target/golden`.
+pub struct GoldenTest<'a> {
+    name: &'a str,
+    size: (u32, u32),
+    frames: u32,
+    tolerance: [u8; 4],
+    reference_dir: PathBuf,
+    output_dir: PathBuf,
+}
+
+impl<'a> GoldenTest<'a> {
+    pub fn new(name: &'a str) -> Self {
+        GoldenTest {
+            name,
+            size: (64, 64),
+            frames: 1,
+            tolerance: [0; 4],
+            reference_dir: "tests/golden".into(),
+            output_dir: "target/golden".into(),
+        }
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    /// Amount of frames to run before capturing
+    pub fn frames(mut self, frames: u32) -> Self {
+        self.frames = frames.max(1);
+        self
+    }
+
+    /// Allowed difference for every channel
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = [tolerance; 4];
+        self
+    }
+
+    /// Allowed difference for the r, g, b and a channels
+    pub fn channel_tolerance(mut self, tolerance: [u8; 4]) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn reference_dir(mut self, path: PathBuf) -> Self {
+        self.reference_dir = path;
+        self
+    }
+
+    pub fn output_dir(mut self, path: PathBuf) -> Self {
+        self.output_dir = path;
+        self
+    }
+
+    /// Runs the system offscreen and compares the captured frame with the reference image. <br>
+    /// Returns [GoldenError::Unavailable] if no headless context can be created.
+    pub fn run<T>(&self, system: T) -> Result<(), GoldenError>
+    where
+        T: System + 'static,
+    {
+        let capture = Arc::new(Mutex::new(None));
+        let capture_system = CaptureSystem {
+            frames: self.frames,
+            frame: 0,
+            capture: capture.clone(),
+        };
+
+        App::builder()
+            .name(self.name)
+            .headless(self.size.0, self.size.1)
+            .frames(self.frames)
+            .screenshot_key(None)
+            .add_system(system)
+            .add_system(SystemDescriptor::new(capture_system).stage(Stage::Render))
+            .build()
+            .try_run()
+            .map_err(|e| match e {
+                AppError::Context(e) => GoldenError::Unavailable(e),
+                e => GoldenError::Context(e.to_string()),
+            })?;
+
+        let actual = capture.lock().unwrap().take().ok_or(GoldenError::NoCapture)?;
+        self.check(&actual)
+    }
+
+    /// Compares an image against the reference image of this test
+    pub fn check(&self, actual: &Image) -> Result<(), GoldenError> {
+        let reference = self.reference_dir.join(format!("{}.png", self.name));
+
+        if std::env::var(BLESS_ENV).as_deref() == Ok("1") {
+            info!("Blessing reference image {:?}", reference);
+            return save(actual, reference);
+        }
+
+        if !reference.exists() {
+            return Err(GoldenError::MissingReference(reference));
+        }
+
+        let expected = Image::read_image(reference.clone()).map_err(|e| GoldenError::Io {
+            path: reference,
+            message: e.to_string(),
+        })?;
+
+        let comparison = compare(actual, &expected, self.tolerance)?;
+        if comparison.mismatched == 0 {
+            return Ok(());
+        }
+
+        let diff = self.output_dir.join(format!("{}-diff.png", self.name));
+        save(actual, self.output_dir.join(format!("{}-actual.png", self.name)))?;
+        save(&comparison.diff, diff.clone())?;
+
+        Err(GoldenError::Mismatch {
+            mismatched: comparison.mismatched,
+            max_difference: comparison.max_difference,
+            diff,
+        })
+    }
+}
+
+fn save(image: &Image, path: PathBuf) -> Result<(), GoldenError> {
+    image.save_png(path.clone()).map_err(|e| GoldenError::Io {
+        path,
+        message: e.to_string(),
+    })
+}
+
+/// Captures the frame rendered during the last frame, runs in the render stage after every other system
+struct CaptureSystem {
+    frames: u32,
+    frame: u32,
+    capture: Arc<Mutex<Option<Image>>>,
+}
+
+impl System for CaptureSystem {
+    fn update(&mut self, context: &mut Context) {
+        self.frame += 1;
+        if self.frame == self.frames {
+            let image = context.engine().renderer().capture_frame();
+            *self.capture.lock().unwrap() = Some(image);
+        }
+    }
+}
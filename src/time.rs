@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+/// How often the application renders a frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameRate {
+    /// Render as fast as possible
+    Unlimited,
+    /// Wait for the display's vertical blank when swapping buffers
+    Vsync,
+    /// Render at most the given amount of frames per second
+    Target(u32),
+}
+
+impl FrameRate {
+    /// Get the minimum time between two frames, `None` if the rate is not capped by the application.
+    pub fn frame_time(&self) -> Option<Duration> {
+        match *self {
+            FrameRate::Target(rate) if rate > 0 => Some(Duration::from_secs(1) / rate),
+            _ => None,
+        }
+    }
+
+    /// Returns true if buffer swaps should wait for the vertical blank
+    pub fn is_vsync(&self) -> bool {
+        matches!(self, FrameRate::Vsync)
+    }
+}
+
+impl Default for FrameRate {
+    fn default() -> Self {
+        FrameRate::Target(144)
+    }
+}
+
+/// Weight of the newest frame when smoothing the frames per second
+const FPS_SMOOTHING: f32 = 0.1;
+
+/// Frame timing, advanced once per frame before any system is updated
+#[derive(Debug, Clone)]
+pub struct Time {
+    delta: Duration,
+    elapsed: Duration,
+    frame_count: u64,
+    smoothed_delta: f32,
+
+    fixed_delta: Duration,
+    max_substeps: u32,
+    accumulator: Duration,
+}
+
+impl Time {
+    /// Creates a timer running `fixed_update` every `fixed_delta`, at most `max_substeps` times per frame
+    pub fn new(fixed_delta: Duration, max_substeps: u32) -> Self {
+        Time {
+            delta: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            frame_count: 0,
+            smoothed_delta: 0.0,
+
+            fixed_delta,
+            max_substeps,
+            accumulator: Duration::ZERO,
+        }
+    }
+
+    /// Starts a new frame which took `delta` since the previous one
+    pub fn advance(&mut self, delta: Duration) {
+        self.delta = delta;
+        self.elapsed += delta;
+        self.frame_count += 1;
+        self.accumulator += delta;
+
+        self.smoothed_delta = match self.frame_count {
+            1 => delta.as_secs_f32(),
+            _ => self.smoothed_delta + (delta.as_secs_f32() - self.smoothed_delta) * FPS_SMOOTHING,
+        };
+    }
+
+    /// Consumes the accumulated time, returning how many fixed steps to run this frame. <br>
+    /// Time beyond [max_substeps](Self::max_substeps) steps is dropped so a slow frame can't snowball into slower ones.
+    pub fn fixed_steps(&mut self) -> u32 {
+        if self.fixed_delta == Duration::ZERO {
+            return 0;
+        }
+
+        let mut steps = 0;
+        while self.accumulator >= self.fixed_delta {
+            if steps == self.max_substeps {
+                trace!("Dropping {:?} of fixed updates", self.accumulator);
+                self.accumulator = Duration::from_nanos(
+                    (self.accumulator.as_nanos() % self.fixed_delta.as_nanos()) as u64,
+                );
+                break;
+            }
+
+            self.accumulator -= self.fixed_delta;
+            steps += 1;
+        }
+
+        steps
+    }
+
+    /// Get the time between the previous frame and this one.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Get the time between the previous frame and this one in seconds.
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    /// Get the total time elapsed since the first frame.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Get the total time elapsed since the first frame in seconds.
+    pub fn elapsed_seconds(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+
+    /// Get the amount of frames started so far.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Get the frames per second, smoothed over the last few frames.
+    pub fn fps(&self) -> f32 {
+        match self.smoothed_delta > 0.0 {
+            true => 1.0 / self.smoothed_delta,
+            false => 0.0,
+        }
+    }
+
+    /// Get the time between two fixed updates.
+    pub fn fixed_delta(&self) -> Duration {
+        self.fixed_delta
+    }
+
+    /// Get the time between two fixed updates in seconds.
+    pub fn fixed_delta_seconds(&self) -> f32 {
+        self.fixed_delta.as_secs_f32()
+    }
+
+    /// Set the time between two fixed updates.
+    pub fn set_fixed_delta(&mut self, fixed_delta: Duration) {
+        self.fixed_delta = fixed_delta;
+    }
+
+    /// Get the maximum amount of fixed updates run in a single frame.
+    pub fn max_substeps(&self) -> u32 {
+        self.max_substeps
+    }
+
+    /// Set the maximum amount of fixed updates run in a single frame.
+    pub fn set_max_substeps(&mut self, max_substeps: u32) {
+        self.max_substeps = max_substeps;
+    }
+
+    /// Get how far the current frame is between the last fixed update and the next one, in `0..1`. <br>
+    /// Used to interpolate state simulated in `fixed_update`.
+    pub fn alpha(&self) -> f32 {
+        match self.fixed_delta == Duration::ZERO {
+            true => 0.0,
+            false => self.accumulator.as_secs_f32() / self.fixed_delta.as_secs_f32(),
+        }
+    }
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Time::new(Duration::from_secs(1) / 60, 5)
+    }
+}
@@ -0,0 +1,169 @@
+extern crate nalgebra as na;
+
+use std::f32::consts::FRAC_PI_2;
+
+use engine::renderer::camera::{
+    Camera, CameraController, ControllerInput, FlyController, OrbitController, PanController, MAX_FOVY,
+};
+
+fn assert_near(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-4, "{} is not {}", actual, expected);
+}
+
+fn assert_near_vector(actual: na::Vector3<f32>, expected: na::Vector3<f32>) {
+    assert!((actual - expected).norm() < 1e-4, "{:?} is not {:?}", actual, expected);
+}
+
+/// Get the normalized device depth of a point in view space
+fn depth(camera: &Camera, z: f32) -> f32 {
+    let clip = camera.projection_matrix() * na::Vector4::new(0.0, 0.0, z, 1.0);
+    clip.z / clip.w
+}
+
+#[test]
+fn reverse_z_maps_near_to_one_and_far_to_zero() {
+    let camera = Camera::reverse_z(1.0, FRAC_PI_2, 0.1);
+    assert!(camera.is_reverse_z());
+    assert_near(depth(&camera, -0.1), 1.0);
+    assert_near(depth(&camera, -1.0e6), 0.0);
+    assert!(depth(&camera, -1.0) > depth(&camera, -10.0));
+
+    // Regular perspective projections map near to -1 and far to 1
+    let camera = Camera::perspective(1.0, FRAC_PI_2, 0.1, 100.0);
+    assert_near(depth(&camera, -0.1), -1.0);
+    assert_near(depth(&camera, -100.0), 1.0);
+}
+
+#[test]
+fn look_at_round_trips_orientation() {
+    let eye = na::Point3::new(1.0, 2.0, 3.0);
+    let target = na::Point3::new(-2.0, 0.0, 1.0);
+
+    let mut camera = Camera::perspective(1.0, FRAC_PI_2, 0.1, 100.0);
+    camera.look_at(eye, target, na::Vector3::y());
+    assert_near_vector(camera.forward(), (target - eye).normalize());
+    assert_near_vector(camera.position().coords, eye.coords);
+    assert_near_vector(camera.view().transform_point(&eye).coords, na::Vector3::zeros());
+
+    // The view is rebuilt from position and orientation alone
+    let view = *camera.view();
+    let mut copy = Camera::perspective(1.0, FRAC_PI_2, 0.1, 100.0);
+    copy.set_position(camera.position());
+    copy.set_orientation(camera.orientation());
+    assert!((copy.view() - view).norm() < 1e-4);
+}
+
+#[test]
+fn resize_updates_aspect() {
+    let mut camera = Camera::perspective(1.0, FRAC_PI_2, 0.1, 100.0);
+    camera.resize(1920, 1080);
+    assert_near(camera.aspect(), 16.0 / 9.0);
+    assert_near(
+        camera.projection_matrix()[(0, 0)] * 16.0 / 9.0,
+        camera.projection_matrix()[(1, 1)],
+    );
+
+    // Minimized windows report a size of 0
+    camera.resize(0, 1080);
+    assert_near(camera.aspect(), 16.0 / 9.0);
+
+    // Orthographic cameras keep their height and center
+    let mut camera = Camera::ortho(-2.0, 4.0, -1.0, 1.0, 0.1, 100.0);
+    camera.resize(400, 100);
+    assert_near(camera.aspect(), 4.0);
+    let corner = camera
+        .projection_matrix()
+        .transform_point(&na::Point3::new(5.0, 1.0, -1.0));
+    assert_near_vector(corner.coords.xy().push(0.0), na::Vector3::new(1.0, 1.0, 0.0));
+}
+
+#[test]
+fn zoom_clamps_fovy() {
+    let mut camera = Camera::perspective(1.0, FRAC_PI_2, 0.1, 100.0);
+    camera.set_zoom(2.0);
+    assert_near(camera.fovy().unwrap(), FRAC_PI_2 / 2.0);
+
+    // Zooming out far enough would exceed 180 degrees
+    camera.set_zoom(0.01);
+    assert_eq!(camera.fovy(), Some(MAX_FOVY));
+    assert!(camera.projection_matrix()[(1, 1)] > 0.0);
+
+    let mut camera = Camera::reverse_z(1.0, FRAC_PI_2, 0.1);
+    camera.set_zoom(0.0);
+    assert_eq!(camera.fovy(), Some(MAX_FOVY));
+    assert!(camera.projection_matrix()[(1, 1)].is_finite());
+
+    assert_eq!(Camera::ortho(-1.0, 1.0, -1.0, 1.0, 0.1, 100.0).fovy(), None);
+}
+
+#[test]
+fn fly_controller_moves_along_the_view() {
+    let mut camera = Camera::perspective(1.0, FRAC_PI_2, 0.1, 100.0);
+    let mut controller = FlyController::default();
+
+    let input = ControllerInput {
+        movement: na::Vector3::new(0.0, 0.0, 1.0),
+        ..Default::default()
+    };
+    controller.update(&mut camera, &input, 0.5);
+    assert_near_vector(
+        camera.position().coords,
+        na::Vector3::new(0.0, 0.0, -controller.speed * 0.5),
+    );
+
+    // Moving the cursor to the right turns the camera to the right, pitch stops short of straight up
+    let input = ControllerInput {
+        look: na::Vector2::new(FRAC_PI_2 / controller.sensitivity, -1.0e6),
+        ..Default::default()
+    };
+    controller.update(&mut camera, &input, 0.0);
+    assert!(controller.pitch < FRAC_PI_2);
+    assert_near_vector(camera.right(), na::Vector3::new(0.0, 0.0, 1.0));
+}
+
+#[test]
+fn orbit_controller_clamps_distance() {
+    let mut camera = Camera::perspective(1.0, FRAC_PI_2, 0.1, 100.0);
+    let mut controller = OrbitController::default();
+
+    controller.update(&mut camera, &ControllerInput::default(), 0.0);
+    assert_near_vector(
+        camera.position().coords,
+        na::Vector3::new(0.0, 0.0, controller.distance),
+    );
+    assert_near_vector(camera.forward(), -na::Vector3::z());
+
+    let input = ControllerInput {
+        zoom: 100.0,
+        ..Default::default()
+    };
+    controller.update(&mut camera, &input, 0.0);
+    assert_eq!(controller.distance, controller.min_distance);
+}
+
+#[test]
+fn pan_controller_clamps_zoom() {
+    let mut camera = Camera::ortho(-1.0, 1.0, -1.0, 1.0, 0.1, 100.0);
+    let mut controller = PanController::default();
+
+    let input = ControllerInput {
+        movement: na::Vector3::new(1.0, 0.0, 0.0),
+        ..Default::default()
+    };
+    controller.update(&mut camera, &input, 1.0);
+    assert_near_vector(camera.position().coords, na::Vector3::new(controller.speed, 0.0, 0.0));
+
+    let input = ControllerInput {
+        zoom: 1000.0,
+        ..Default::default()
+    };
+    controller.update(&mut camera, &input, 0.0);
+    assert_eq!(camera.zoom(), controller.max_zoom);
+
+    let input = ControllerInput {
+        zoom: -1000.0,
+        ..Default::default()
+    };
+    controller.update(&mut camera, &input, 0.0);
+    assert_eq!(camera.zoom(), controller.min_zoom);
+}
@@ -0,0 +1,49 @@
+extern crate nalgebra as na;
+
+use engine::renderer::{
+    buffer::{BlockLayout, Layout, LayoutEncoder},
+    shader::Barrier,
+};
+
+#[derive(BlockLayout)]
+struct Particle {
+    position: na::Vector3<f32>,
+    life: f32,
+    velocity: na::Vector2<f32>,
+}
+
+#[test]
+fn barriers() {
+    let barrier = Barrier::SHADER_STORAGE | Barrier::VERTEX_ATTRIB_ARRAY;
+    assert_eq!(
+        barrier.bits(),
+        gl::SHADER_STORAGE_BARRIER_BIT | gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT
+    );
+    assert!(barrier.contains(Barrier::SHADER_STORAGE));
+    assert!(!barrier.contains(Barrier::COMMAND));
+    assert!(Barrier::ALL.contains(barrier));
+}
+
+#[test]
+fn runtime_sized_arrays() {
+    let particles = [
+        Particle {
+            position: na::Vector3::new(1.0, 2.0, 3.0),
+            life: 0.5,
+            velocity: na::Vector2::new(4.0, 5.0),
+        },
+        Particle {
+            position: na::Vector3::zeros(),
+            life: 1.0,
+            velocity: na::Vector2::zeros(),
+        },
+    ];
+
+    // std430 keeps the struct alignment at 16, so every particle takes 32 bytes
+    assert_eq!(Particle::size(Layout::Std430), 32);
+    let data = LayoutEncoder::encode(Layout::Std430, &particles[..]);
+    assert_eq!(data.len(), 64);
+    assert_eq!(&data[12..16], &0.5f32.to_ne_bytes());
+    assert_eq!(&data[16..20], &4.0f32.to_ne_bytes());
+    assert_eq!(&data[44..48], &1.0f32.to_ne_bytes());
+}
@@ -0,0 +1,90 @@
+extern crate nalgebra as na;
+
+use engine::renderer::{
+    bounds::{Aabb, BoundingSphere, POSITION_ATTRIBUTE},
+    camera::Camera,
+    culling::CullingPass,
+    render_target::vertex_array::DefaultVertex,
+};
+
+fn cube() -> Vec<DefaultVertex> {
+    vec![
+        DefaultVertex::new((-1.0, -2.0, -3.0), (0.0, 0.0, 0.0), (0.0, 0.0)),
+        DefaultVertex::new((1.0, 2.0, 3.0), (1.0, 1.0, 1.0), (1.0, 1.0)),
+        DefaultVertex::new((0.5, -0.5, 0.0), (1.0, 0.0, 0.0), (0.5, 0.5)),
+    ]
+}
+
+fn camera() -> Camera {
+    let mut camera = Camera::perspective(1.0, std::f32::consts::FRAC_PI_2, 0.1, 100.0);
+    camera.look_at(na::Point3::new(0.0, 0.0, 10.0), na::Point3::origin(), na::Vector3::y());
+    camera
+}
+
+#[test]
+fn aabb_from_vertices() {
+    let aabb = Aabb::from_vertices(&cube(), POSITION_ATTRIBUTE).unwrap();
+
+    assert_eq!(aabb.min, na::Point3::new(-1.0, -2.0, -3.0));
+    assert_eq!(aabb.max, na::Point3::new(1.0, 2.0, 3.0));
+    assert_eq!(aabb.center(), na::Point3::origin());
+    assert!(Aabb::from_vertices(&cube(), "Normal").is_none());
+    assert!(Aabb::from_vertices::<DefaultVertex>(&[], POSITION_ATTRIBUTE).is_none());
+}
+
+#[test]
+fn sphere_from_vertices() {
+    let sphere = BoundingSphere::from_vertices(&cube(), POSITION_ATTRIBUTE).unwrap();
+
+    assert_eq!(sphere.center, na::Point3::origin());
+    assert!((sphere.radius - 14f32.sqrt()).abs() < 1e-5);
+}
+
+#[test]
+fn aabb_transform() {
+    let aabb = Aabb::new(na::Point3::new(-1.0, -1.0, -1.0), na::Point3::new(1.0, 1.0, 1.0));
+    let model = na::Matrix4::new_translation(&na::Vector3::new(5.0, 0.0, 0.0)) * na::Matrix4::new_scaling(2.0);
+
+    let transformed = aabb.transform(&model);
+    assert_eq!(transformed.min, na::Point3::new(3.0, -2.0, -2.0));
+    assert_eq!(transformed.max, na::Point3::new(7.0, 2.0, 2.0));
+}
+
+#[test]
+fn frustum_contains() {
+    let frustum = camera().frustum();
+
+    assert!(frustum.contains_point(&na::Point3::origin()));
+    assert!(!frustum.contains_point(&na::Point3::new(0.0, 0.0, 20.0)));
+    assert!(!frustum.contains_point(&na::Point3::new(0.0, 0.0, -200.0)));
+    assert!(!frustum.contains_point(&na::Point3::new(20.0, 0.0, 0.0)));
+
+    assert!(frustum.intersects_sphere(&BoundingSphere::new(na::Point3::new(11.0, 0.0, 0.0), 2.0)));
+    assert!(!frustum.intersects_sphere(&BoundingSphere::new(na::Point3::new(20.0, 0.0, 0.0), 2.0)));
+}
+
+#[test]
+fn reverse_z_frustum_has_no_far_plane() {
+    let mut camera = Camera::reverse_z(1.0, std::f32::consts::FRAC_PI_2, 0.1);
+    camera.look_at(na::Point3::new(0.0, 0.0, 10.0), na::Point3::origin(), na::Vector3::y());
+    let frustum = camera.frustum();
+
+    assert!(frustum.contains_point(&na::Point3::new(0.0, 0.0, -10000.0)));
+    assert!(!frustum.contains_point(&na::Point3::new(0.0, 0.0, 10.0)));
+}
+
+#[test]
+fn culling_pass_counts() {
+    let mut pass = CullingPass::from_camera(&camera());
+    let aabb = Aabb::new(na::Point3::new(-1.0, -1.0, -1.0), na::Point3::new(1.0, 1.0, 1.0));
+
+    assert!(pass.test(Some(&aabb), &na::Matrix4::identity()));
+    assert!(!pass.test(Some(&aabb), &na::Matrix4::new_translation(&na::Vector3::new(0.0, 50.0, 0.0))));
+    assert!(!pass.test(Some(&aabb), &na::Matrix4::new_translation(&na::Vector3::new(0.0, 0.0, 20.0))));
+    assert!(pass.test(None, &na::Matrix4::identity()));
+
+    let stats = pass.stats();
+    assert_eq!(stats.drawn, 2);
+    assert_eq!(stats.culled, 2);
+    assert_eq!(stats.total(), 4);
+}
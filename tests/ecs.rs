@@ -0,0 +1,156 @@
+extern crate nalgebra as na;
+
+use std::rc::Rc;
+
+use engine::{
+    ecs::{Camera, Mesh, MeshRenderer, Transform, World},
+    renderer::bounds::Aabb,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Velocity(f32);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Health(u32);
+
+struct EmptyMesh;
+
+impl Mesh for EmptyMesh {
+    fn draw(&self) {}
+
+    fn bounds(&self) -> Option<&Aabb> {
+        None
+    }
+}
+
+#[test]
+fn spawn_and_despawn() {
+    let mut world = World::new();
+    let a = world.spawn();
+    let b = world.spawn();
+    assert_eq!(world.len(), 2);
+
+    assert!(world.despawn(a));
+    assert!(!world.despawn(a));
+    assert!(!world.is_alive(a));
+    assert_eq!(world.entities().collect::<Vec<_>>(), vec![b]);
+
+    // The index is reused with a new generation, the old handle stays dead
+    let c = world.spawn();
+    assert_eq!(c.index(), a.index());
+    assert_ne!(c, a);
+    assert!(!world.is_alive(a));
+    assert!(world.is_alive(c));
+}
+
+#[test]
+fn components() {
+    let mut world = World::new();
+    let entity = world.spawn();
+
+    assert_eq!(world.insert(entity, Health(10)).unwrap(), None);
+    assert_eq!(world.insert(entity, Health(5)).unwrap(), Some(Health(10)));
+    assert!(world.has::<Health>(entity));
+    assert!(!world.has::<Velocity>(entity));
+
+    world.get_mut::<Health>(entity).unwrap().0 += 1;
+    assert_eq!(world.get::<Health>(entity), Some(&Health(6)));
+
+    assert_eq!(world.remove::<Health>(entity), Some(Health(6)));
+    assert_eq!(world.get::<Health>(entity), None);
+}
+
+#[test]
+fn despawn_removes_components() {
+    let mut world = World::new();
+    let old = world.spawn();
+    world.insert(old, Health(1)).unwrap();
+    world.despawn(old);
+
+    assert!(world.insert(old, Health(2)).is_err());
+
+    let new = world.spawn();
+    assert_eq!(new.index(), old.index());
+    assert_eq!(world.get::<Health>(new), None);
+    assert_eq!(world.query::<&Health>().count(), 0);
+}
+
+#[test]
+fn query_tuples() {
+    let mut world = World::new();
+    let moving = world.spawn();
+    world.insert(moving, Transform::default()).unwrap();
+    world.insert(moving, Velocity(2.0)).unwrap();
+
+    let still = world.spawn();
+    world.insert(still, Transform::default()).unwrap();
+
+    let hurt = world.spawn();
+    world.insert(hurt, Velocity(1.0)).unwrap();
+    world.insert(hurt, Health(3)).unwrap();
+
+    let matched: Vec<_> = world.query::<(&Transform, &Velocity)>().map(|(e, _)| e).collect();
+    assert_eq!(matched, vec![moving]);
+
+    let optional: Vec<_> = world
+        .query::<(&Velocity, Option<&Health>)>()
+        .map(|(e, (_, health))| (e, health.copied()))
+        .collect();
+    assert_eq!(optional, vec![(moving, None), (hurt, Some(Health(3)))]);
+}
+
+#[test]
+fn query_mut() {
+    let mut world = World::new();
+    for i in 0..3 {
+        let entity = world.spawn();
+        world.insert(entity, Transform::default()).unwrap();
+        world.insert(entity, Velocity(i as f32)).unwrap();
+    }
+
+    for (_, (transform, velocity)) in world.query_mut::<(&mut Transform, &Velocity)>() {
+        transform.translate(na::Vector3::new(velocity.0, 0.0, 0.0));
+    }
+
+    let positions: Vec<_> = world.query::<&Transform>().map(|(_, t)| t.translation().x).collect();
+    assert_eq!(positions, vec![0.0, 1.0, 2.0]);
+}
+
+#[test]
+#[should_panic(expected = "mutably more than once")]
+fn conflicting_query() {
+    let mut world = World::new();
+    world.query_mut::<(&mut Health, &Health)>().count();
+}
+
+#[test]
+fn transform_matrix() {
+    let transform = Transform::from_translation(na::Vector3::new(1.0, 2.0, 3.0))
+        .with_rotation(na::UnitQuaternion::from_axis_angle(&na::Vector3::y_axis(), std::f32::consts::FRAC_PI_2))
+        .with_scale(na::Vector3::repeat(2.0));
+
+    // Scaled to (2, 0, 0), rotated to (0, 0, -2) and translated
+    let point = transform.matrix().transform_point(&na::Point3::new(1.0, 0.0, 0.0));
+    assert!((point - na::Point3::new(1.0, 2.0, 1.0)).norm() < 1e-5);
+    assert!((transform.forward() - na::Vector3::new(-1.0, 0.0, 0.0)).norm() < 1e-5);
+}
+
+#[test]
+fn builtin_components() {
+    let mut world = World::new();
+    let mesh: Rc<dyn Mesh> = Rc::new(EmptyMesh);
+
+    let camera = world.spawn();
+    world.insert(camera, Camera::perspective(1.0, 1.0, 0.1, 100.0)).unwrap();
+    world.insert(camera, Transform::default()).unwrap();
+
+    for _ in 0..2 {
+        let entity = world.spawn();
+        world.insert(entity, Transform::default()).unwrap();
+        world.insert(entity, MeshRenderer::new(mesh.clone(), "basic")).unwrap();
+    }
+
+    assert_eq!(world.query::<(&Transform, &MeshRenderer)>().count(), 2);
+    assert_eq!(world.query::<(&Camera, Option<&Transform>)>().count(), 1);
+    assert_eq!(Rc::strong_count(&mesh), 3);
+}
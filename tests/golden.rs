@@ -0,0 +1,94 @@
+use std::ffi::CString;
+
+use engine::{
+    color::prelude::RGBAColor,
+    renderer::{
+        render_target::{vertex_array::DefaultVertex, RenderTarget},
+        shader::{FragmentShader, ShaderProgram, VertexShader},
+    },
+    testing::GoldenTest,
+    Context, System,
+};
+
+struct ClearColor;
+
+impl System for ClearColor {
+    fn awake(&mut self, context: &mut Context) {
+        context
+            .engine_mut()
+            .renderer_mut()
+            .set_clear_color(RGBAColor::new(0.2, 0.4, 0.8, 1.0));
+    }
+}
+
+const VERTEX: &str = "#version 330 core
+in vec3 Position;
+in vec3 Color;
+out vec3 color;
+void main() {
+    color = Color;
+    gl_Position = vec4(Position, 1.0);
+}";
+
+const FRAGMENT: &str = "#version 330 core
+in vec3 color;
+out vec4 FragColor;
+void main() {
+    FragColor = vec4(color, 1.0);
+}";
+
+/// Draws a red quad in the center of the screen before a green quad covering the whole screen behind it
+#[derive(Default)]
+struct OverlappingQuads {
+    program: Option<ShaderProgram<'static>>,
+    quads: Vec<RenderTarget<'static, DefaultVertex>>,
+}
+
+fn quad(extent: f32, depth: f32, color: (f32, f32, f32)) -> RenderTarget<'static, DefaultVertex> {
+    let vertices = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+        .map(|(x, y)| DefaultVertex::new((x * extent, y * extent, depth), color, (0.0, 0.0)));
+    RenderTarget::new(vertices, [0, 1, 2, 2, 3, 0])
+}
+
+impl System for OverlappingQuads {
+    fn awake(&mut self, _: &mut Context) {
+        let vertex = VertexShader::from_source(&CString::new(VERTEX).unwrap()).unwrap();
+        let fragment = FragmentShader::from_source(&CString::new(FRAGMENT).unwrap()).unwrap();
+        self.program = Some(ShaderProgram::new::<DefaultVertex>(vec![Box::new(vertex), Box::new(fragment)]).unwrap());
+
+        // The near quad is drawn first, without depth testing the far quad would cover it
+        self.quads = vec![quad(0.5, -0.5, (1.0, 0.0, 0.0)), quad(1.0, 0.5, (0.0, 1.0, 0.0))];
+    }
+
+    fn update(&mut self, _: &mut Context) {
+        if let Some(program) = &self.program {
+            program.bind();
+            for quad in &self.quads {
+                quad.draw();
+            }
+        }
+    }
+}
+
+// Golden tests need a headless OpenGL backend such as OSMesa, run them with `cargo test -- --ignored`
+
+#[test]
+#[ignore = "requires a headless OpenGL backend"]
+fn clear_color() {
+    GoldenTest::new("clear_color")
+        .size(64, 64)
+        .frames(2)
+        .tolerance(1)
+        .run(ClearColor)
+        .unwrap();
+}
+
+#[test]
+#[ignore = "requires a headless OpenGL backend"]
+fn depth_test() {
+    GoldenTest::new("depth_test")
+        .size(64, 64)
+        .tolerance(1)
+        .run(OverlappingQuads::default())
+        .unwrap();
+}
@@ -0,0 +1,124 @@
+extern crate nalgebra as na;
+
+use engine::input::{
+    ElementState, GamepadAxis, GamepadButton, GamepadId, Input, InputEvent, InputSource, MouseButton, ScriptedInput,
+    VirtualKeyCode,
+};
+
+fn key(key: VirtualKeyCode, state: ElementState) -> InputEvent {
+    InputEvent::Key { key, state }
+}
+
+#[test]
+fn key_states_last_one_frame() {
+    let mut input = Input::new();
+
+    input.handle_event(&key(VirtualKeyCode::W, ElementState::Pressed));
+    assert!(input.key_pressed(VirtualKeyCode::W));
+    assert!(input.key_held(VirtualKeyCode::W));
+
+    input.end_frame();
+    input.handle_event(&key(VirtualKeyCode::W, ElementState::Pressed));
+    assert!(!input.key_pressed(VirtualKeyCode::W));
+    assert!(input.key_held(VirtualKeyCode::W));
+
+    input.end_frame();
+    input.handle_event(&key(VirtualKeyCode::W, ElementState::Released));
+    assert!(input.key_released(VirtualKeyCode::W));
+    assert!(!input.key_held(VirtualKeyCode::W));
+
+    input.end_frame();
+    assert!(!input.key_released(VirtualKeyCode::W));
+}
+
+#[test]
+fn focus_loss_releases_everything() {
+    let mut input = Input::new();
+    input.handle_event(&key(VirtualKeyCode::Space, ElementState::Pressed));
+    input.handle_event(&InputEvent::MouseButton {
+        button: MouseButton::Left,
+        state: ElementState::Pressed,
+    });
+    input.end_frame();
+
+    input.handle_event(&InputEvent::Focused(false));
+    assert!(!input.focused());
+    assert!(input.key_released(VirtualKeyCode::Space));
+    assert!(input.mouse_released(MouseButton::Left));
+    assert!(!input.mouse_held(MouseButton::Left));
+}
+
+#[test]
+fn cursor_scroll_and_text() {
+    let mut input = Input::new();
+    input.handle_event(&InputEvent::CursorMoved {
+        position: na::Point2::new(10.0, 20.0),
+    });
+    input.handle_event(&InputEvent::MouseMotion {
+        delta: na::Vector2::new(1.0, 2.0),
+    });
+    input.handle_event(&InputEvent::MouseMotion {
+        delta: na::Vector2::new(3.0, -1.0),
+    });
+    input.handle_event(&InputEvent::Scroll {
+        delta: na::Vector2::new(0.0, 1.0),
+    });
+    input.handle_event(&InputEvent::Text('h'));
+    input.handle_event(&InputEvent::Text('i'));
+
+    assert_eq!(input.cursor_position(), Some(na::Point2::new(10.0, 20.0)));
+    assert_eq!(input.cursor_delta(), na::Vector2::new(4.0, 1.0));
+    assert_eq!(input.scroll_delta(), na::Vector2::new(0.0, 1.0));
+    assert_eq!(input.text(), "hi");
+
+    input.end_frame();
+    assert_eq!(input.cursor_position(), Some(na::Point2::new(10.0, 20.0)));
+    assert_eq!(input.cursor_delta(), na::Vector2::zeros());
+    assert_eq!(input.text(), "");
+
+    input.handle_event(&InputEvent::CursorLeft);
+    assert_eq!(input.cursor_position(), None);
+}
+
+#[test]
+fn gamepads() {
+    let mut input = Input::new();
+    let id = GamepadId(0);
+
+    input.handle_event(&InputEvent::GamepadConnected(id));
+    input.handle_event(&InputEvent::GamepadButton {
+        id,
+        button: GamepadButton::South,
+        state: ElementState::Pressed,
+    });
+    input.handle_event(&InputEvent::GamepadAxis {
+        id,
+        axis: GamepadAxis::LeftStickX,
+        value: -0.5,
+    });
+
+    let gamepad = input.gamepad(id).unwrap();
+    assert!(gamepad.pressed(GamepadButton::South));
+    assert_eq!(gamepad.axis(GamepadAxis::LeftStickX), -0.5);
+    assert_eq!(gamepad.axis(GamepadAxis::RightTrigger), 0.0);
+
+    input.end_frame();
+    assert!(!input.gamepad(id).unwrap().pressed(GamepadButton::South));
+    assert!(input.gamepad(id).unwrap().held(GamepadButton::South));
+
+    input.handle_event(&InputEvent::GamepadDisconnected(id));
+    assert!(input.gamepads().is_empty());
+}
+
+#[test]
+fn scripted_input_replays_by_frame() {
+    let mut source = ScriptedInput::new()
+        .at(0, key(VirtualKeyCode::A, ElementState::Pressed))
+        .at(2, key(VirtualKeyCode::A, ElementState::Released));
+
+    assert_eq!(source.poll(0), vec![key(VirtualKeyCode::A, ElementState::Pressed)]);
+    assert!(source.poll(1).is_empty());
+    assert!(!source.is_finished());
+    assert_eq!(source.poll(3), vec![key(VirtualKeyCode::A, ElementState::Released)]);
+    assert!(source.is_finished());
+}
@@ -0,0 +1,174 @@
+extern crate nalgebra as na;
+
+use engine::input::{
+    Actions, Axis2Binding, Axis2Mapping, AxisBinding, AxisMapping, Binding, ConfigFormat,
+    ElementState, GamepadAxis, GamepadId, Input, InputEvent, InputMap, MouseButton, VirtualKeyCode,
+};
+
+fn press(input: &mut Input, key: VirtualKeyCode) {
+    input.handle_event(&InputEvent::Key {
+        key,
+        state: ElementState::Pressed,
+    });
+}
+
+fn release(input: &mut Input, key: VirtualKeyCode) {
+    input.handle_event(&InputEvent::Key {
+        key,
+        state: ElementState::Released,
+    });
+}
+
+fn wasd() -> Axis2Binding {
+    Axis2Binding::Buttons {
+        up: Binding::Key(VirtualKeyCode::W),
+        down: Binding::Key(VirtualKeyCode::S),
+        left: Binding::Key(VirtualKeyCode::A),
+        right: Binding::Key(VirtualKeyCode::D),
+    }
+}
+
+fn map() -> InputMap {
+    let mut map = InputMap::new();
+    map.bind("jump", Binding::Key(VirtualKeyCode::Space));
+    map.bind("jump", Binding::Mouse(MouseButton::Left));
+    map.bind("jump", Binding::Mouse(MouseButton::Other(4)));
+    map.set_axis(
+        "move_x",
+        AxisMapping {
+            dead_zone: 0.2,
+            bindings: vec![
+                AxisBinding::Buttons {
+                    positive: Binding::Key(VirtualKeyCode::D),
+                    negative: Binding::Key(VirtualKeyCode::A),
+                },
+                AxisBinding::Gamepad(GamepadAxis::LeftStickX),
+            ],
+        },
+    );
+    map.set_axis_2d(
+        "move",
+        Axis2Mapping {
+            dead_zone: 0.2,
+            bindings: vec![
+                wasd(),
+                Axis2Binding::Gamepad {
+                    x: GamepadAxis::LeftStickX,
+                    y: GamepadAxis::LeftStickY,
+                },
+            ],
+        },
+    );
+    map
+}
+
+#[test]
+fn actions() {
+    let map = map();
+    let mut input = Input::new();
+
+    press(&mut input, VirtualKeyCode::Space);
+    let actions = Actions::new(&map, &input);
+    assert!(actions.pressed("jump"));
+    assert!(actions.held("jump"));
+    assert!(!actions.pressed("unknown"));
+
+    input.end_frame();
+    release(&mut input, VirtualKeyCode::Space);
+    assert!(Actions::new(&map, &input).released("jump"));
+}
+
+#[test]
+fn button_and_gamepad_axes() {
+    let map = map();
+    let mut input = Input::new();
+
+    press(&mut input, VirtualKeyCode::A);
+    assert_eq!(map.axis(&input, "move_x"), -1.0);
+
+    release(&mut input, VirtualKeyCode::A);
+    input.handle_event(&InputEvent::GamepadAxis {
+        id: GamepadId(0),
+        axis: GamepadAxis::LeftStickX,
+        value: 0.1,
+    });
+    assert_eq!(map.axis(&input, "move_x"), 0.0);
+
+    input.handle_event(&InputEvent::GamepadAxis {
+        id: GamepadId(0),
+        axis: GamepadAxis::LeftStickX,
+        value: 0.6,
+    });
+    assert!((map.axis(&input, "move_x") - 0.5).abs() < 1e-5);
+}
+
+#[test]
+fn composite_2d_axis() {
+    let map = map();
+    let mut input = Input::new();
+
+    press(&mut input, VirtualKeyCode::W);
+    assert_eq!(map.axis_2d(&input, "move"), na::Vector2::new(0.0, 1.0));
+
+    press(&mut input, VirtualKeyCode::D);
+    let diagonal = map.axis_2d(&input, "move");
+    assert!((diagonal.norm() - 1.0).abs() < 1e-5);
+    assert!(diagonal.x > 0.0 && diagonal.y > 0.0);
+}
+
+#[test]
+fn rebind_from_input() {
+    let mut map = map();
+    let mut input = Input::new();
+
+    assert!(!map.rebind_from_input("jump", &input));
+
+    press(&mut input, VirtualKeyCode::J);
+    assert!(map.rebind_from_input("jump", &input));
+    assert_eq!(map.bindings("jump"), &[Binding::Key(VirtualKeyCode::J)]);
+}
+
+#[test]
+fn config_round_trip() {
+    let map = map();
+
+    for format in [ConfigFormat::Ron, ConfigFormat::Toml] {
+        let contents = map.serialize(format).unwrap();
+        let parsed = InputMap::parse(&contents, format).unwrap();
+        assert_eq!(parsed, map, "{:?}:\n{}", format, contents);
+    }
+}
+
+#[test]
+fn parse_ron() {
+    let map = InputMap::parse(
+        r#"(
+            actions: {
+                "jump": ["Key(Space)", "Gamepad(South)"],
+            },
+            axes_2d: {
+                "move": (
+                    dead_zone: 0.1,
+                    bindings: ["Buttons(Key(W), Key(S), Key(A), Key(D))"],
+                ),
+            },
+        )"#,
+        ConfigFormat::Ron,
+    )
+    .unwrap();
+
+    assert_eq!(map.bindings("jump").len(), 2);
+    assert_eq!(map.axis_2d_mapping("move").unwrap().bindings, vec![wasd()]);
+}
+
+#[test]
+fn save_and_load() {
+    let path = std::env::temp_dir().join(format!("engine-input-map-{}.toml", std::process::id()));
+    map().save(path.clone()).unwrap();
+
+    let loaded = InputMap::load(path.clone()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.path(), Some(path.as_path()));
+    assert_eq!(loaded.bindings("jump"), map().bindings("jump"));
+}
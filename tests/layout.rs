@@ -0,0 +1,136 @@
+extern crate nalgebra as na;
+
+use engine::renderer::buffer::{BindingPoints, BlockLayout, Layout, LayoutEncoder};
+
+#[derive(BlockLayout)]
+struct Light {
+    position: na::Vector3<f32>,
+    intensity: f32,
+    color: na::Vector3<f32>,
+}
+
+#[derive(BlockLayout)]
+struct Lights {
+    count: u32,
+    lights: [Light; 2],
+    ambient: na::Vector2<f32>,
+    enabled: bool,
+}
+
+#[derive(BlockLayout)]
+struct Camera {
+    view: na::Matrix4<f32>,
+    scale: f32,
+    normal: na::Matrix3<f32>,
+}
+
+fn floats(data: &[u8], offset: usize, count: usize) -> Vec<f32> {
+    data[offset..offset + count * 4]
+        .chunks(4)
+        .map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect()
+}
+
+#[test]
+fn scalars_and_vectors() {
+    for layout in [Layout::Std140, Layout::Std430] {
+        assert_eq!(f32::alignment(layout), 4);
+        assert_eq!(na::Vector2::<f32>::alignment(layout), 8);
+        assert_eq!(na::Vector3::<f32>::alignment(layout), 16);
+        assert_eq!(na::Vector3::<f32>::size(layout), 12);
+    }
+
+    // A scalar is packed into the last component of a vec3
+    let data = LayoutEncoder::encode(
+        Layout::Std140,
+        &Light {
+            position: na::Vector3::new(1.0, 2.0, 3.0),
+            intensity: 4.0,
+            color: na::Vector3::new(5.0, 6.0, 7.0),
+        },
+    );
+    assert_eq!(data.len(), 32);
+    assert_eq!(floats(&data, 0, 8), vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 0.0]);
+}
+
+#[test]
+fn arrays() {
+    // std140 rounds the stride of every array up to 16 bytes
+    assert_eq!(<[f32; 3]>::size(Layout::Std140), 48);
+    assert_eq!(<[f32; 3]>::size(Layout::Std430), 12);
+    assert_eq!(<[na::Vector3<f32>; 2]>::size(Layout::Std430), 32);
+
+    let data = LayoutEncoder::encode(Layout::Std140, &[1.0f32, 2.0]);
+    assert_eq!(floats(&data, 0, 8), vec![1.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0]);
+
+    let data = LayoutEncoder::encode(Layout::Std430, &[1.0f32, 2.0][..]);
+    assert_eq!(floats(&data, 0, 2), vec![1.0, 2.0]);
+}
+
+#[test]
+fn matrices() {
+    // Columns are laid out like an array of vectors
+    assert_eq!(na::Matrix2::<f32>::size(Layout::Std140), 32);
+    assert_eq!(na::Matrix2::<f32>::size(Layout::Std430), 16);
+    assert_eq!(na::Matrix3::<f32>::size(Layout::Std430), 48);
+
+    let data = LayoutEncoder::encode(
+        Layout::Std140,
+        &na::Matrix3::new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0),
+    );
+    assert_eq!(
+        floats(&data, 0, 12),
+        vec![1.0, 4.0, 7.0, 0.0, 2.0, 5.0, 8.0, 0.0, 3.0, 6.0, 9.0, 0.0]
+    );
+}
+
+#[test]
+fn structs() {
+    assert_eq!(Light::alignment(Layout::Std140), 16);
+    assert_eq!(Light::size(Layout::Std140), 32);
+
+    // count at 0, lights at 16 and 48, ambient at 80, enabled at 88, padded to 96
+    assert_eq!(Lights::size(Layout::Std140), 96);
+    let data = LayoutEncoder::encode(
+        Layout::Std140,
+        &Lights {
+            count: 2,
+            lights: [
+                Light {
+                    position: na::Vector3::repeat(1.0),
+                    intensity: 0.5,
+                    color: na::Vector3::repeat(0.25),
+                },
+                Light {
+                    position: na::Vector3::repeat(2.0),
+                    intensity: 1.5,
+                    color: na::Vector3::repeat(0.75),
+                },
+            ],
+            ambient: na::Vector2::new(0.1, 0.2),
+            enabled: true,
+        },
+    );
+    assert_eq!(data.len(), 96);
+    assert_eq!(&data[0..4], &2u32.to_ne_bytes());
+    assert_eq!(floats(&data, 16, 4), vec![1.0, 1.0, 1.0, 0.5]);
+    assert_eq!(floats(&data, 48, 4), vec![2.0, 2.0, 2.0, 1.5]);
+    assert_eq!(floats(&data, 64, 3), vec![0.75, 0.75, 0.75]);
+    assert_eq!(floats(&data, 80, 2), vec![0.1, 0.2]);
+    assert_eq!(&data[88..92], &1u32.to_ne_bytes());
+
+    // view at 0, scale at 64, normal at 80
+    assert_eq!(Camera::size(Layout::Std140), 128);
+    assert_eq!(Camera::size(Layout::Std430), 128);
+}
+
+#[test]
+fn binding_points() {
+    let mut points = BindingPoints::new();
+    assert_eq!(points.assign("Camera"), 0);
+    assert_eq!(points.assign("Lights"), 1);
+    assert_eq!(points.assign("Camera"), 0);
+    assert_eq!(points.get("Lights"), Some(1));
+    assert_eq!(points.get("Material"), None);
+    assert_eq!(points.len(), 2);
+}
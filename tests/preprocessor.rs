@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use engine::{
+    assets::{Asset, AssetKind, AssetManager},
+    renderer::shader::{Defines, Preprocessor},
+};
+
+fn files(files: &[(&str, &str)]) -> HashMap<String, String> {
+    files
+        .iter()
+        .map(|(name, source)| (name.to_string(), source.to_string()))
+        .collect()
+}
+
+#[test]
+fn header() {
+    let mut sources = files(&[("basic.vert", "void main() {}")]);
+    let processed = Preprocessor::new()
+        .define("MAX_LIGHTS", "4")
+        .define("SHADOWS", "")
+        .process("basic.vert", &mut sources)
+        .unwrap();
+
+    assert_eq!(
+        processed.source(),
+        "#version 330 core\n#define MAX_LIGHTS 4\n#define SHADOWS\nvoid main() {}\n"
+    );
+    assert_eq!(processed.location(1), None);
+    assert_eq!(processed.location(4), Some(("basic.vert", 1)));
+}
+
+#[test]
+fn own_version_is_kept() {
+    let mut sources = files(&[("basic.frag", "#version 450\nout vec4 color;")]);
+    let processed = Preprocessor::new().process("basic.frag", &mut sources).unwrap();
+
+    assert_eq!(processed.source(), "#version 450\nout vec4 color;\n");
+    assert_eq!(processed.location(2), Some(("basic.frag", 2)));
+}
+
+#[test]
+fn includes() {
+    let mut sources = files(&[
+        (
+            "main.frag",
+            "#include \"lighting.glsl\"\n#include \"common.glsl\"\nvoid main() {}",
+        ),
+        (
+            "lighting.glsl",
+            "#include \"common.glsl\"\nvec3 light() { return vec3(PI); }",
+        ),
+        ("common.glsl", "#version 330\nconst float PI = 3.14;"),
+    ]);
+    let processed = Preprocessor::new().process("main.frag", &mut sources).unwrap();
+
+    // Every file is included once, versions of included files are dropped
+    assert_eq!(
+        processed.source(),
+        "#version 330 core\nconst float PI = 3.14;\nvec3 light() { return vec3(PI); }\nvoid main() {}\n"
+    );
+    assert_eq!(processed.files(), ["main.frag", "lighting.glsl", "common.glsl"]);
+    assert_eq!(processed.location(2), Some(("common.glsl", 2)));
+    assert_eq!(processed.location(3), Some(("lighting.glsl", 2)));
+    assert_eq!(processed.location(4), Some(("main.frag", 3)));
+    assert!(processed.depends_on("common.glsl"));
+    assert!(!processed.depends_on("other.glsl"));
+}
+
+#[test]
+fn include_errors() {
+    let mut sources = files(&[
+        ("a.glsl", "#include \"b.glsl\""),
+        ("b.glsl", "\n#include \"a.glsl\""),
+        ("missing.glsl", "#include \"nothing.glsl\""),
+    ]);
+
+    let error = Preprocessor::new().process("a.glsl", &mut sources).unwrap_err();
+    assert_eq!(error, "b.glsl:2: include cycle a.glsl -> b.glsl -> a.glsl");
+
+    let error = Preprocessor::new().process("missing.glsl", &mut sources).unwrap_err();
+    assert!(
+        error.starts_with("missing.glsl:1: failed to include \"nothing.glsl\""),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn asset_includes_are_relative_to_the_including_file() {
+    let root = std::env::temp_dir().join(format!("engine-includes-{}", std::process::id()));
+    let sources = [
+        (
+            "shaders/main.frag",
+            "#include \"lib/common.glsl\"\n#include \"common.glsl\"\nvoid main() {}",
+        ),
+        ("shaders/common.glsl", "float local;"),
+        ("shaders/lib/common.glsl", "#include \"../../util.glsl\"\nfloat shared;"),
+        ("util.glsl", "float util;"),
+    ];
+
+    let mut assets = AssetManager::new();
+    for (file, source) in sources {
+        let path = root.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, source).unwrap();
+        assets.create_asset(Asset::new(file.to_string(), path, AssetKind::Shader, "main", 0).unwrap());
+    }
+
+    let main = root.join("shaders/main.frag");
+    let processed = Preprocessor::new()
+        .process(main.to_str().unwrap(), &mut assets)
+        .unwrap();
+
+    // Files of the same name in different directories are different assets
+    assert_eq!(
+        processed.source(),
+        "#version 330 core\nfloat util;\nfloat shared;\nfloat local;\nvoid main() {}\n"
+    );
+    let key = |file: &str| AssetManager::key(root.join(file));
+    assert_eq!(
+        processed.files(),
+        [
+            key("shaders/main.frag"),
+            key("shaders/lib/common.glsl"),
+            key("util.glsl"),
+            key("shaders/common.glsl")
+        ]
+    );
+    assert!(processed.depends_on(&key("shaders/lib/common.glsl")));
+    assert!(!processed.depends_on("common.glsl"));
+    assert!(!processed.depends_on(&key("lib/common.glsl")));
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn error_lines() {
+    let mut sources = files(&[
+        ("main.frag", "#include \"common.glsl\"\nvoid main() { oops }"),
+        ("common.glsl", "float x;"),
+    ]);
+    let processed = Preprocessor::new()
+        .define("A", "1")
+        .process("main.frag", &mut sources)
+        .unwrap();
+
+    // Line 4 of the output is line 2 of main.frag
+    assert_eq!(
+        processed.map_log("0:4(15): error: syntax error"),
+        "main.frag:2(15): error: syntax error"
+    );
+    assert_eq!(
+        processed.map_log("ERROR: 0:3: 'x' : redefinition"),
+        "ERROR: common.glsl:1: 'x' : redefinition"
+    );
+    assert_eq!(
+        processed.map_log("0(4) : error C0000: syntax error"),
+        "main.frag:2 : error C0000: syntax error"
+    );
+    assert_eq!(processed.map_log("warning: unused 10:4"), "warning: unused 10:4");
+}
+
+#[test]
+fn variant_defines() {
+    let mut a = Defines::new();
+    a.insert("SHADOWS".to_string(), String::new());
+    a.insert("LIGHTS".to_string(), "2".to_string());
+
+    let mut b = Defines::new();
+    b.insert("LIGHTS".to_string(), "2".to_string());
+    b.insert("SHADOWS".to_string(), String::new());
+
+    // Defines are keyed independent of insertion order
+    assert_eq!(a, b);
+
+    let base = Preprocessor::new().define("LIGHTS", "1");
+    assert_eq!(base.with_defines(&a).defines(), &b);
+}
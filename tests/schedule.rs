@@ -0,0 +1,105 @@
+use engine::{Schedule, Stage, System, SystemDescriptor};
+
+struct Empty;
+
+impl System for Empty {}
+
+fn system(label: &str) -> SystemDescriptor {
+    SystemDescriptor::new(Empty).label(label)
+}
+
+#[test]
+fn stages() {
+    let schedule = Schedule::new(vec![
+        system("render").stage(Stage::Render),
+        Empty.into(),
+        system("startup").stage(Stage::Startup),
+    ])
+    .unwrap();
+
+    assert_eq!(schedule.labels(Stage::Startup), vec![Some("startup")]);
+    assert_eq!(schedule.labels(Stage::Update), vec![None]);
+    assert_eq!(schedule.labels(Stage::Render), vec![Some("render")]);
+    assert!(schedule.labels(Stage::LateUpdate).is_empty());
+}
+
+#[test]
+fn insertion_order_without_constraints() {
+    let schedule = Schedule::new(vec![system("a"), system("b"), system("c")]).unwrap();
+    assert_eq!(schedule.labels(Stage::Update), vec![Some("a"), Some("b"), Some("c")]);
+}
+
+#[test]
+fn before_and_after() {
+    let schedule = Schedule::new(vec![
+        system("render").after("physics"),
+        system("physics").after("input"),
+        system("input"),
+        system("audio").before("input"),
+    ])
+    .unwrap();
+
+    assert_eq!(
+        schedule.labels(Stage::Update),
+        vec![Some("audio"), Some("input"), Some("physics"), Some("render")]
+    );
+}
+
+#[test]
+fn shared_labels() {
+    let schedule = Schedule::new(vec![
+        system("late").after("group"),
+        system("group"),
+        system("group"),
+    ])
+    .unwrap();
+
+    assert_eq!(schedule.labels(Stage::Update), vec![Some("group"), Some("group"), Some("late")]);
+}
+
+#[test]
+fn constraints_across_stages_are_ignored() {
+    let schedule = Schedule::new(vec![
+        system("a").after("b"),
+        system("b").stage(Stage::PreUpdate).after("a"),
+    ])
+    .unwrap();
+
+    assert_eq!(schedule.labels(Stage::Update), vec![Some("a")]);
+}
+
+#[test]
+fn cycle() {
+    let error = Schedule::new(vec![system("a").after("b"), system("b").after("a"), system("c")])
+        .err()
+        .unwrap();
+
+    assert!(error.contains("a, b"), "{}", error);
+}
+
+#[test]
+fn disabled_unlabelled_system() {
+    let error = Schedule::new(vec![SystemDescriptor::new(Empty).enabled(false), Empty.into()])
+        .err()
+        .unwrap();
+    assert!(error.contains("could never be enabled"), "{}", error);
+
+    let schedule = Schedule::new(vec![system("a").enabled(false), Empty.into()]).unwrap();
+    assert_eq!(schedule.enabled(Stage::Update, schedule.systems()), vec![false, true]);
+}
+
+#[test]
+fn disabled_system_sharing_a_label() {
+    let schedule = Schedule::new(vec![system("group").enabled(false), system("group")]).unwrap();
+
+    // The label is enabled by the second system, the first keeps the state it started with
+    let mut systems = schedule.systems().clone();
+    assert!(systems.is_enabled("group"));
+    assert_eq!(schedule.enabled(Stage::Update, &systems), vec![false, true]);
+
+    systems.disable("group");
+    assert_eq!(schedule.enabled(Stage::Update, &systems), vec![false, false]);
+
+    systems.enable("group");
+    assert_eq!(schedule.enabled(Stage::Update, &systems), vec![true, true]);
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use engine::renderer::shader::{validate_stages, Preprocessor, ShaderFile, ShaderKind};
+
+const BASIC: &str = "#version 450
+uniform mat4 model;
+#pragma stage vertex
+void main() { gl_Position = model * vec4(0.0); }
+#pragma stage fragment
+out vec4 color;
+void main() { color = vec4(1.0); }";
+
+#[test]
+fn sections() {
+    let file = ShaderFile::parse("basic.shader", BASIC).unwrap();
+
+    assert_eq!(file.kinds(), vec![ShaderKind::VertexShader, ShaderKind::FragmentShader]);
+    assert_eq!(
+        file.stage(ShaderKind::VertexShader),
+        Some("#version 450\nuniform mat4 model;\n\nvoid main() { gl_Position = model * vec4(0.0); }\n\n\n\n")
+    );
+    assert_eq!(
+        file.stage(ShaderKind::FragmentShader),
+        Some("#version 450\nuniform mat4 model;\n\n\n\nout vec4 color;\nvoid main() { color = vec4(1.0); }\n")
+    );
+    assert_eq!(file.stage(ShaderKind::GeometryShader), None);
+}
+
+#[test]
+fn stage_names() {
+    let source =
+        "#pragma stage tess_control\n#pragma stage tess_evaluation\n#pragma stage geometry\n#pragma stage compute";
+    let file = ShaderFile::parse("all.shader", source).unwrap();
+
+    assert_eq!(
+        file.kinds(),
+        vec![
+            ShaderKind::TessControlShader,
+            ShaderKind::TessEvaluationShader,
+            ShaderKind::GeometryShader,
+            ShaderKind::ComputeShader,
+        ]
+    );
+}
+
+#[test]
+fn program_kinds_skip_compute() {
+    let source = "#pragma stage vertex\n#pragma stage fragment\n#pragma stage compute";
+    let file = ShaderFile::parse("mixed.shader", source).unwrap();
+
+    assert_eq!(file.program_kinds(), vec![ShaderKind::VertexShader, ShaderKind::FragmentShader]);
+    assert!(validate_stages(&file.program_kinds()).is_ok());
+    assert!(validate_stages(&file.kinds()).is_err());
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        ShaderFile::parse("bad.shader", "void main() {}"),
+        Err("bad.shader: no `#pragma stage <name>` sections".to_string())
+    );
+    assert_eq!(
+        ShaderFile::parse("bad.shader", "#pragma stage vertex\n#pragma stage pixel"),
+        Err("bad.shader:2: unknown shader stage \"pixel\"".to_string())
+    );
+    assert_eq!(
+        ShaderFile::parse("bad.shader", "#pragma stage vertex\n\n#pragma stage vertex"),
+        Err("bad.shader:3: stage \"vertex\" is declared more than once".to_string())
+    );
+    assert!(!ShaderFile::is_shader_file("#version 330 core\nvoid main() {}"));
+}
+
+#[test]
+fn preprocessed_lines() {
+    let mut sources = HashMap::new();
+    sources.insert(
+        "common.glsl".to_string(),
+        "float half(float x) { return x / 2.0; }".to_string(),
+    );
+
+    let source = "#include \"common.glsl\"\n#pragma stage vertex\nvoid main() {}\n#pragma stage fragment\nbroken";
+    let file = ShaderFile::parse("lit.shader", source).unwrap();
+    let fragment = file.stage(ShaderKind::FragmentShader).unwrap();
+    let processed = Preprocessor::new()
+        .process_source("lit.shader", fragment, &mut sources)
+        .unwrap();
+
+    // Lines of the fragment section keep their line in the file
+    assert_eq!(processed.map_log("0:6: syntax error"), "lit.shader:5: syntax error");
+    assert!(processed.depends_on("common.glsl"));
+}
@@ -0,0 +1,202 @@
+extern crate nalgebra as na;
+
+use std::ffi::CString;
+
+use engine::{
+    assets::{Asset, AssetKind},
+    renderer::{
+        buffer::BindingPoints,
+        render_target::vertex_array::DefaultVertex,
+        shader::{FragmentShader, ShaderError, ShaderKind, ShaderProgram, VertexShader},
+    },
+    App, Context, System,
+};
+
+fn shader_asset(name: &str, kind: ShaderKind, contents: &[u8]) -> Asset {
+    let path = std::env::temp_dir().join(format!("engine-{}-{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    let asset = Asset::new(name.to_string(), path.clone(), AssetKind::Shader, "basic", kind as u8).unwrap();
+    std::fs::remove_file(path).unwrap();
+    asset
+}
+
+#[test]
+fn invalid_source_is_an_error() {
+    let asset = shader_asset("broken.vert", ShaderKind::VertexShader, &[b'v', 0xff, 0xfe]);
+    assert!(asset.raw_to_cstr().unwrap_err().contains("not valid UTF-8"));
+
+    let asset = shader_asset("nul.vert", ShaderKind::VertexShader, b"void main() {}\0");
+    assert!(asset.raw_to_cstr().unwrap_err().contains("nul byte"));
+
+    let asset = shader_asset("basic.vert", ShaderKind::VertexShader, b"void main() {}");
+    assert_eq!(asset.raw_to_cstr().unwrap().as_bytes(), b"void main() {}");
+}
+
+#[test]
+fn errors_carry_logs() {
+    let error = ShaderError::Compile {
+        kind: ShaderKind::FragmentShader,
+        log: "lit.shader:5: syntax error".to_string(),
+    };
+    assert_eq!(
+        String::from(error),
+        "failed to compile FragmentShader:\nlit.shader:5: syntax error"
+    );
+
+    let error = ShaderError::Link("error: vertex output `uv` is not read".to_string());
+    assert_eq!(
+        error.to_string(),
+        "failed to link shader program:\nerror: vertex output `uv` is not read"
+    );
+}
+
+/// Runs a test on the OpenGL context of a headless app
+struct Headless<F>(Option<F>);
+
+impl<F: FnOnce() + 'static> System for Headless<F> {
+    fn awake(&mut self, _: &mut Context) {
+        if let Some(test) = self.0.take() {
+            test();
+        }
+    }
+}
+
+fn headless(test: impl FnOnce() + 'static) {
+    App::builder()
+        .name("shader_reload")
+        .headless(16, 16)
+        .frames(1)
+        .screenshot_key(None)
+        .add_system(Headless(Some(test)))
+        .build()
+        .try_run()
+        .unwrap();
+}
+
+const VERTEX: &str = "#version 330 core
+in vec3 Position;
+in vec3 Color;
+in vec2 TexCoords;
+void main() {
+    gl_Position = vec4(Position, 1.0);
+}";
+
+const FRAGMENT: &str = "#version 330 core
+uniform vec4 tint;
+layout(std140) uniform Lights {
+    vec4 ambient;
+};
+out vec4 FragColor;
+void main() {
+    FragColor = tint * ambient;
+}";
+
+/// The same inputs as [FRAGMENT], combined the other way around
+const CHANGED_FRAGMENT: &[u8] = b"#version 330 core
+uniform vec4 tint;
+layout(std140) uniform Lights {
+    vec4 ambient;
+};
+out vec4 FragColor;
+void main() {
+    FragColor = tint + ambient;
+}";
+
+fn program() -> ShaderProgram<'static> {
+    let vertex = VertexShader::from_source(&CString::new(VERTEX).unwrap()).unwrap();
+    let fragment = FragmentShader::from_source(&CString::new(FRAGMENT).unwrap()).unwrap();
+    ShaderProgram::new::<DefaultVertex>(vec![Box::new(vertex), Box::new(fragment)]).unwrap()
+}
+
+fn current_program() -> gl::types::GLuint {
+    let mut current = 0;
+    unsafe { gl::GetIntegerv(gl::CURRENT_PROGRAM, &mut current) };
+    current as gl::types::GLuint
+}
+
+fn is_program(id: gl::types::GLuint) -> bool {
+    unsafe { gl::IsProgram(id) == gl::TRUE }
+}
+
+// Reloading needs a headless OpenGL backend such as OSMesa, run these tests with `cargo test -- --ignored`
+
+#[test]
+#[ignore = "requires a headless OpenGL backend"]
+fn failed_reload_keeps_the_old_program() {
+    headless(|| {
+        let mut program = program();
+        program.bind();
+        let id = program.id();
+
+        let broken = shader_asset("broken.frag", ShaderKind::FragmentShader, b"void main() { oops }");
+        let error = program.reload(&broken).unwrap_err();
+        assert!(matches!(error, ShaderError::Compile { .. }), "{}", error);
+
+        assert_eq!(program.id(), id);
+        assert!(is_program(id));
+        assert_eq!(current_program(), id);
+        assert!(program.uniform("tint").is_some());
+    });
+}
+
+#[test]
+#[ignore = "requires a headless OpenGL backend"]
+fn reload_swaps_in_the_new_program() {
+    headless(|| {
+        let mut program = program();
+        program.bind();
+        let old = program.id();
+
+        let changed = shader_asset("changed.frag", ShaderKind::FragmentShader, CHANGED_FRAGMENT);
+        program.reload(&changed).unwrap();
+
+        // The new program stays bound in place of the old one, which is deleted
+        assert_ne!(program.id(), old);
+        assert!(is_program(program.id()));
+        assert!(!is_program(old));
+        assert_eq!(current_program(), program.id());
+    });
+}
+
+#[test]
+#[ignore = "requires a headless OpenGL backend"]
+fn reload_applies_uniforms_again() {
+    headless(|| {
+        let mut program = program();
+        let tint = na::Vector4::new(0.25f32, 0.5, 0.75, 1.0);
+        program.set_uniform("tint", &tint).unwrap();
+
+        let changed = shader_asset("uniforms.frag", ShaderKind::FragmentShader, CHANGED_FRAGMENT);
+        program.reload(&changed).unwrap();
+
+        let location = program.uniform("tint").unwrap().location;
+        let mut value = [0.0f32; 4];
+        unsafe { gl::GetUniformfv(program.id(), location, value.as_mut_ptr()) };
+        assert_eq!(value, [0.25, 0.5, 0.75, 1.0]);
+    });
+}
+
+#[test]
+#[ignore = "requires a headless OpenGL backend"]
+fn reload_binds_blocks_again() {
+    headless(|| {
+        let mut points = BindingPoints::new();
+        points.assign("Padding");
+        let point = points.assign("Lights");
+
+        let mut program = program();
+        program.bind_blocks(&points);
+
+        let changed = shader_asset("blocks.frag", ShaderKind::FragmentShader, CHANGED_FRAGMENT);
+        program.reload(&changed).unwrap();
+
+        let name = CString::new("Lights").unwrap();
+        let mut binding = 0;
+        unsafe {
+            let index = gl::GetUniformBlockIndex(program.id(), name.as_ptr());
+            gl::GetActiveUniformBlockiv(program.id(), index, gl::UNIFORM_BLOCK_BINDING, &mut binding);
+        }
+        assert_eq!(binding as gl::types::GLuint, point);
+        assert_ne!(point, 0);
+    });
+}
@@ -0,0 +1,40 @@
+use engine::renderer::{
+    render_target::PrimitiveTopology,
+    shader::{validate_stages, ShaderKind::*},
+};
+
+#[test]
+fn valid_combinations() {
+    assert!(validate_stages(&[VertexShader, FragmentShader]).is_ok());
+    assert!(validate_stages(&[VertexShader, GeometryShader, FragmentShader]).is_ok());
+    assert!(validate_stages(&[VertexShader, TessControlShader, TessEvaluationShader, FragmentShader]).is_ok());
+
+    // Tessellation levels default to the patch parameters without a control shader
+    assert!(validate_stages(&[VertexShader, TessEvaluationShader, GeometryShader, FragmentShader]).is_ok());
+}
+
+#[test]
+fn invalid_combinations() {
+    let error = validate_stages(&[FragmentShader]).unwrap_err();
+    assert!(error.contains("no VertexShader"), "{}", error);
+
+    let error = validate_stages(&[VertexShader, TessControlShader, FragmentShader]).unwrap_err();
+    assert!(error.contains("requires a TessEvaluationShader"), "{}", error);
+
+    let error = validate_stages(&[VertexShader, FragmentShader, FragmentShader]).unwrap_err();
+    assert!(error.contains("more than one FragmentShader"), "{}", error);
+
+    assert!(validate_stages(&[VertexShader, ComputeShader]).is_err());
+}
+
+#[test]
+fn patches() {
+    let topology = PrimitiveTopology::Patches(4);
+    assert_eq!(topology.get_opengl_enum(), gl::PATCHES);
+    assert_eq!(topology.patch_vertices(), Some(4));
+    assert_eq!(PrimitiveTopology::Triangles.patch_vertices(), None);
+
+    // Empty patches are rejected before touching OpenGL
+    assert!(PrimitiveTopology::Patches(0).apply().is_err());
+    assert!(PrimitiveTopology::Triangles.apply().is_ok());
+}
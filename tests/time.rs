@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use engine::time::{FrameRate, Time};
+
+fn millis(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn advance() {
+    let mut time = Time::default();
+    time.advance(millis(10));
+    time.advance(millis(20));
+
+    assert_eq!(time.delta(), millis(20));
+    assert_eq!(time.elapsed(), millis(30));
+    assert_eq!(time.frame_count(), 2);
+}
+
+#[test]
+fn smoothed_fps() {
+    let mut time = Time::default();
+    assert_eq!(time.fps(), 0.0);
+
+    time.advance(millis(10));
+    assert!((time.fps() - 100.0).abs() < 1e-3);
+
+    // A single slow frame only nudges the smoothed rate
+    time.advance(millis(100));
+    assert!(time.fps() > 50.0 && time.fps() < 100.0);
+}
+
+#[test]
+fn fixed_steps_accumulate() {
+    let mut time = Time::new(millis(10), 5);
+
+    time.advance(millis(4));
+    assert_eq!(time.fixed_steps(), 0);
+
+    time.advance(millis(7));
+    assert_eq!(time.fixed_steps(), 1);
+
+    time.advance(millis(25));
+    assert_eq!(time.fixed_steps(), 2);
+    assert!((time.alpha() - 0.6).abs() < 1e-3);
+}
+
+#[test]
+fn fixed_steps_clamped() {
+    let mut time = Time::new(millis(10), 3);
+
+    time.advance(millis(1005));
+    assert_eq!(time.fixed_steps(), 3);
+    assert!((time.alpha() - 0.5).abs() < 1e-3);
+
+    // The dropped time is not caught up on later frames
+    time.advance(millis(5));
+    assert_eq!(time.fixed_steps(), 1);
+}
+
+#[test]
+fn frame_rate() {
+    assert_eq!(FrameRate::Target(100).frame_time(), Some(millis(10)));
+    assert_eq!(FrameRate::Vsync.frame_time(), None);
+    assert_eq!(FrameRate::Unlimited.frame_time(), None);
+    assert!(FrameRate::Vsync.is_vsync());
+}
@@ -0,0 +1,118 @@
+extern crate nalgebra as na;
+
+use engine::ecs::{Entity, GlobalTransform, Transform, World};
+
+fn spawn(world: &mut World, x: f32) -> Entity {
+    let entity = world.spawn();
+    world.insert(entity, Transform::from_translation(na::Vector3::new(x, 0.0, 0.0))).unwrap();
+    entity
+}
+
+fn global_x(world: &World, entity: Entity) -> f32 {
+    world.get::<GlobalTransform>(entity).unwrap().translation().x
+}
+
+#[test]
+fn propagation() {
+    let mut world = World::new();
+    let root = spawn(&mut world, 1.0);
+    let child = spawn(&mut world, 2.0);
+    let grandchild = spawn(&mut world, 3.0);
+    world.set_parent(child, Some(root)).unwrap();
+    world.set_parent(grandchild, Some(child)).unwrap();
+
+    world.propagate_transforms();
+    assert_eq!(global_x(&world, root), 1.0);
+    assert_eq!(global_x(&world, child), 3.0);
+    assert_eq!(global_x(&world, grandchild), 6.0);
+    assert!(!world.get::<Transform>(grandchild).unwrap().is_dirty());
+}
+
+#[test]
+fn rotation_and_scale_are_inherited() {
+    let mut world = World::new();
+    let root = world.spawn();
+    let rotation = na::UnitQuaternion::from_axis_angle(&na::Vector3::y_axis(), std::f32::consts::FRAC_PI_2);
+    world
+        .insert(root, Transform::default().with_rotation(rotation).with_scale(na::Vector3::repeat(2.0)))
+        .unwrap();
+    let child = spawn(&mut world, 1.0);
+    world.set_parent(child, Some(root)).unwrap();
+
+    world.propagate_transforms();
+    let global = world.get::<GlobalTransform>(child).unwrap();
+    assert!((global.translation() - na::Vector3::new(0.0, 0.0, -2.0)).norm() < 1e-5);
+    assert!(global.rotation().angle_to(&rotation) < 1e-5);
+}
+
+#[test]
+fn only_dirty_subtrees_are_recomputed() {
+    let mut world = World::new();
+    let a = spawn(&mut world, 1.0);
+    let a_child = spawn(&mut world, 1.0);
+    let b = spawn(&mut world, 5.0);
+    let b_child = spawn(&mut world, 1.0);
+    world.set_parent(a_child, Some(a)).unwrap();
+    world.set_parent(b_child, Some(b)).unwrap();
+    world.propagate_transforms();
+
+    // Tamper with a clean global transform to observe that it is not recomputed
+    *world.get_mut::<GlobalTransform>(b_child).unwrap() = GlobalTransform::default();
+    world.get_mut::<Transform>(a).unwrap().translate(na::Vector3::new(1.0, 0.0, 0.0));
+    world.propagate_transforms();
+
+    assert_eq!(global_x(&world, a_child), 3.0);
+    assert_eq!(global_x(&world, b_child), 0.0);
+}
+
+#[test]
+fn reparenting() {
+    let mut world = World::new();
+    let a = spawn(&mut world, 1.0);
+    let b = spawn(&mut world, 10.0);
+    let child = spawn(&mut world, 1.0);
+
+    world.set_parent(child, Some(a)).unwrap();
+    world.propagate_transforms();
+    assert_eq!(global_x(&world, child), 2.0);
+
+    world.set_parent(child, Some(b)).unwrap();
+    assert!(world.children(a).is_empty());
+    assert_eq!(world.children(b), &[child]);
+    assert_eq!(world.parent(child), Some(b));
+
+    world.propagate_transforms();
+    assert_eq!(global_x(&world, child), 11.0);
+
+    world.set_parent(child, None).unwrap();
+    world.propagate_transforms();
+    assert_eq!(global_x(&world, child), 1.0);
+}
+
+#[test]
+fn cycles_are_rejected() {
+    let mut world = World::new();
+    let a = spawn(&mut world, 0.0);
+    let b = spawn(&mut world, 0.0);
+    world.set_parent(b, Some(a)).unwrap();
+
+    assert!(world.set_parent(a, Some(b)).is_err());
+    assert!(world.set_parent(a, Some(a)).is_err());
+    assert_eq!(world.parent(a), None);
+}
+
+#[test]
+fn despawn_recursive() {
+    let mut world = World::new();
+    let root = spawn(&mut world, 0.0);
+    let parent = spawn(&mut world, 0.0);
+    let child = spawn(&mut world, 0.0);
+    world.set_parent(parent, Some(root)).unwrap();
+    world.set_parent(child, Some(parent)).unwrap();
+
+    assert!(world.despawn_recursive(parent));
+    assert!(!world.is_alive(parent));
+    assert!(!world.is_alive(child));
+    assert!(world.children(root).is_empty());
+    assert_eq!(world.len(), 1);
+}
@@ -0,0 +1,73 @@
+extern crate nalgebra as na;
+
+use engine::{
+    color::prelude::RGBAColor,
+    renderer::shader::{is_compatible, TextureUnit, UniformData, UniformError, UniformType, UniformValue},
+};
+
+#[test]
+fn gl_types() {
+    assert_eq!(UniformType::from_gl(gl::FLOAT_VEC3), UniformType::Vec3);
+    assert_eq!(UniformType::from_gl(gl::FLOAT_MAT2x3), UniformType::Mat2x3);
+    assert_eq!(UniformType::from_gl(gl::SAMPLER_2D), UniformType::Sampler);
+    assert_eq!(UniformType::from_gl(gl::DOUBLE), UniformType::Other(gl::DOUBLE));
+}
+
+#[test]
+fn single_values() {
+    let value = na::Vector3::new(1.0f32, 2.0, 3.0);
+    assert_eq!(value.uniform_type(), UniformType::Vec3);
+    assert_eq!(value.count(), 1);
+    assert_eq!(value.data(), UniformData::Float(vec![1.0, 2.0, 3.0]));
+
+    assert_eq!(true.data(), UniformData::Int(vec![1]));
+    assert_eq!(TextureUnit(2).uniform_type(), UniformType::Sampler);
+    assert_eq!(TextureUnit(2).data(), UniformData::Int(vec![2]));
+
+    let color = RGBAColor::new(0.1f32, 0.2, 0.3, 1.0);
+    assert_eq!(color.uniform_type(), UniformType::Vec4);
+    assert_eq!(color.data(), UniformData::Float(vec![0.1, 0.2, 0.3, 1.0]));
+}
+
+#[test]
+fn matrices_are_column_major() {
+    let matrix = na::Matrix2::new(1.0f32, 2.0, 3.0, 4.0);
+    assert_eq!(matrix.data(), UniformData::Float(vec![1.0, 3.0, 2.0, 4.0]));
+
+    // GLSL mat2x3 has 2 columns and 3 rows
+    assert_eq!(na::Matrix3x2::<f32>::zeros().uniform_type(), UniformType::Mat2x3);
+}
+
+#[test]
+fn arrays() {
+    let lights = [na::Vector2::new(1.0f32, 2.0), na::Vector2::new(3.0, 4.0)];
+    assert_eq!(lights.uniform_type(), UniformType::Vec2);
+    assert_eq!(lights.count(), 2);
+    assert_eq!(lights.data(), UniformData::Float(vec![1.0, 2.0, 3.0, 4.0]));
+
+    let weights = vec![1u32, 2, 3];
+    assert_eq!(weights[..2].count(), 2);
+    assert_eq!(weights.data(), UniformData::UInt(vec![1, 2, 3]));
+}
+
+#[test]
+fn compatibility() {
+    assert!(is_compatible(UniformType::Mat4, UniformType::Mat4));
+    assert!(is_compatible(UniformType::Bool, UniformType::Int));
+    assert!(is_compatible(UniformType::Sampler, UniformType::Int));
+    assert!(!is_compatible(UniformType::Float, UniformType::Int));
+    assert!(!is_compatible(UniformType::Vec3, UniformType::Vec4));
+}
+
+#[test]
+fn errors() {
+    let error = UniformError::TypeMismatch {
+        name: "model".to_string(),
+        expected: UniformType::Mat4,
+        given: UniformType::Mat3,
+    };
+    assert_eq!(error.to_string(), "uniform \"model\" is Mat4 but Mat3 was given");
+
+    let message: String = UniformError::Unknown("time".to_string()).into();
+    assert_eq!(message, "shader program has no active uniform \"time\"");
+}
@@ -0,0 +1,57 @@
+extern crate nalgebra as na;
+
+use engine::renderer::render_target::vertex_array::{f16, PackedNormal, VertexField, VertexFieldKindConverter};
+
+fn converted<T: VertexFieldKindConverter>() -> (gl::types::GLenum, usize) {
+    (T::get_vertex_field_kind().get_opengl_enum(), T::get_component_count())
+}
+
+#[test]
+fn scalars() {
+    assert_eq!(converted::<i8>(), (gl::BYTE, 1));
+    assert_eq!(converted::<u8>(), (gl::UNSIGNED_BYTE, 1));
+    assert_eq!(converted::<i16>(), (gl::SHORT, 1));
+    assert_eq!(converted::<u16>(), (gl::UNSIGNED_SHORT, 1));
+    assert_eq!(converted::<i32>(), (gl::INT, 1));
+    assert_eq!(converted::<u32>(), (gl::UNSIGNED_INT, 1));
+    assert_eq!(converted::<f16>(), (gl::HALF_FLOAT, 1));
+    assert_eq!(converted::<f32>(), (gl::FLOAT, 1));
+    assert_eq!(converted::<f64>(), (gl::DOUBLE, 1));
+}
+
+#[test]
+fn arrays_and_vectors() {
+    assert_eq!(converted::<[f32; 3]>(), (gl::FLOAT, 3));
+    assert_eq!(converted::<[[u8; 2]; 2]>(), (gl::UNSIGNED_BYTE, 4));
+    assert_eq!(converted::<na::Vector2<f16>>(), (gl::HALF_FLOAT, 2));
+    assert_eq!(converted::<na::Vector4<i32>>(), (gl::INT, 4));
+    assert_eq!(converted::<na::Point3<f32>>(), (gl::FLOAT, 3));
+    assert_eq!(converted::<PackedNormal>(), (gl::INT_2_10_10_10_REV, 4));
+
+    let field = VertexField::new::<[f32; 2]>("uv", 3);
+    assert_eq!((field.count(), field.size()), (6, 24));
+}
+
+#[test]
+fn packed_normal() {
+    // 10 bit components hold -511..511, the 2 bit w component -1..1, negative values are two's complement
+    assert_eq!(PackedNormal::new(0.0, 0.0, 0.0, 0.0), PackedNormal(0));
+    assert_eq!(PackedNormal::new(1.0, 0.0, 0.0, 0.0), PackedNormal(511));
+    assert_eq!(PackedNormal::new(-1.0, 0.0, 0.0, 0.0), PackedNormal(0b10_0000_0001));
+    assert_eq!(
+        PackedNormal::new(0.0, 1.0, -1.0, 0.0),
+        PackedNormal(511 << 10 | 0b10_0000_0001 << 20)
+    );
+    assert_eq!(PackedNormal::new(0.0, 0.0, 0.0, 1.0), PackedNormal(1 << 30));
+    assert_eq!(PackedNormal::new(0.0, 0.0, 0.0, -1.0), PackedNormal(0b11 << 30));
+
+    // Values outside of -1..1 are clamped
+    assert_eq!(
+        PackedNormal::new(2.0, -3.0, 0.0, 0.0),
+        PackedNormal::new(1.0, -1.0, 0.0, 0.0)
+    );
+    assert_eq!(
+        PackedNormal::from_normal(na::Vector3::new(0.0, 0.0, 1.0)),
+        PackedNormal::new(0.0, 0.0, 1.0, 0.0)
+    );
+}
@@ -0,0 +1,129 @@
+extern crate nalgebra as na;
+
+use engine::renderer::render_target::vertex_array::{f16, PackedNormal, Vertex, VertexDefiner, VertexField};
+use memoffset::offset_of;
+
+#[repr(C)]
+#[derive(Clone, Copy, Vertex)]
+struct Mixed {
+    position: [f32; 3],
+    #[vertex(name = "Color", normalized)]
+    color: [u8; 4],
+    id: u16,
+    // Aligned to 8 bytes, padding is inserted before it
+    weight: f64,
+    normal: PackedNormal,
+    uv: na::Vector2<f16>,
+}
+
+fn fields(definer: &VertexDefiner) -> Vec<(String, Option<usize>, usize, bool)> {
+    definer
+        .fields()
+        .map(|field| {
+            (
+                field.name().to_string(),
+                field.offset(),
+                field.count(),
+                field.is_normalized(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn offsets_match_layout() {
+    let definer = Mixed::get_definition();
+
+    assert_eq!(
+        fields(&definer),
+        vec![
+            ("position".to_string(), Some(offset_of!(Mixed, position)), 3, false),
+            ("Color".to_string(), Some(offset_of!(Mixed, color)), 4, true),
+            ("id".to_string(), Some(offset_of!(Mixed, id)), 1, false),
+            ("weight".to_string(), Some(offset_of!(Mixed, weight)), 1, false),
+            ("normal".to_string(), Some(offset_of!(Mixed, normal)), 4, false),
+            ("uv".to_string(), Some(offset_of!(Mixed, uv)), 2, false),
+        ]
+    );
+    assert_eq!(offset_of!(Mixed, weight), 24);
+    assert_eq!(definer.stride(), std::mem::size_of::<Mixed>());
+}
+
+#[test]
+fn kinds() {
+    let definer = Mixed::get_definition();
+    let kinds: Vec<_> = definer.fields().map(|field| field.kind().get_opengl_enum()).collect();
+
+    assert_eq!(
+        kinds,
+        vec![
+            gl::FLOAT,
+            gl::UNSIGNED_BYTE,
+            gl::UNSIGNED_SHORT,
+            gl::DOUBLE,
+            gl::INT_2_10_10_10_REV,
+            gl::HALF_FLOAT,
+        ]
+    );
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Vertex)]
+struct Instance {
+    model: [f32; 16],
+    normal_matrix: [[f32; 3]; 3],
+    tint: [f32; 4],
+}
+
+#[test]
+fn matrices_occupy_a_location_per_column() {
+    let definer = VertexDefiner::instanced::<Mixed, Instance>();
+
+    let columns: Vec<_> = definer
+        .fields()
+        .skip(6)
+        .map(|field| (field.column_count(), field.locations()))
+        .collect();
+    assert_eq!(columns, vec![(4, 4), (3, 3), (4, 1)]);
+
+    let locations: Vec<_> = definer.field_locations().map(|(location, _)| location).collect();
+    assert_eq!(locations, vec![0, 1, 2, 3, 4, 5, 6, 10, 13]);
+    assert_eq!(definer.binding_base_index(1), 6);
+
+    // Fields which can not be split evenly end with a smaller column
+    let odd = VertexField::new::<f32>("odd", 5);
+    assert_eq!((odd.column_count(), odd.locations()), (4, 2));
+}
+
+#[test]
+fn matrices_have_the_shape_of_their_type() {
+    // nalgebra matrices are named rows x columns, GLSL matrices columns x rows
+    let shape = |field: VertexField| (field.column_count(), field.locations());
+    assert_eq!(shape(VertexField::from_type::<na::Matrix3x2<f32>>("mat2x3")), (3, 2));
+    assert_eq!(shape(VertexField::from_type::<na::Matrix2x3<f32>>("mat3x2")), (2, 3));
+    assert_eq!(shape(VertexField::from_type::<na::Matrix3x4<f32>>("mat4x3")), (3, 4));
+    assert_eq!(shape(VertexField::from_type::<na::Matrix4x3<f32>>("mat3x4")), (4, 3));
+    assert_eq!(shape(VertexField::from_type::<[[f32; 2]; 3]>("mat3x2")), (2, 3));
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Vertex)]
+struct Precise {
+    position: [f64; 3],
+    weight: f64,
+    uv: [f64; 2],
+    color: [f64; 4],
+    transform: na::Matrix4<f64>,
+    id: i32,
+}
+
+#[test]
+fn wide_doubles_occupy_two_locations() {
+    let definer = Precise::get_definition();
+
+    let locations: Vec<_> = definer.fields().map(|field| field.locations()).collect();
+    assert_eq!(locations, vec![2, 1, 1, 2, 8, 1]);
+
+    let locations: Vec<_> = definer.field_locations().map(|(location, _)| location).collect();
+    assert_eq!(locations, vec![0, 2, 3, 4, 6, 14]);
+}